@@ -51,6 +51,95 @@ export async function getMXEPublicKeyWithRetry(
   );
 }
 
+export const DEFAULT_CIRCUIT_BASE_URL =
+  "https://sapphire-literary-rat-567.mypinata.cloud/ipfs/bafybeib4prz6zcn3rhgc73sh3ogy7u4ccjhzy7cqe2ojlrccgciaa3s4ky/";
+
+export type ConfigParams = {
+  admins: PublicKey[];
+  allowedMints: PublicKey[];
+  circuitBaseUrl: string;
+  checkAdmins: boolean;
+  checkMints: boolean;
+  offchainCircuits: boolean;
+  protocolFeeBps: number;
+  creatorFeeBps: number;
+  lpFeeBps: number;
+};
+
+export type FeeBucket = { protocol: {} } | { creator: {} } | { lp: {} };
+
+export function getConfigAddress(programId: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync([Buffer.from("config")], programId)[0];
+}
+
+export function getUserAccountAddress(programId: PublicKey, mint: PublicKey, owner: PublicKey): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [Buffer.from("user_account"), mint.toBuffer(), owner.toBuffer()],
+    programId
+  )[0];
+}
+
+// Must be sent by the program's upgrade authority
+export async function initializeConfig(
+  program: Program<ArxPredict>,
+  payer: anchor.web3.Keypair,
+  params: ConfigParams,
+) {
+  const programData = PublicKey.findProgramAddressSync(
+    [program.programId.toBuffer()],
+    new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+  )[0];
+  return await program.methods
+    .initializeConfig(params)
+    .accountsPartial({
+      payer: payer.publicKey,
+      programData,
+    })
+    .signers([payer])
+    .rpc({ commitment: "confirmed" });
+}
+
+export async function updateConfig(
+  program: Program<ArxPredict>,
+  authority: anchor.web3.Keypair,
+  params: ConfigParams,
+) {
+  return await program.methods
+    .updateConfig(params)
+    .accountsPartial({
+      authority: authority.publicKey,
+    })
+    .signers([authority])
+    .rpc({ commitment: "confirmed" });
+}
+
+export async function transferConfigAuthority(
+  program: Program<ArxPredict>,
+  authority: anchor.web3.Keypair,
+  newAuthority: PublicKey | null,
+) {
+  return await program.methods
+    .transferConfigAuthority(newAuthority)
+    .accountsPartial({
+      authority: authority.publicKey,
+    })
+    .signers([authority])
+    .rpc({ commitment: "confirmed" });
+}
+
+export async function acceptConfigAuthority(
+  program: Program<ArxPredict>,
+  newAuthority: anchor.web3.Keypair,
+) {
+  return await program.methods
+    .acceptConfigAuthority()
+    .accountsPartial({
+      newAuthority: newAuthority.publicKey,
+    })
+    .signers([newAuthority])
+    .rpc({ commitment: "confirmed" });
+}
+
 export async function createUserPosition(
   provider: anchor.AnchorProvider,
   program: Program<ArxPredict>,
@@ -142,6 +231,14 @@ export async function getProbs(
   return null;
 }
 
+// reveal_probs exposes the probs only, at most once a minute and without a cranker reward. Settlement reveals the vote totals
+export const DEFAULT_REVEAL_CONFIG = {
+  policy: { probsOnly: {} },
+  minInterval: new anchor.BN(60),
+  reward: new anchor.BN(0),
+  hideVotes: false,
+};
+
 export async function createMarket(
   provider: anchor.AnchorProvider,
   program: Program<ArxPredict>,
@@ -150,7 +247,14 @@ export async function createMarket(
   question: string,
   options: string[],
   liquidity_parameter: number,
-  mint: PublicKey
+  mint: PublicKey,
+  tradingEndsAt: number,
+  resolveBy: number,
+  batchEpoch: number = 0, // seconds per batch, zero trades continuously
+  resolutionSource: any = { authority: {} },
+  resolvers: PublicKey[] = [],
+  scalar: { lowerBound: anchor.BN, upperBound: anchor.BN } | null = null,
+  reveal: any = DEFAULT_REVEAL_CONFIG
 ) {
   const nonce = randomBytes(16);
   const pollComputationOffset = new anchor.BN(randomBytes(8), "hex");
@@ -161,7 +265,14 @@ export async function createMarket(
       question,
       options,
       new anchor.BN(liquidity_parameter),
-      new anchor.BN(deserializeLE(nonce).toString())
+      new anchor.BN(deserializeLE(nonce).toString()),
+      new anchor.BN(batchEpoch),
+      new anchor.BN(tradingEndsAt),
+      new anchor.BN(resolveBy),
+      resolutionSource,
+      resolvers,
+      scalar,
+      reveal
     )
     .accountsPartial({
       computationAccount: getComputationAccAddress(
@@ -200,23 +311,22 @@ export async function fundAndCreateMarket(
   mint: PublicKey,
   owner: anchor.web3.Keypair,
   ata: anchor.web3.PublicKey,
+  tradingEndsAt: number,
+  resolveBy: number,
+  batchEpoch: number = 0, // seconds per batch, zero trades continuously
+  resolutionSource: any = { authority: {} },
+  resolvers: PublicKey[] = [],
+  scalar: { lowerBound: anchor.BN, upperBound: anchor.BN } | null = null,
+  reveal: any = DEFAULT_REVEAL_CONFIG
 ) {
 
   const fundingAmount = liquidityParameter * Math.log(options.length) * 1e6;
   console.log(`Funding market ${marketId}, amount: ${fundingAmount/1e6} USDC`);
 
-  // First fund the market
-  const fundMarketSig = await program.methods
-    .fundMarket(marketId, new anchor.BN(fundingAmount))
-    .accountsPartial({
-      payer: owner.publicKey,
-      ata: ata,
-      mint: mint,
-    })
-    .signers([owner])
-    .rpc({ commitment: "confirmed" });
+  // First deposit the funding, create_market takes it from the creator's account
+  const depositSig = await deposit(program, owner, ata, mint, fundingAmount);
 
-  console.log(`Market ${marketId} funded with signature: ${fundMarketSig}`);
+  console.log(`Market ${marketId} funding deposited with signature: ${depositSig}`);
 
   // Then create the market
   const nonce = randomBytes(16);
@@ -228,7 +338,14 @@ export async function fundAndCreateMarket(
       question,
       options,
       new anchor.BN(liquidityParameter),
-      new anchor.BN(deserializeLE(nonce).toString())
+      new anchor.BN(deserializeLE(nonce).toString()),
+      new anchor.BN(batchEpoch),
+      new anchor.BN(tradingEndsAt),
+      new anchor.BN(resolveBy),
+      resolutionSource,
+      resolvers,
+      scalar,
+      reveal
     )
     .accountsPartial({
       computationAccount: getComputationAccAddress(
@@ -260,22 +377,22 @@ export async function fundAndCreateMarket(
   
   console.log(`Market ${marketId} computation finalized with signature: ${finalizePollSig}`);
   return { 
-    fundMarketSig, 
+    depositSig, 
     createMarketSig, 
     finalizeSig: finalizePollSig 
   };
 }
 
-export async function sendPayment(
+// Credits the owner's account for `mint`, one deposit funds trades in every market using that mint
+export async function deposit(
     program: Program<ArxPredict>,
     owner: anchor.web3.Keypair,
     ata: anchor.web3.PublicKey,
     mint: anchor.web3.PublicKey,
-    marketId: number,
     amount: number
   ) {
     const sig = await program.methods
-      .sendPayment(marketId, new anchor.BN(amount))
+      .deposit(new anchor.BN(amount))
       .accountsPartial({
         payer: owner.publicKey,
         ata: ata,
@@ -286,56 +403,78 @@ export async function sendPayment(
     return sig;
 }
 
-export async function fundMarket(
+export async function withdraw(
   program: Program<ArxPredict>,
   owner: anchor.web3.Keypair,
   ata: anchor.web3.PublicKey,
   mint: anchor.web3.PublicKey,
-  marketId: number,
   amount: number
 ) {
   const sig = await program.methods
-    .fundMarket(marketId, new anchor.BN(amount))
+    .withdraw(new anchor.BN(amount))
     .accountsPartial({
       payer: owner.publicKey,
       ata: ata,
       mint: mint,
     })
+    .signers([owner])
     .rpc({ commitment: "confirmed" });
   return sig;
 }
 
-export async function withdrawPayment(
+export async function settleMarket(
+  provider: anchor.AnchorProvider,
   program: Program<ArxPredict>,
-  owner: anchor.web3.Keypair,
-  ata: anchor.web3.PublicKey,
-  mint: anchor.web3.PublicKey,
+  winner: number,
   marketId: number,
-  amount: number
+  arciumClusterPubkey: PublicKey,
+  eventPromise: any,
+  priceFeed?: PublicKey
 ) {
-  const sig = await program.methods
-    .withdrawPayment(marketId, new anchor.BN(amount))
+  const revealComputationOffset = new anchor.BN(randomBytes(8), "hex");
+  const revealQueueSig = await program.methods
+    .settleMarket(revealComputationOffset, marketId, winner)
     .accountsPartial({
-      payer: owner.publicKey,
-      ata: ata,
-      mint: mint,
+      computationAccount: getComputationAccAddress(
+        program.programId,
+        revealComputationOffset
+      ),
+      clusterAccount: arciumClusterPubkey,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(program.programId),
+      executingPool: getExecutingPoolAccAddress(program.programId),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset("reveal_market")).readUInt32LE()
+      ),
+      priceFeed: priceFeed ?? null,
     })
-    .signers([owner])
     .rpc({ commitment: "confirmed" });
-  return sig;
+
+  const revealFinalizeSig = await awaitComputationFinalization(
+    provider as anchor.AnchorProvider,
+    revealComputationOffset,
+    program.programId,
+    "confirmed"
+  );
+
+  const settleMarketEvent = await eventPromise;
+  return settleMarketEvent;
 }
 
-export async function settleMarket(
+// `value` is ignored for price feed markets, the feed's price is used instead
+export async function settleScalarMarket(
   provider: anchor.AnchorProvider,
   program: Program<ArxPredict>,
-  winner: number,
+  value: number,
   marketId: number,
   arciumClusterPubkey: PublicKey,
-  eventPromise: any
+  eventPromise: any,
+  priceFeed?: PublicKey
 ) {
   const revealComputationOffset = new anchor.BN(randomBytes(8), "hex");
   const revealQueueSig = await program.methods
-    .settleMarket(revealComputationOffset, marketId, winner)
+    .settleScalarMarket(revealComputationOffset, marketId, new anchor.BN(value))
     .accountsPartial({
       computationAccount: getComputationAccAddress(
         program.programId,
@@ -349,6 +488,7 @@ export async function settleMarket(
         program.programId,
         Buffer.from(getCompDefAccOffset("reveal_market")).readUInt32LE()
       ),
+      priceFeed: priceFeed ?? null,
     })
     .rpc({ commitment: "confirmed" });
 
@@ -374,6 +514,7 @@ export async function buyShares(
   marketId: number,
   vote: number,
   shares: number,
+  maxCost: number,
   buySharesEventPromise: any
 ) {
   const nonce = randomBytes(16);
@@ -388,7 +529,8 @@ export async function buyShares(
       Array.from(ciphertext[0]),
       Array.from(mpcPublicKey),
       new anchor.BN(deserializeLE(nonce).toString()),
-      new anchor.BN(shares)
+      new anchor.BN(shares),
+      new anchor.BN(maxCost)
     )
     .accountsPartial({
       computationAccount: getComputationAccAddress(
@@ -422,6 +564,62 @@ export async function buyShares(
   return buySharesEvent;
 }
 
+export async function buyWithAmount(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  arciumClusterPubkey: PublicKey,
+  cipher: RescueCipher,
+  mpcPublicKey: Uint8Array,
+  owner: anchor.web3.Keypair,
+  marketId: number,
+  vote: number,
+  amount: number,
+  buySharesEventPromise: any
+) {
+  const nonce = randomBytes(16);
+  const voteBigInt = BigInt(vote);
+  const plaintext = [voteBigInt];
+  const ciphertext = cipher.encrypt(plaintext, nonce);
+  const computationOffset = new anchor.BN(randomBytes(8), "hex");
+  const queueSig = await program.methods
+    .buyWithAmount(
+      computationOffset,
+      marketId,
+      Array.from(ciphertext[0]),
+      Array.from(mpcPublicKey),
+      new anchor.BN(deserializeLE(nonce).toString()),
+      new anchor.BN(amount)
+    )
+    .accountsPartial({
+      computationAccount: getComputationAccAddress(
+        program.programId,
+        computationOffset
+      ),
+      clusterAccount: arciumClusterPubkey,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(program.programId),
+      executingPool: getExecutingPoolAccAddress(program.programId),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset("buy_with_amount")).readUInt32LE()
+      ),
+      payer: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+
+  const finalizeSig = await awaitComputationFinalization(
+    provider as anchor.AnchorProvider,
+    computationOffset,
+    program.programId,
+    "confirmed"
+  );
+
+  const buySharesEvent = await buySharesEventPromise;
+  console.log(`Buy with amount event=> status: ${buySharesEvent.status}, amount: ${buySharesEvent.amount / 1e6}, tvl: ${buySharesEvent.tvl / 1e6} USDC`);
+  return buySharesEvent;
+}
+
 export async function sellShares(
   provider: anchor.AnchorProvider,
   program: Program<ArxPredict>,
@@ -432,6 +630,7 @@ export async function sellShares(
   marketId: number,
   vote: number,
   shares: number,
+  minProceeds: number,
   sellSharesEventPromise: any
 ) {
   const nonce = randomBytes(16);
@@ -446,7 +645,8 @@ export async function sellShares(
       Array.from(ciphertext[0]),
       Array.from(mpcPublicKey),
       new anchor.BN(deserializeLE(nonce).toString()),
-      new anchor.BN(shares)
+      new anchor.BN(shares),
+      new anchor.BN(minProceeds)
     )
     .accountsPartial({
       computationAccount: getComputationAccAddress(
@@ -478,24 +678,37 @@ export async function sellShares(
   return finalizeSig;
 }
 
-export async function claimRewards(
+// Option and share count are encrypted together, only the payment is revealed
+// Prices buying and selling `shares` of `option` against the current market, fees included. The outcome and size
+// stay encrypted and the quote is only readable with `cipher`
+export async function quoteTrade(
   provider: anchor.AnchorProvider,
   program: Program<ArxPredict>,
   arciumClusterPubkey: PublicKey,
+  cipher: RescueCipher,
+  ownerPublicKey: Uint8Array,
   owner: anchor.web3.Keypair,
   marketId: number,
-  claimRewardsEventPromise: any
+  option: number,
+  shares: number,
+  tradeQuoteEventPromise: any
 ) {
-  const claimRewardsComputationOffset = new anchor.BN(randomBytes(8), "hex");
-  const queueClaimRewardsSig = await program.methods
-    .claimRewards(
-      claimRewardsComputationOffset,
-      marketId
+  const nonce = randomBytes(16);
+  const ciphertext = cipher.encrypt([BigInt(option), BigInt(shares)], nonce);
+  const computationOffset = new anchor.BN(randomBytes(8), "hex");
+  await program.methods
+    .quoteTrade(
+      computationOffset,
+      marketId,
+      Array.from(ciphertext[0]),
+      Array.from(ciphertext[1]),
+      Array.from(ownerPublicKey),
+      new anchor.BN(deserializeLE(nonce).toString())
     )
     .accountsPartial({
       computationAccount: getComputationAccAddress(
         program.programId,
-        claimRewardsComputationOffset
+        computationOffset
       ),
       clusterAccount: arciumClusterPubkey,
       mxeAccount: getMXEAccAddress(program.programId),
@@ -503,58 +716,1064 @@ export async function claimRewards(
       executingPool: getExecutingPoolAccAddress(program.programId),
       compDefAccount: getCompDefAccAddress(
         program.programId,
-        Buffer.from(getCompDefAccOffset("claim_rewards")).readUInt32LE()
+        Buffer.from(getCompDefAccOffset("quote_trade")).readUInt32LE()
       ),
-      //authority: owner,
       payer: owner.publicKey,
     })
     .signers([owner])
     .rpc({ commitment: "confirmed" });
 
-  const finalizeSig = await awaitComputationFinalization(
+  await awaitComputationFinalization(
     provider as anchor.AnchorProvider,
-    claimRewardsComputationOffset,
+    computationOffset,
     program.programId,
     "confirmed"
   );
 
-  const claimRewardsEvent = await claimRewardsEventPromise;
-  const claimRewardsAmountUsdc = claimRewardsEvent.amount / 1e6;
-  console.log(`Claim rewards event=> amount: ${claimRewardsAmountUsdc}`);
-  return claimRewardsEvent;
+  const tradeQuoteEvent = await tradeQuoteEventPromise;
+  const [buyCost, sellProceeds] = cipher.decrypt(
+    [tradeQuoteEvent.buyCost, tradeQuoteEvent.sellProceeds],
+    new anchor.BN(tradeQuoteEvent.nonce).toArrayLike(Buffer, "le", 16)
+  );
+  return {
+    marketVersion: tradeQuoteEvent.marketVersion.toNumber(),
+    buyCost,
+    sellProceeds,
+  };
 }
 
+export async function buySharesPrivate(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  arciumClusterPubkey: PublicKey,
+  cipher: RescueCipher,
+  mpcPublicKey: Uint8Array,
+  owner: anchor.web3.Keypair,
+  marketId: number,
+  vote: number,
+  shares: number,
+  maxCost: number,
+  buySharesEventPromise: any
+) {
+  const nonce = randomBytes(16);
+  const plaintext = [BigInt(vote), BigInt(shares)];
+  const ciphertext = cipher.encrypt(plaintext, nonce);
+  const computationOffset = new anchor.BN(randomBytes(8), "hex");
+  const queueSig = await program.methods
+    .buySharesPrivate(
+      computationOffset,
+      marketId,
+      Array.from(ciphertext[0]),
+      Array.from(ciphertext[1]),
+      Array.from(mpcPublicKey),
+      new anchor.BN(deserializeLE(nonce).toString()),
+      new anchor.BN(maxCost)
+    )
+    .accountsPartial({
+      computationAccount: getComputationAccAddress(
+        program.programId,
+        computationOffset
+      ),
+      clusterAccount: arciumClusterPubkey,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(program.programId),
+      executingPool: getExecutingPoolAccAddress(program.programId),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset("buy_shares_private")).readUInt32LE()
+      ),
+      payer: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+
+  const finalizeSig = await awaitComputationFinalization(
+    provider as anchor.AnchorProvider,
+    computationOffset,
+    program.programId,
+    "confirmed"
+  );
+
+  const event = await buySharesEventPromise;
+  console.log(`Private buy shares event=> status: ${event.status}, amount: ${event.amount / 1e6}, tvl: ${event.tvl / 1e6} USDC`);
+  return event;
+}
 
-export async function claimMarketFunds(
+// Option and share count are encrypted together, only the payment is revealed
+export async function sellSharesPrivate(
+  provider: anchor.AnchorProvider,
   program: Program<ArxPredict>,
+  arciumClusterPubkey: PublicKey,
+  cipher: RescueCipher,
+  mpcPublicKey: Uint8Array,
   owner: anchor.web3.Keypair,
-  ata: anchor.web3.PublicKey,
-  mint: anchor.web3.PublicKey,
   marketId: number,
-  claimMarketFundsEventPromise: any
+  vote: number,
+  shares: number,
+  minProceeds: number,
+  sellSharesEventPromise: any
 ) {
-  const sig = await program.methods
-    .claimMarketFunds(marketId)
+  const nonce = randomBytes(16);
+  const plaintext = [BigInt(vote), BigInt(shares)];
+  const ciphertext = cipher.encrypt(plaintext, nonce);
+  const computationOffset = new anchor.BN(randomBytes(8), "hex");
+  const queueSig = await program.methods
+    .sellSharesPrivate(
+      computationOffset,
+      marketId,
+      Array.from(ciphertext[0]),
+      Array.from(ciphertext[1]),
+      Array.from(mpcPublicKey),
+      new anchor.BN(deserializeLE(nonce).toString()),
+      new anchor.BN(minProceeds)
+    )
     .accountsPartial({
+      computationAccount: getComputationAccAddress(
+        program.programId,
+        computationOffset
+      ),
+      clusterAccount: arciumClusterPubkey,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(program.programId),
+      executingPool: getExecutingPoolAccAddress(program.programId),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset("sell_shares_private")).readUInt32LE()
+      ),
       payer: owner.publicKey,
-      ata: ata,
-      mint: mint,
     })
+    .signers([owner])
     .rpc({ commitment: "confirmed" });
 
-  const claimMarketFundsEvent = await claimMarketFundsEventPromise;
-  const claimMarketFundsAmountUsdc = claimMarketFundsEvent.amount / 1e6;
-  console.log(`Claim market funds event=> amount: ${claimMarketFundsAmountUsdc}`);
-  return sig;
+  const finalizeSig = await awaitComputationFinalization(
+    provider as anchor.AnchorProvider,
+    computationOffset,
+    program.programId,
+    "confirmed"
+  );
+
+  const event = await sellSharesEventPromise;
+  console.log(`Private sell shares event=> status: ${event.status}, amount: ${event.amount / 1e6}, tvl: ${event.tvl / 1e6} USDC`);
+  return event;
 }
 
+// Batch ids start at 0 and the next batch can be opened as soon as the current one is clearing
+// Batch taking commits right now, batch ids count the market's epochs
+export async function getCurrentBatchId(
+  program: Program<ArxPredict>,
+  marketId: number,
+): Promise<number> {
+  const market = await getMarketData(program, marketId);
+  const now = Math.floor(Date.now() / 1000);
+  return Math.floor((now - market.batchEpochStart.toNumber()) / market.batchEpoch.toNumber());
+}
 
-export async function getUserPosition(
+export async function openBatch(
+  provider: anchor.AnchorProvider,
   program: Program<ArxPredict>,
+  arciumClusterPubkey: PublicKey,
   owner: anchor.web3.Keypair,
-  marketId: number
+  marketId: number,
+  batchId: number,
 ) {
-  try {
+  const nonce = randomBytes(16);
+  const computationOffset = new anchor.BN(randomBytes(8), "hex");
+  const queueSig = await program.methods
+    .openBatch(
+      computationOffset,
+      marketId,
+      new anchor.BN(batchId),
+      new anchor.BN(deserializeLE(nonce).toString())
+    )
+    .accountsPartial({
+      computationAccount: getComputationAccAddress(
+        program.programId,
+        computationOffset
+      ),
+      clusterAccount: arciumClusterPubkey,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(program.programId),
+      executingPool: getExecutingPoolAccAddress(program.programId),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset("init_batch_stats")).readUInt32LE()
+      ),
+      payer: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+
+  return await awaitComputationFinalization(
+    provider as anchor.AnchorProvider,
+    computationOffset,
+    program.programId,
+    "confirmed"
+  );
+}
+
+export async function commitBatchTrade(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  arciumClusterPubkey: PublicKey,
+  cipher: RescueCipher,
+  mpcPublicKey: Uint8Array,
+  owner: anchor.web3.Keypair,
+  marketId: number,
+  batchId: number,
+  vote: number,
+  amount: number,
+) {
+  const nonce = randomBytes(16);
+  const ciphertext = cipher.encrypt([BigInt(vote)], nonce);
+  const computationOffset = new anchor.BN(randomBytes(8), "hex");
+  const queueSig = await program.methods
+    .commitBatchTrade(
+      computationOffset,
+      marketId,
+      new anchor.BN(batchId),
+      Array.from(ciphertext[0]),
+      Array.from(mpcPublicKey),
+      new anchor.BN(deserializeLE(nonce).toString()),
+      new anchor.BN(amount)
+    )
+    .accountsPartial({
+      computationAccount: getComputationAccAddress(
+        program.programId,
+        computationOffset
+      ),
+      clusterAccount: arciumClusterPubkey,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(program.programId),
+      executingPool: getExecutingPoolAccAddress(program.programId),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset("commit_batch_trade")).readUInt32LE()
+      ),
+      payer: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+
+  return await awaitComputationFinalization(
+    provider as anchor.AnchorProvider,
+    computationOffset,
+    program.programId,
+    "confirmed"
+  );
+}
+
+export async function clearBatch(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  arciumClusterPubkey: PublicKey,
+  owner: anchor.web3.Keypair,
+  marketId: number,
+  batchId: number,
+) {
+  const computationOffset = new anchor.BN(randomBytes(8), "hex");
+  const queueSig = await program.methods
+    .clearBatch(
+      computationOffset,
+      marketId,
+      new anchor.BN(batchId)
+    )
+    .accountsPartial({
+      computationAccount: getComputationAccAddress(
+        program.programId,
+        computationOffset
+      ),
+      clusterAccount: arciumClusterPubkey,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(program.programId),
+      executingPool: getExecutingPoolAccAddress(program.programId),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset("clear_batch")).readUInt32LE()
+      ),
+      payer: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+
+  return await awaitComputationFinalization(
+    provider as anchor.AnchorProvider,
+    computationOffset,
+    program.programId,
+    "confirmed"
+  );
+}
+
+export async function settleBatchTrade(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  arciumClusterPubkey: PublicKey,
+  owner: anchor.web3.Keypair,
+  marketId: number,
+  batchId: number,
+) {
+  const computationOffset = new anchor.BN(randomBytes(8), "hex");
+  const queueSig = await program.methods
+    .settleBatchTrade(
+      computationOffset,
+      marketId,
+      new anchor.BN(batchId)
+    )
+    .accountsPartial({
+      computationAccount: getComputationAccAddress(
+        program.programId,
+        computationOffset
+      ),
+      clusterAccount: arciumClusterPubkey,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(program.programId),
+      executingPool: getExecutingPoolAccAddress(program.programId),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset("settle_batch_trade")).readUInt32LE()
+      ),
+      payer: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+
+  return await awaitComputationFinalization(
+    provider as anchor.AnchorProvider,
+    computationOffset,
+    program.programId,
+    "confirmed"
+  );
+}
+
+export async function refundBatchTrade(
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  marketId: number,
+  batchId: number,
+) {
+  return await program.methods
+    .refundBatchTrade(marketId, new anchor.BN(batchId))
+    .accountsPartial({
+      payer: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+}
+
+export function getLimitOrderAddress(
+  programId: PublicKey,
+  marketId: number,
+  owner: PublicKey,
+  orderId: number
+): PublicKey {
+  return PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("limit_order"),
+      new anchor.BN(marketId).toArrayLike(Buffer, "le", 4),
+      owner.toBuffer(),
+      new anchor.BN(orderId).toArrayLike(Buffer, "le", 8),
+    ],
+    programId
+  )[0];
+}
+
+// Rests a buy of `shares` of `option` until the outcome's price is at or below `limitPrice` (0 to 1).
+// `amount` is escrowed from the user account, the fill's cost and fee come out of it
+export async function placeLimitOrder(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  arciumClusterPubkey: PublicKey,
+  cipher: RescueCipher,
+  ownerPublicKey: Uint8Array,
+  owner: anchor.web3.Keypair,
+  marketId: number,
+  orderId: number,
+  option: number,
+  shares: number,
+  limitPrice: number,
+  amount: number,
+) {
+  const nonce = randomBytes(16);
+  const ciphertext = cipher.encrypt(
+    [BigInt(option), BigInt(shares), BigInt(Math.round(limitPrice * 1e12))],
+    nonce
+  );
+  const computationOffset = new anchor.BN(randomBytes(8), "hex");
+  await program.methods
+    .placeLimitOrder(
+      computationOffset,
+      marketId,
+      new anchor.BN(orderId),
+      Array.from(ciphertext[0]),
+      Array.from(ciphertext[1]),
+      Array.from(ciphertext[2]),
+      Array.from(ownerPublicKey),
+      new anchor.BN(deserializeLE(nonce).toString()),
+      new anchor.BN(amount),
+      new anchor.BN(deserializeLE(randomBytes(16)).toString())
+    )
+    .accountsPartial({
+      computationAccount: getComputationAccAddress(
+        program.programId,
+        computationOffset
+      ),
+      clusterAccount: arciumClusterPubkey,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(program.programId),
+      executingPool: getExecutingPoolAccAddress(program.programId),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset("place_limit_order")).readUInt32LE()
+      ),
+      payer: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+
+  return await awaitComputationFinalization(
+    provider as anchor.AnchorProvider,
+    computationOffset,
+    program.programId,
+    "confirmed"
+  );
+}
+
+// Walks up to four open orders against the market, anyone can send it. Only the total cost of the fills is revealed
+export async function matchOrders(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  arciumClusterPubkey: PublicKey,
+  payer: anchor.web3.Keypair,
+  marketId: number,
+  orders: PublicKey[],
+  ordersMatchedEventPromise: any
+) {
+  const computationOffset = new anchor.BN(randomBytes(8), "hex");
+  await program.methods
+    .matchOrders(computationOffset, marketId)
+    .accountsPartial({
+      computationAccount: getComputationAccAddress(
+        program.programId,
+        computationOffset
+      ),
+      clusterAccount: arciumClusterPubkey,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(program.programId),
+      executingPool: getExecutingPoolAccAddress(program.programId),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset("match_orders")).readUInt32LE()
+      ),
+      payer: payer.publicKey,
+      order0: orders[0],
+      order1: orders[1] ?? null,
+      order2: orders[2] ?? null,
+      order3: orders[3] ?? null,
+    })
+    .signers([payer])
+    .rpc({ commitment: "confirmed" });
+
+  await awaitComputationFinalization(
+    provider as anchor.AnchorProvider,
+    computationOffset,
+    program.programId,
+    "confirmed"
+  );
+  return await ordersMatchedEventPromise;
+}
+
+// Moves a filled order into the owner's position and refunds the rest of its escrow, an unfilled order is cancelled
+export async function settleLimitOrder(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  arciumClusterPubkey: PublicKey,
+  owner: anchor.web3.Keypair,
+  marketId: number,
+  orderId: number,
+  limitOrderSettledEventPromise: any
+) {
+  const computationOffset = new anchor.BN(randomBytes(8), "hex");
+  await program.methods
+    .settleLimitOrder(computationOffset, marketId, new anchor.BN(orderId))
+    .accountsPartial({
+      computationAccount: getComputationAccAddress(
+        program.programId,
+        computationOffset
+      ),
+      clusterAccount: arciumClusterPubkey,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(program.programId),
+      executingPool: getExecutingPoolAccAddress(program.programId),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset("settle_limit_order")).readUInt32LE()
+      ),
+      payer: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+
+  await awaitComputationFinalization(
+    provider as anchor.AnchorProvider,
+    computationOffset,
+    program.programId,
+    "confirmed"
+  );
+  return await limitOrderSettledEventPromise;
+}
+
+// Deposits one token per share from the user account for `shares` of every outcome, without trading against the market
+export async function mintCompleteSet(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  arciumClusterPubkey: PublicKey,
+  owner: anchor.web3.Keypair,
+  marketId: number,
+  shares: number,
+  completeSetMintedEventPromise: any
+) {
+  const computationOffset = new anchor.BN(randomBytes(8), "hex");
+  await program.methods
+    .mintCompleteSet(computationOffset, marketId, new anchor.BN(shares))
+    .accountsPartial({
+      computationAccount: getComputationAccAddress(
+        program.programId,
+        computationOffset
+      ),
+      clusterAccount: arciumClusterPubkey,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(program.programId),
+      executingPool: getExecutingPoolAccAddress(program.programId),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset("mint_complete_set")).readUInt32LE()
+      ),
+      payer: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+
+  await awaitComputationFinalization(
+    provider as anchor.AnchorProvider,
+    computationOffset,
+    program.programId,
+    "confirmed"
+  );
+  return await completeSetMintedEventPromise;
+}
+
+// Burns `shares` of every outcome for one token per share, the event's status says whether the position held them
+export async function redeemCompleteSet(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  arciumClusterPubkey: PublicKey,
+  owner: anchor.web3.Keypair,
+  marketId: number,
+  shares: number,
+  completeSetRedeemedEventPromise: any
+) {
+  const computationOffset = new anchor.BN(randomBytes(8), "hex");
+  await program.methods
+    .redeemCompleteSet(computationOffset, marketId, new anchor.BN(shares))
+    .accountsPartial({
+      computationAccount: getComputationAccAddress(
+        program.programId,
+        computationOffset
+      ),
+      clusterAccount: arciumClusterPubkey,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(program.programId),
+      executingPool: getExecutingPoolAccAddress(program.programId),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset("redeem_complete_set")).readUInt32LE()
+      ),
+      payer: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+
+  await awaitComputationFinalization(
+    provider as anchor.AnchorProvider,
+    computationOffset,
+    program.programId,
+    "confirmed"
+  );
+  return await completeSetRedeemedEventPromise;
+}
+
+// Unlocks a position whose trade never called back, only allowed after PENDING_TRADE_TIMEOUT
+export async function releasePendingTrade(
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  marketId: number,
+) {
+  return await program.methods
+    .releasePendingTrade(marketId)
+    .accountsPartial({
+      payer: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+}
+
+// Permissionless once resolve_by has passed without a settlement
+export async function expireMarket(
+  program: Program<ArxPredict>,
+  payer: anchor.web3.Keypair,
+  marketId: number,
+) {
+  return await program.methods
+    .expireMarket(marketId)
+    .accountsPartial({
+      payer: payer.publicKey,
+    })
+    .signers([payer])
+    .rpc({ commitment: "confirmed" });
+}
+
+export async function claimRewards(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  arciumClusterPubkey: PublicKey,
+  owner: anchor.web3.Keypair,
+  marketId: number,
+  claimRewardsEventPromise: any
+) {
+  const claimRewardsComputationOffset = new anchor.BN(randomBytes(8), "hex");
+  const queueClaimRewardsSig = await program.methods
+    .claimRewards(
+      claimRewardsComputationOffset,
+      marketId
+    )
+    .accountsPartial({
+      computationAccount: getComputationAccAddress(
+        program.programId,
+        claimRewardsComputationOffset
+      ),
+      clusterAccount: arciumClusterPubkey,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(program.programId),
+      executingPool: getExecutingPoolAccAddress(program.programId),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset("claim_rewards")).readUInt32LE()
+      ),
+      //authority: owner,
+      payer: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+
+  const finalizeSig = await awaitComputationFinalization(
+    provider as anchor.AnchorProvider,
+    claimRewardsComputationOffset,
+    program.programId,
+    "confirmed"
+  );
+
+  const claimRewardsEvent = await claimRewardsEventPromise;
+  const claimRewardsAmountUsdc = claimRewardsEvent.amount / 1e6;
+  console.log(`Claim rewards event=> amount: ${claimRewardsAmountUsdc}`);
+  return claimRewardsEvent;
+}
+
+// Scalar markets pay LONG and SHORT shares by where the resolved value fell between the bounds
+export async function claimRewardsScalar(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  arciumClusterPubkey: PublicKey,
+  owner: anchor.web3.Keypair,
+  marketId: number,
+  claimRewardsEventPromise: any
+) {
+  const claimRewardsComputationOffset = new anchor.BN(randomBytes(8), "hex");
+  const queueClaimRewardsSig = await program.methods
+    .claimRewardsScalar(
+      claimRewardsComputationOffset,
+      marketId
+    )
+    .accountsPartial({
+      computationAccount: getComputationAccAddress(
+        program.programId,
+        claimRewardsComputationOffset
+      ),
+      clusterAccount: arciumClusterPubkey,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(program.programId),
+      executingPool: getExecutingPoolAccAddress(program.programId),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset("claim_rewards_scalar")).readUInt32LE()
+      ),
+      //authority: owner,
+      payer: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+
+  const finalizeSig = await awaitComputationFinalization(
+    provider as anchor.AnchorProvider,
+    claimRewardsComputationOffset,
+    program.programId,
+    "confirmed"
+  );
+
+  const claimRewardsEvent = await claimRewardsEventPromise;
+  const claimRewardsAmountUsdc = claimRewardsEvent.amount / 1e6;
+  console.log(`Claim rewards event=> amount: ${claimRewardsAmountUsdc}`);
+  return claimRewardsEvent;
+}
+
+// Multisig markets settle once enough resolvers voted for the same outcome
+export async function submitResolution(
+  program: Program<ArxPredict>,
+  resolver: anchor.web3.Keypair,
+  marketId: number,
+  outcome: number,
+) {
+  return await program.methods
+    .submitResolution(marketId, outcome)
+    .accountsPartial({
+      payer: resolver.publicKey,
+    })
+    .signers([resolver])
+    .rpc({ commitment: "confirmed" });
+}
+
+// Optimistic markets: anyone proposes with a bond, anyone can match it to dispute before the window ends
+export async function proposeOutcome(
+  program: Program<ArxPredict>,
+  proposer: anchor.web3.Keypair,
+  marketId: number,
+  outcome: number,
+) {
+  return await program.methods
+    .proposeOutcome(marketId, outcome)
+    .accountsPartial({
+      payer: proposer.publicKey,
+    })
+    .signers([proposer])
+    .rpc({ commitment: "confirmed" });
+}
+
+export async function disputeOutcome(
+  program: Program<ArxPredict>,
+  disputer: anchor.web3.Keypair,
+  marketId: number,
+) {
+  return await program.methods
+    .disputeOutcome(marketId)
+    .accountsPartial({
+      payer: disputer.publicKey,
+    })
+    .signers([disputer])
+    .rpc({ commitment: "confirmed" });
+}
+
+export async function finalizeOutcome(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  marketId: number,
+  arciumClusterPubkey: PublicKey,
+  eventPromise: any
+) {
+  const revealComputationOffset = new anchor.BN(randomBytes(8), "hex");
+  await program.methods
+    .finalizeOutcome(revealComputationOffset, marketId)
+    .accountsPartial({
+      computationAccount: getComputationAccAddress(
+        program.programId,
+        revealComputationOffset
+      ),
+      clusterAccount: arciumClusterPubkey,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(program.programId),
+      executingPool: getExecutingPoolAccAddress(program.programId),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset("reveal_market")).readUInt32LE()
+      ),
+    })
+    .rpc({ commitment: "confirmed" });
+
+  await awaitComputationFinalization(
+    provider as anchor.AnchorProvider,
+    revealComputationOffset,
+    program.programId,
+    "confirmed"
+  );
+
+  return await eventPromise;
+}
+
+export async function claimBond(
+  program: Program<ArxPredict>,
+  claimant: anchor.web3.Keypair,
+  marketId: number,
+) {
+  return await program.methods
+    .claimBond(marketId)
+    .accountsPartial({
+      payer: claimant.publicKey,
+    })
+    .signers([claimant])
+    .rpc({ commitment: "confirmed" });
+}
+
+export async function cancelMarket(
+  program: Program<ArxPredict>,
+  authority: anchor.web3.Keypair,
+  marketId: number,
+) {
+  return await program.methods
+    .cancelMarket(marketId)
+    .accountsPartial({
+      payer: authority.publicKey,
+    })
+    .signers([authority])
+    .rpc({ commitment: "confirmed" });
+}
+
+export async function claimRefund(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  arciumClusterPubkey: PublicKey,
+  owner: anchor.web3.Keypair,
+  marketId: number,
+  claimRefundEventPromise: any
+) {
+  const claimRefundComputationOffset = new anchor.BN(randomBytes(8), "hex");
+  const queueClaimRefundSig = await program.methods
+    .claimRefund(
+      claimRefundComputationOffset,
+      marketId
+    )
+    .accountsPartial({
+      computationAccount: getComputationAccAddress(
+        program.programId,
+        claimRefundComputationOffset
+      ),
+      clusterAccount: arciumClusterPubkey,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(program.programId),
+      executingPool: getExecutingPoolAccAddress(program.programId),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset("claim_refund")).readUInt32LE()
+      ),
+      payer: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+
+  const finalizeSig = await awaitComputationFinalization(
+    provider as anchor.AnchorProvider,
+    claimRefundComputationOffset,
+    program.programId,
+    "confirmed"
+  );
+
+  const claimRefundEvent = await claimRefundEventPromise;
+  const claimRefundAmountUsdc = claimRefundEvent.amount / 1e6;
+  console.log(`Claim refund event=> amount: ${claimRefundAmountUsdc}`);
+  return claimRefundEvent;
+}
+
+
+export async function claimMarketFunds(
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  ata: anchor.web3.PublicKey,
+  mint: anchor.web3.PublicKey,
+  marketId: number,
+  claimMarketFundsEventPromise: any
+) {
+  const sig = await program.methods
+    .claimMarketFunds(marketId)
+    .accountsPartial({
+      payer: owner.publicKey,
+      ata: ata,
+      mint: mint,
+    })
+    .rpc({ commitment: "confirmed" });
+
+  const claimMarketFundsEvent = await claimMarketFundsEventPromise;
+  const claimMarketFundsAmountUsdc = claimMarketFundsEvent.amount / 1e6;
+  console.log(`Claim market funds event=> amount: ${claimMarketFundsAmountUsdc}`);
+  return sig;
+}
+
+export async function claimFees(
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  ata: anchor.web3.PublicKey,
+  mint: anchor.web3.PublicKey,
+  marketId: number,
+  bucket: FeeBucket,
+) {
+  return await program.methods
+    .claimFees(marketId, bucket)
+    .accountsPartial({
+      payer: owner.publicKey,
+      ata: ata,
+      mint: mint,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+}
+
+// Only positions that claimed their rewards or refund can be closed
+export async function closeUserPosition(
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  marketId: number
+) {
+  return await program.methods
+    .closeUserPosition(marketId)
+    .accountsPartial({
+      payer: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+}
+
+// Fees must be claimed first, the remaining tvl is credited to the authority's user account
+export async function closeMarket(
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  marketId: number
+) {
+  return await program.methods
+    .closeMarket(marketId)
+    .accountsPartial({
+      payer: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+}
+
+// Re-encrypts the owner's position to `cipher`'s key and decrypts it, nothing is revealed on chain
+export async function viewPosition(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  arciumClusterPubkey: PublicKey,
+  cipher: RescueCipher,
+  ownerPublicKey: Uint8Array,
+  owner: anchor.web3.Keypair,
+  marketId: number,
+  positionViewEventPromise: any
+) {
+  const nonce = randomBytes(16);
+  const viewComputationOffset = new anchor.BN(randomBytes(8), "hex");
+  await program.methods
+    .viewPosition(
+      viewComputationOffset,
+      marketId,
+      Array.from(ownerPublicKey),
+      new anchor.BN(deserializeLE(nonce).toString())
+    )
+    .accountsPartial({
+      computationAccount: getComputationAccAddress(
+        program.programId,
+        viewComputationOffset
+      ),
+      clusterAccount: arciumClusterPubkey,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(program.programId),
+      executingPool: getExecutingPoolAccAddress(program.programId),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset("view_position")).readUInt32LE()
+      ),
+      payer: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+
+  await awaitComputationFinalization(
+    provider as anchor.AnchorProvider,
+    viewComputationOffset,
+    program.programId,
+    "confirmed"
+  );
+
+  const positionViewEvent = await positionViewEventPromise;
+  const decrypted = cipher.decrypt(
+    [...positionViewEvent.shares, ...positionViewEvent.costBasis],
+    new anchor.BN(positionViewEvent.nonce).toArrayLike(Buffer, "le", 16)
+  );
+  const numOptions = positionViewEvent.shares.length;
+  return {
+    tradeSeq: positionViewEvent.tradeSeq.toNumber(),
+    shares: decrypted.slice(0, numOptions),
+    costBasis: decrypted.slice(numOptions),
+  };
+}
+
+// Marks the owner's position to market, decrypted with `cipher`. PnL is the sell-everything value minus the cost basis
+export async function valuePosition(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  arciumClusterPubkey: PublicKey,
+  cipher: RescueCipher,
+  ownerPublicKey: Uint8Array,
+  owner: anchor.web3.Keypair,
+  marketId: number,
+  positionValueEventPromise: any
+) {
+  const nonce = randomBytes(16);
+  const valueComputationOffset = new anchor.BN(randomBytes(8), "hex");
+  await program.methods
+    .valuePosition(
+      valueComputationOffset,
+      marketId,
+      Array.from(ownerPublicKey),
+      new anchor.BN(deserializeLE(nonce).toString())
+    )
+    .accountsPartial({
+      computationAccount: getComputationAccAddress(
+        program.programId,
+        valueComputationOffset
+      ),
+      clusterAccount: arciumClusterPubkey,
+      mxeAccount: getMXEAccAddress(program.programId),
+      mempoolAccount: getMempoolAccAddress(program.programId),
+      executingPool: getExecutingPoolAccAddress(program.programId),
+      compDefAccount: getCompDefAccAddress(
+        program.programId,
+        Buffer.from(getCompDefAccOffset("value_position")).readUInt32LE()
+      ),
+      payer: owner.publicKey,
+    })
+    .signers([owner])
+    .rpc({ commitment: "confirmed" });
+
+  await awaitComputationFinalization(
+    provider as anchor.AnchorProvider,
+    valueComputationOffset,
+    program.programId,
+    "confirmed"
+  );
+
+  const positionValueEvent = await positionValueEventPromise;
+  const [value, costBasis] = cipher.decrypt(
+    [positionValueEvent.value, positionValueEvent.costBasis],
+    new anchor.BN(positionValueEvent.nonce).toArrayLike(Buffer, "le", 16)
+  );
+  return {
+    tradeSeq: positionValueEvent.tradeSeq.toNumber(),
+    value,
+    costBasis,
+    pnl: value - costBasis,
+  };
+}
+
+export async function getUserPosition(
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  marketId: number
+) {
+  try {
     const userPositionSeed = [
       Buffer.from("user_position"),
       new anchor.BN(marketId).toArrayLike(Buffer, "le", 4),
@@ -562,7 +1781,7 @@ export async function getUserPosition(
     ];
     const userPositionPDA = PublicKey.findProgramAddressSync(userPositionSeed, program.programId)[0];
     const userPosition = await program.account.userPosition.fetch(userPositionPDA);
-    console.log(`User position=> ${userPositionPDA.toBase58()} marketId: ${userPosition.marketId}, balance: ${userPosition.balance.toNumber() / 1e6} USDC`);
+    console.log(`User position=> ${userPositionPDA.toBase58()} marketId: ${userPosition.marketId}, pending: ${userPosition.pending}`);
     return userPosition;
   } catch (e) {
     console.error("Error getting user position: ", e);
@@ -570,6 +1789,22 @@ export async function getUserPosition(
   }
 }
 
+export async function getUserAccount(
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  mint: PublicKey
+) {
+  try {
+    const userAccountPDA = getUserAccountAddress(program.programId, mint, owner.publicKey);
+    const userAccount = await program.account.userAccount.fetch(userAccountPDA);
+    console.log(`User account=> ${userAccountPDA.toBase58()} balance: ${userAccount.balance.toNumber() / 1e6} USDC`);
+    return userAccount;
+  } catch (e) {
+    console.error("Error getting user account: ", e);
+    return null;
+  }
+}
+
 
 export async function getMarketData(
   program: Program<ArxPredict>,
@@ -1,22 +1,23 @@
 import * as anchor from "@coral-xyz/anchor";
 
-import { createTokenMint, getRequiredATA, readKpJson } from "./utils";
+import { createTokenMint, getRequiredATA, readKpJson, unixNow } from "./utils";
 import {
 //   getMXEPublicKeyWithRetry,
   getProbs as getProbsHelper,
   createUserPosition as createUserPositionHelper,
   createMarket as createMarketHelper,
-  sendPayment as sendPaymentHelper,
+  deposit as depositHelper,
 //   revealResult as revealResultHelper,
   buyShares as buySharesHelper,
   sellShares as sellSharesHelper,
-  withdrawPayment as withdrawPaymentHelper,
+  withdraw as withdrawHelper,
   settleMarket as settleMarketHelper,
   claimRewards as claimRewardsHelper,
   claimMarketFunds as claimMarketFundsHelper,
   getMXEPublicKeyWithRetry,
   fundAndCreateMarket,
   getUserPosition,
+  getUserAccount,
   getMarketData,
 } from "./arcium_helper";
 
@@ -30,13 +31,16 @@ async function createMarket(
     marketId: number,
     options: string[],
     question: string,
-    liquidityParameter: number = 10
+    liquidityParameter: number = 10,
+    tradingSeconds: number = 7 * 24 * 3600,
+    resolutionSeconds: number = 7 * 24 * 3600
 ) {
     // const liquidityParameter = 10;
     // const options = ["Yes", "No"];
     // const question = `$SOL to 500?`;
 
     console.log("Creating market", marketId, "with liquidity parameter", liquidityParameter);
+    const tradingEndsAt = unixNow() + tradingSeconds;
     const sig = await fundAndCreateMarket(
         setupData.provider,
         setupData.program,
@@ -47,7 +51,9 @@ async function createMarket(
         liquidityParameter,
         setupData.mint,
         setupData.wallet,
-        setupData.ata
+        setupData.ata,
+        tradingEndsAt,
+        tradingEndsAt + resolutionSeconds
       );
     console.log("Market created: ", sig);
 }
@@ -79,7 +85,7 @@ async function createUserPosition(
     console.log("User position created: ", sig);
 }
 
-async function sendPayment() {
+async function deposit() {
     const setupData = await setup();
     const {
         program,
@@ -87,12 +93,11 @@ async function sendPayment() {
         wallet,
         mint,
     } = setupData;
-    const marketId = 1;
     const paymentAmount = 5 * 1e6;
     const ata = await getRequiredATA(provider, wallet, mint, wallet, wallet, 0);
     console.log("ATA: ", ata.toBase58());
-    const sig = await sendPaymentHelper(program, wallet, ata, mint, marketId, paymentAmount);
-    console.log("Payment sent: ", sig);
+    const sig = await depositHelper(program, wallet, ata, mint, paymentAmount);
+    console.log("Deposit sent: ", sig);
 }
 
 async function calculateSharesAndBuy(
@@ -198,7 +203,7 @@ async function buySharesInAmount(
     const SLIPPAGE = 1.05
     const marketStats = await getMarketStats(setupData, marketId);
     console.log("Market stats: ", marketStats.voteStats[0]/1e6, marketStats.voteStats[1]/1e6, marketStats.liquidityParameter);
-    const userPositionInital = await getUserPosition(setupData.program, setupData.wallet, marketId);
+    const userAccountInitial = await getUserAccount(setupData.program, setupData.wallet, setupData.mint);
     const shares = calculateSharesForAmount(
         marketStats.liquidityParameter,
         marketStats.voteStats,
@@ -209,17 +214,17 @@ async function buySharesInAmount(
 
     const amountWithSlippage = amount * SLIPPAGE;
     let amountToSend = amountWithSlippage;
-    if(userPositionInital) {
-        amountToSend = Math.max(0, amountWithSlippage - userPositionInital.balance.toNumber());
+    if(userAccountInitial) {
+        amountToSend = Math.max(0, amountWithSlippage - userAccountInitial.balance.toNumber());
         amountToSend = Math.min(amountToSend, 1 * 1e6); //Minimum amount to send is 1 USDC
-        console.log(`User position already has ${userPositionInital.balance.toNumber() / 1e6} USDC sending ${amountToSend / 1e6} USDC`);
+        console.log(`User account already has ${userAccountInitial.balance.toNumber() / 1e6} USDC sending ${amountToSend / 1e6} USDC`);
     }
     
     if(amountToSend > 0) {
         try {
             const ata = await getRequiredATA(setupData.provider, setupData.wallet, setupData.mint, setupData.wallet, setupData.wallet, 0);
-            const sig = await sendPaymentHelper(setupData.program, setupData.wallet, ata, setupData.mint, marketId, amountToSend);
-            console.log("Payment sent: ", sig);
+            const sig = await depositHelper(setupData.program, setupData.wallet, ata, setupData.mint, amountToSend);
+            console.log("Deposit sent: ", sig);
         } catch (error) {
             console.log("Payment error: ", error);
         }
@@ -245,7 +250,7 @@ async function buySharesInAmount(
     }
 
 
-    const userPositionFinal = await getUserPosition(setupData.program, setupData.wallet, marketId);
+    const userAccountFinal = await getUserAccount(setupData.program, setupData.wallet, setupData.mint);
 }
 
 async function settleMarket(setupData: SetupData, marketId: number, winner: number) {
@@ -329,7 +334,7 @@ async function main() {
     // const shares = calculateSharesForAmount(10, [10704587, 8880614], 0, 1.184921 * 1e6);
     // console.log("Shares: ", shares / 1e6);  //2.0751953125
 
-    // await sendPayment();
+    // await deposit();
     // await buyShares(setupData);
     // await revealProbs(setupData, marketId);
 
@@ -468,4 +468,1058 @@ export async function initClaimRewardsCompDef(
     await provider.sendAndConfirm(finalizeTx);
   }
   return sig;
-}
\ No newline at end of file
+}
+
+export async function initBuyWithAmountCompDef(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  uploadRawCircuit: boolean
+): Promise<string> {
+  const baseSeedCompDefAcc = getArciumAccountBaseSeed(
+    "ComputationDefinitionAccount"
+  );
+  const offset = getCompDefAccOffset("buy_with_amount");
+
+  const compDefPDA = PublicKey.findProgramAddressSync(
+    [baseSeedCompDefAcc, program.programId.toBuffer(), offset],
+    getArciumProgAddress()
+  )[0];
+
+  const exists = await checkCompDefAccountExists(provider, compDefPDA);
+  if (exists) {
+    console.log(`Computation definition account ${compDefPDA.toString()} already exists. Skipping initialization.`);
+    return "Account already exists";
+  }
+
+  const sig = await program.methods
+    .initBuyWithAmountCompDef()
+    .accounts({
+      compDefAccount: compDefPDA,
+      payer: owner.publicKey,
+      mxeAccount: getMXEAccAddress(program.programId),
+    })
+    .signers([owner])
+    .rpc({
+      commitment: "confirmed",
+    });
+
+  if (uploadRawCircuit) {
+    const rawCircuit = fs.readFileSync("build/buy_with_amount.arcis");
+
+    await uploadCircuit(
+      provider as anchor.AnchorProvider,
+      "buy_with_amount",
+      program.programId,
+      rawCircuit,
+      true
+    );
+  } else {
+    const finalizeTx = await buildFinalizeCompDefTx(
+      provider as anchor.AnchorProvider,
+      Buffer.from(offset).readUInt32LE(),
+      program.programId
+    );
+
+    const latestBlockhash = await provider.connection.getLatestBlockhash();
+    finalizeTx.recentBlockhash = latestBlockhash.blockhash;
+    finalizeTx.lastValidBlockHeight = latestBlockhash.lastValidBlockHeight;
+
+    finalizeTx.sign(owner);
+
+    await provider.sendAndConfirm(finalizeTx);
+  }
+  return sig;
+}
+
+export async function initBuySharesPrivateCompDef(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  uploadRawCircuit: boolean
+): Promise<string> {
+  const baseSeedCompDefAcc = getArciumAccountBaseSeed(
+    "ComputationDefinitionAccount"
+  );
+  const offset = getCompDefAccOffset("buy_shares_private");
+
+  const compDefPDA = PublicKey.findProgramAddressSync(
+    [baseSeedCompDefAcc, program.programId.toBuffer(), offset],
+    getArciumProgAddress()
+  )[0];
+
+  const exists = await checkCompDefAccountExists(provider, compDefPDA);
+  if (exists) {
+    console.log(`Computation definition account ${compDefPDA.toString()} already exists. Skipping initialization.`);
+    return "Account already exists";
+  }
+
+  const sig = await program.methods
+    .initBuySharesPrivateCompDef()
+    .accounts({
+      compDefAccount: compDefPDA,
+      payer: owner.publicKey,
+      mxeAccount: getMXEAccAddress(program.programId),
+    })
+    .signers([owner])
+    .rpc({
+      commitment: "confirmed",
+    });
+
+  if (uploadRawCircuit) {
+    const rawCircuit = fs.readFileSync("build/buy_shares_private.arcis");
+
+    await uploadCircuit(
+      provider as anchor.AnchorProvider,
+      "buy_shares_private",
+      program.programId,
+      rawCircuit,
+      true
+    );
+  } else {
+    const finalizeTx = await buildFinalizeCompDefTx(
+      provider as anchor.AnchorProvider,
+      Buffer.from(offset).readUInt32LE(),
+      program.programId
+    );
+
+    const latestBlockhash = await provider.connection.getLatestBlockhash();
+    finalizeTx.recentBlockhash = latestBlockhash.blockhash;
+    finalizeTx.lastValidBlockHeight = latestBlockhash.lastValidBlockHeight;
+
+    finalizeTx.sign(owner);
+
+    await provider.sendAndConfirm(finalizeTx);
+  }
+  return sig;
+}
+
+export async function initSellSharesPrivateCompDef(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  uploadRawCircuit: boolean
+): Promise<string> {
+  const baseSeedCompDefAcc = getArciumAccountBaseSeed(
+    "ComputationDefinitionAccount"
+  );
+  const offset = getCompDefAccOffset("sell_shares_private");
+
+  const compDefPDA = PublicKey.findProgramAddressSync(
+    [baseSeedCompDefAcc, program.programId.toBuffer(), offset],
+    getArciumProgAddress()
+  )[0];
+
+  const exists = await checkCompDefAccountExists(provider, compDefPDA);
+  if (exists) {
+    console.log(`Computation definition account ${compDefPDA.toString()} already exists. Skipping initialization.`);
+    return "Account already exists";
+  }
+
+  const sig = await program.methods
+    .initSellSharesPrivateCompDef()
+    .accounts({
+      compDefAccount: compDefPDA,
+      payer: owner.publicKey,
+      mxeAccount: getMXEAccAddress(program.programId),
+    })
+    .signers([owner])
+    .rpc({
+      commitment: "confirmed",
+    });
+
+  if (uploadRawCircuit) {
+    const rawCircuit = fs.readFileSync("build/sell_shares_private.arcis");
+
+    await uploadCircuit(
+      provider as anchor.AnchorProvider,
+      "sell_shares_private",
+      program.programId,
+      rawCircuit,
+      true
+    );
+  } else {
+    const finalizeTx = await buildFinalizeCompDefTx(
+      provider as anchor.AnchorProvider,
+      Buffer.from(offset).readUInt32LE(),
+      program.programId
+    );
+
+    const latestBlockhash = await provider.connection.getLatestBlockhash();
+    finalizeTx.recentBlockhash = latestBlockhash.blockhash;
+    finalizeTx.lastValidBlockHeight = latestBlockhash.lastValidBlockHeight;
+
+    finalizeTx.sign(owner);
+
+    await provider.sendAndConfirm(finalizeTx);
+  }
+  return sig;
+}
+
+export async function initInitBatchStatsCompDef(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  uploadRawCircuit: boolean
+): Promise<string> {
+  const baseSeedCompDefAcc = getArciumAccountBaseSeed(
+    "ComputationDefinitionAccount"
+  );
+  const offset = getCompDefAccOffset("init_batch_stats");
+
+  const compDefPDA = PublicKey.findProgramAddressSync(
+    [baseSeedCompDefAcc, program.programId.toBuffer(), offset],
+    getArciumProgAddress()
+  )[0];
+
+  const exists = await checkCompDefAccountExists(provider, compDefPDA);
+  if (exists) {
+    console.log(`Computation definition account ${compDefPDA.toString()} already exists. Skipping initialization.`);
+    return "Account already exists";
+  }
+
+  const sig = await program.methods
+    .initInitBatchStatsCompDef()
+    .accounts({
+      compDefAccount: compDefPDA,
+      payer: owner.publicKey,
+      mxeAccount: getMXEAccAddress(program.programId),
+    })
+    .signers([owner])
+    .rpc({
+      commitment: "confirmed",
+    });
+
+  if (uploadRawCircuit) {
+    const rawCircuit = fs.readFileSync("build/init_batch_stats.arcis");
+
+    await uploadCircuit(
+      provider as anchor.AnchorProvider,
+      "init_batch_stats",
+      program.programId,
+      rawCircuit,
+      true
+    );
+  } else {
+    const finalizeTx = await buildFinalizeCompDefTx(
+      provider as anchor.AnchorProvider,
+      Buffer.from(offset).readUInt32LE(),
+      program.programId
+    );
+
+    const latestBlockhash = await provider.connection.getLatestBlockhash();
+    finalizeTx.recentBlockhash = latestBlockhash.blockhash;
+    finalizeTx.lastValidBlockHeight = latestBlockhash.lastValidBlockHeight;
+
+    finalizeTx.sign(owner);
+
+    await provider.sendAndConfirm(finalizeTx);
+  }
+  return sig;
+}
+
+export async function initCommitBatchTradeCompDef(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  uploadRawCircuit: boolean
+): Promise<string> {
+  const baseSeedCompDefAcc = getArciumAccountBaseSeed(
+    "ComputationDefinitionAccount"
+  );
+  const offset = getCompDefAccOffset("commit_batch_trade");
+
+  const compDefPDA = PublicKey.findProgramAddressSync(
+    [baseSeedCompDefAcc, program.programId.toBuffer(), offset],
+    getArciumProgAddress()
+  )[0];
+
+  const exists = await checkCompDefAccountExists(provider, compDefPDA);
+  if (exists) {
+    console.log(`Computation definition account ${compDefPDA.toString()} already exists. Skipping initialization.`);
+    return "Account already exists";
+  }
+
+  const sig = await program.methods
+    .initCommitBatchTradeCompDef()
+    .accounts({
+      compDefAccount: compDefPDA,
+      payer: owner.publicKey,
+      mxeAccount: getMXEAccAddress(program.programId),
+    })
+    .signers([owner])
+    .rpc({
+      commitment: "confirmed",
+    });
+
+  if (uploadRawCircuit) {
+    const rawCircuit = fs.readFileSync("build/commit_batch_trade.arcis");
+
+    await uploadCircuit(
+      provider as anchor.AnchorProvider,
+      "commit_batch_trade",
+      program.programId,
+      rawCircuit,
+      true
+    );
+  } else {
+    const finalizeTx = await buildFinalizeCompDefTx(
+      provider as anchor.AnchorProvider,
+      Buffer.from(offset).readUInt32LE(),
+      program.programId
+    );
+
+    const latestBlockhash = await provider.connection.getLatestBlockhash();
+    finalizeTx.recentBlockhash = latestBlockhash.blockhash;
+    finalizeTx.lastValidBlockHeight = latestBlockhash.lastValidBlockHeight;
+
+    finalizeTx.sign(owner);
+
+    await provider.sendAndConfirm(finalizeTx);
+  }
+  return sig;
+}
+
+export async function initClearBatchCompDef(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  uploadRawCircuit: boolean
+): Promise<string> {
+  const baseSeedCompDefAcc = getArciumAccountBaseSeed(
+    "ComputationDefinitionAccount"
+  );
+  const offset = getCompDefAccOffset("clear_batch");
+
+  const compDefPDA = PublicKey.findProgramAddressSync(
+    [baseSeedCompDefAcc, program.programId.toBuffer(), offset],
+    getArciumProgAddress()
+  )[0];
+
+  const exists = await checkCompDefAccountExists(provider, compDefPDA);
+  if (exists) {
+    console.log(`Computation definition account ${compDefPDA.toString()} already exists. Skipping initialization.`);
+    return "Account already exists";
+  }
+
+  const sig = await program.methods
+    .initClearBatchCompDef()
+    .accounts({
+      compDefAccount: compDefPDA,
+      payer: owner.publicKey,
+      mxeAccount: getMXEAccAddress(program.programId),
+    })
+    .signers([owner])
+    .rpc({
+      commitment: "confirmed",
+    });
+
+  if (uploadRawCircuit) {
+    const rawCircuit = fs.readFileSync("build/clear_batch.arcis");
+
+    await uploadCircuit(
+      provider as anchor.AnchorProvider,
+      "clear_batch",
+      program.programId,
+      rawCircuit,
+      true
+    );
+  } else {
+    const finalizeTx = await buildFinalizeCompDefTx(
+      provider as anchor.AnchorProvider,
+      Buffer.from(offset).readUInt32LE(),
+      program.programId
+    );
+
+    const latestBlockhash = await provider.connection.getLatestBlockhash();
+    finalizeTx.recentBlockhash = latestBlockhash.blockhash;
+    finalizeTx.lastValidBlockHeight = latestBlockhash.lastValidBlockHeight;
+
+    finalizeTx.sign(owner);
+
+    await provider.sendAndConfirm(finalizeTx);
+  }
+  return sig;
+}
+
+export async function initSettleBatchTradeCompDef(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  uploadRawCircuit: boolean
+): Promise<string> {
+  const baseSeedCompDefAcc = getArciumAccountBaseSeed(
+    "ComputationDefinitionAccount"
+  );
+  const offset = getCompDefAccOffset("settle_batch_trade");
+
+  const compDefPDA = PublicKey.findProgramAddressSync(
+    [baseSeedCompDefAcc, program.programId.toBuffer(), offset],
+    getArciumProgAddress()
+  )[0];
+
+  const exists = await checkCompDefAccountExists(provider, compDefPDA);
+  if (exists) {
+    console.log(`Computation definition account ${compDefPDA.toString()} already exists. Skipping initialization.`);
+    return "Account already exists";
+  }
+
+  const sig = await program.methods
+    .initSettleBatchTradeCompDef()
+    .accounts({
+      compDefAccount: compDefPDA,
+      payer: owner.publicKey,
+      mxeAccount: getMXEAccAddress(program.programId),
+    })
+    .signers([owner])
+    .rpc({
+      commitment: "confirmed",
+    });
+
+  if (uploadRawCircuit) {
+    const rawCircuit = fs.readFileSync("build/settle_batch_trade.arcis");
+
+    await uploadCircuit(
+      provider as anchor.AnchorProvider,
+      "settle_batch_trade",
+      program.programId,
+      rawCircuit,
+      true
+    );
+  } else {
+    const finalizeTx = await buildFinalizeCompDefTx(
+      provider as anchor.AnchorProvider,
+      Buffer.from(offset).readUInt32LE(),
+      program.programId
+    );
+
+    const latestBlockhash = await provider.connection.getLatestBlockhash();
+    finalizeTx.recentBlockhash = latestBlockhash.blockhash;
+    finalizeTx.lastValidBlockHeight = latestBlockhash.lastValidBlockHeight;
+
+    finalizeTx.sign(owner);
+
+    await provider.sendAndConfirm(finalizeTx);
+  }
+  return sig;
+}
+
+export async function initClaimRefundCompDef(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  uploadRawCircuit: boolean
+): Promise<string> {
+  const baseSeedCompDefAcc = getArciumAccountBaseSeed(
+    "ComputationDefinitionAccount"
+  );
+  const offset = getCompDefAccOffset("claim_refund");
+
+  const compDefPDA = PublicKey.findProgramAddressSync(
+    [baseSeedCompDefAcc, program.programId.toBuffer(), offset],
+    getArciumProgAddress()
+  )[0];
+
+  const exists = await checkCompDefAccountExists(provider, compDefPDA);
+  if (exists) {
+    console.log(`Computation definition account ${compDefPDA.toString()} already exists. Skipping initialization.`);
+    return "Account already exists";
+  }
+
+  const sig = await program.methods
+    .initClaimRefundCompDef()
+    .accounts({
+      compDefAccount: compDefPDA,
+      payer: owner.publicKey,
+      mxeAccount: getMXEAccAddress(program.programId),
+    })
+    .signers([owner])
+    .rpc({
+      commitment: "confirmed",
+    });
+
+  if (uploadRawCircuit) {
+    const rawCircuit = fs.readFileSync("build/claim_refund.arcis");
+
+    await uploadCircuit(
+      provider as anchor.AnchorProvider,
+      "claim_refund",
+      program.programId,
+      rawCircuit,
+      true
+    );
+  } else {
+    const finalizeTx = await buildFinalizeCompDefTx(
+      provider as anchor.AnchorProvider,
+      Buffer.from(offset).readUInt32LE(),
+      program.programId
+    );
+
+    const latestBlockhash = await provider.connection.getLatestBlockhash();
+    finalizeTx.recentBlockhash = latestBlockhash.blockhash;
+    finalizeTx.lastValidBlockHeight = latestBlockhash.lastValidBlockHeight;
+
+    finalizeTx.sign(owner);
+
+    await provider.sendAndConfirm(finalizeTx);
+  }
+  return sig;
+}
+
+export async function initClaimRewardsScalarCompDef(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  uploadRawCircuit: boolean
+): Promise<string> {
+  const baseSeedCompDefAcc = getArciumAccountBaseSeed(
+    "ComputationDefinitionAccount"
+  );
+  const offset = getCompDefAccOffset("claim_rewards_scalar");
+
+  const compDefPDA = PublicKey.findProgramAddressSync(
+    [baseSeedCompDefAcc, program.programId.toBuffer(), offset],
+    getArciumProgAddress()
+  )[0];
+
+  const exists = await checkCompDefAccountExists(provider, compDefPDA);
+  if (exists) {
+    console.log(`Computation definition account ${compDefPDA.toString()} already exists. Skipping initialization.`);
+    return "Account already exists";
+  }
+
+  const sig = await program.methods
+    .initClaimRewardsScalarCompDef()
+    .accounts({
+      compDefAccount: compDefPDA,
+      payer: owner.publicKey,
+      mxeAccount: getMXEAccAddress(program.programId),
+    })
+    .signers([owner])
+    .rpc({
+      commitment: "confirmed",
+    });
+
+  if (uploadRawCircuit) {
+    const rawCircuit = fs.readFileSync("build/claim_rewards_scalar.arcis");
+
+    await uploadCircuit(
+      provider as anchor.AnchorProvider,
+      "claim_rewards_scalar",
+      program.programId,
+      rawCircuit,
+      true
+    );
+  } else {
+    const finalizeTx = await buildFinalizeCompDefTx(
+      provider as anchor.AnchorProvider,
+      Buffer.from(offset).readUInt32LE(),
+      program.programId
+    );
+
+    const latestBlockhash = await provider.connection.getLatestBlockhash();
+    finalizeTx.recentBlockhash = latestBlockhash.blockhash;
+    finalizeTx.lastValidBlockHeight = latestBlockhash.lastValidBlockHeight;
+
+    finalizeTx.sign(owner);
+
+    await provider.sendAndConfirm(finalizeTx);
+  }
+  return sig;
+}
+
+export async function initViewPositionCompDef(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  uploadRawCircuit: boolean
+): Promise<string> {
+  const baseSeedCompDefAcc = getArciumAccountBaseSeed(
+    "ComputationDefinitionAccount"
+  );
+  const offset = getCompDefAccOffset("view_position");
+
+  const compDefPDA = PublicKey.findProgramAddressSync(
+    [baseSeedCompDefAcc, program.programId.toBuffer(), offset],
+    getArciumProgAddress()
+  )[0];
+
+  const exists = await checkCompDefAccountExists(provider, compDefPDA);
+  if (exists) {
+    console.log(`Computation definition account ${compDefPDA.toString()} already exists. Skipping initialization.`);
+    return "Account already exists";
+  }
+
+  const sig = await program.methods
+    .initViewPositionCompDef()
+    .accounts({
+      compDefAccount: compDefPDA,
+      payer: owner.publicKey,
+      mxeAccount: getMXEAccAddress(program.programId),
+    })
+    .signers([owner])
+    .rpc({
+      commitment: "confirmed",
+    });
+
+  if (uploadRawCircuit) {
+    const rawCircuit = fs.readFileSync("build/view_position.arcis");
+
+    await uploadCircuit(
+      provider as anchor.AnchorProvider,
+      "view_position",
+      program.programId,
+      rawCircuit,
+      true
+    );
+  } else {
+    const finalizeTx = await buildFinalizeCompDefTx(
+      provider as anchor.AnchorProvider,
+      Buffer.from(offset).readUInt32LE(),
+      program.programId
+    );
+
+    const latestBlockhash = await provider.connection.getLatestBlockhash();
+    finalizeTx.recentBlockhash = latestBlockhash.blockhash;
+    finalizeTx.lastValidBlockHeight = latestBlockhash.lastValidBlockHeight;
+
+    finalizeTx.sign(owner);
+
+    await provider.sendAndConfirm(finalizeTx);
+  }
+  return sig;
+}
+
+export async function initValuePositionCompDef(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  uploadRawCircuit: boolean
+): Promise<string> {
+  const baseSeedCompDefAcc = getArciumAccountBaseSeed(
+    "ComputationDefinitionAccount"
+  );
+  const offset = getCompDefAccOffset("value_position");
+
+  const compDefPDA = PublicKey.findProgramAddressSync(
+    [baseSeedCompDefAcc, program.programId.toBuffer(), offset],
+    getArciumProgAddress()
+  )[0];
+
+  const exists = await checkCompDefAccountExists(provider, compDefPDA);
+  if (exists) {
+    console.log(`Computation definition account ${compDefPDA.toString()} already exists. Skipping initialization.`);
+    return "Account already exists";
+  }
+
+  const sig = await program.methods
+    .initValuePositionCompDef()
+    .accounts({
+      compDefAccount: compDefPDA,
+      payer: owner.publicKey,
+      mxeAccount: getMXEAccAddress(program.programId),
+    })
+    .signers([owner])
+    .rpc({
+      commitment: "confirmed",
+    });
+
+  if (uploadRawCircuit) {
+    const rawCircuit = fs.readFileSync("build/value_position.arcis");
+
+    await uploadCircuit(
+      provider as anchor.AnchorProvider,
+      "value_position",
+      program.programId,
+      rawCircuit,
+      true
+    );
+  } else {
+    const finalizeTx = await buildFinalizeCompDefTx(
+      provider as anchor.AnchorProvider,
+      Buffer.from(offset).readUInt32LE(),
+      program.programId
+    );
+
+    const latestBlockhash = await provider.connection.getLatestBlockhash();
+    finalizeTx.recentBlockhash = latestBlockhash.blockhash;
+    finalizeTx.lastValidBlockHeight = latestBlockhash.lastValidBlockHeight;
+
+    finalizeTx.sign(owner);
+
+    await provider.sendAndConfirm(finalizeTx);
+  }
+  return sig;
+}
+
+export async function initQuoteTradeCompDef(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  uploadRawCircuit: boolean
+): Promise<string> {
+  const baseSeedCompDefAcc = getArciumAccountBaseSeed(
+    "ComputationDefinitionAccount"
+  );
+  const offset = getCompDefAccOffset("quote_trade");
+
+  const compDefPDA = PublicKey.findProgramAddressSync(
+    [baseSeedCompDefAcc, program.programId.toBuffer(), offset],
+    getArciumProgAddress()
+  )[0];
+
+  const exists = await checkCompDefAccountExists(provider, compDefPDA);
+  if (exists) {
+    console.log(`Computation definition account ${compDefPDA.toString()} already exists. Skipping initialization.`);
+    return "Account already exists";
+  }
+
+  const sig = await program.methods
+    .initQuoteTradeCompDef()
+    .accounts({
+      compDefAccount: compDefPDA,
+      payer: owner.publicKey,
+      mxeAccount: getMXEAccAddress(program.programId),
+    })
+    .signers([owner])
+    .rpc({
+      commitment: "confirmed",
+    });
+
+  if (uploadRawCircuit) {
+    const rawCircuit = fs.readFileSync("build/quote_trade.arcis");
+
+    await uploadCircuit(
+      provider as anchor.AnchorProvider,
+      "quote_trade",
+      program.programId,
+      rawCircuit,
+      true
+    );
+  } else {
+    const finalizeTx = await buildFinalizeCompDefTx(
+      provider as anchor.AnchorProvider,
+      Buffer.from(offset).readUInt32LE(),
+      program.programId
+    );
+
+    const latestBlockhash = await provider.connection.getLatestBlockhash();
+    finalizeTx.recentBlockhash = latestBlockhash.blockhash;
+    finalizeTx.lastValidBlockHeight = latestBlockhash.lastValidBlockHeight;
+
+    finalizeTx.sign(owner);
+
+    await provider.sendAndConfirm(finalizeTx);
+  }
+  return sig;
+}
+
+export async function initPlaceLimitOrderCompDef(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  uploadRawCircuit: boolean
+): Promise<string> {
+  const baseSeedCompDefAcc = getArciumAccountBaseSeed(
+    "ComputationDefinitionAccount"
+  );
+  const offset = getCompDefAccOffset("place_limit_order");
+
+  const compDefPDA = PublicKey.findProgramAddressSync(
+    [baseSeedCompDefAcc, program.programId.toBuffer(), offset],
+    getArciumProgAddress()
+  )[0];
+
+  const exists = await checkCompDefAccountExists(provider, compDefPDA);
+  if (exists) {
+    console.log(`Computation definition account ${compDefPDA.toString()} already exists. Skipping initialization.`);
+    return "Account already exists";
+  }
+
+  const sig = await program.methods
+    .initPlaceLimitOrderCompDef()
+    .accounts({
+      compDefAccount: compDefPDA,
+      payer: owner.publicKey,
+      mxeAccount: getMXEAccAddress(program.programId),
+    })
+    .signers([owner])
+    .rpc({
+      commitment: "confirmed",
+    });
+
+  if (uploadRawCircuit) {
+    const rawCircuit = fs.readFileSync("build/place_limit_order.arcis");
+
+    await uploadCircuit(
+      provider as anchor.AnchorProvider,
+      "place_limit_order",
+      program.programId,
+      rawCircuit,
+      true
+    );
+  } else {
+    const finalizeTx = await buildFinalizeCompDefTx(
+      provider as anchor.AnchorProvider,
+      Buffer.from(offset).readUInt32LE(),
+      program.programId
+    );
+
+    const latestBlockhash = await provider.connection.getLatestBlockhash();
+    finalizeTx.recentBlockhash = latestBlockhash.blockhash;
+    finalizeTx.lastValidBlockHeight = latestBlockhash.lastValidBlockHeight;
+
+    finalizeTx.sign(owner);
+
+    await provider.sendAndConfirm(finalizeTx);
+  }
+  return sig;
+}
+
+export async function initMatchOrdersCompDef(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  uploadRawCircuit: boolean
+): Promise<string> {
+  const baseSeedCompDefAcc = getArciumAccountBaseSeed(
+    "ComputationDefinitionAccount"
+  );
+  const offset = getCompDefAccOffset("match_orders");
+
+  const compDefPDA = PublicKey.findProgramAddressSync(
+    [baseSeedCompDefAcc, program.programId.toBuffer(), offset],
+    getArciumProgAddress()
+  )[0];
+
+  const exists = await checkCompDefAccountExists(provider, compDefPDA);
+  if (exists) {
+    console.log(`Computation definition account ${compDefPDA.toString()} already exists. Skipping initialization.`);
+    return "Account already exists";
+  }
+
+  const sig = await program.methods
+    .initMatchOrdersCompDef()
+    .accounts({
+      compDefAccount: compDefPDA,
+      payer: owner.publicKey,
+      mxeAccount: getMXEAccAddress(program.programId),
+    })
+    .signers([owner])
+    .rpc({
+      commitment: "confirmed",
+    });
+
+  if (uploadRawCircuit) {
+    const rawCircuit = fs.readFileSync("build/match_orders.arcis");
+
+    await uploadCircuit(
+      provider as anchor.AnchorProvider,
+      "match_orders",
+      program.programId,
+      rawCircuit,
+      true
+    );
+  } else {
+    const finalizeTx = await buildFinalizeCompDefTx(
+      provider as anchor.AnchorProvider,
+      Buffer.from(offset).readUInt32LE(),
+      program.programId
+    );
+
+    const latestBlockhash = await provider.connection.getLatestBlockhash();
+    finalizeTx.recentBlockhash = latestBlockhash.blockhash;
+    finalizeTx.lastValidBlockHeight = latestBlockhash.lastValidBlockHeight;
+
+    finalizeTx.sign(owner);
+
+    await provider.sendAndConfirm(finalizeTx);
+  }
+  return sig;
+}
+
+export async function initSettleLimitOrderCompDef(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  uploadRawCircuit: boolean
+): Promise<string> {
+  const baseSeedCompDefAcc = getArciumAccountBaseSeed(
+    "ComputationDefinitionAccount"
+  );
+  const offset = getCompDefAccOffset("settle_limit_order");
+
+  const compDefPDA = PublicKey.findProgramAddressSync(
+    [baseSeedCompDefAcc, program.programId.toBuffer(), offset],
+    getArciumProgAddress()
+  )[0];
+
+  const exists = await checkCompDefAccountExists(provider, compDefPDA);
+  if (exists) {
+    console.log(`Computation definition account ${compDefPDA.toString()} already exists. Skipping initialization.`);
+    return "Account already exists";
+  }
+
+  const sig = await program.methods
+    .initSettleLimitOrderCompDef()
+    .accounts({
+      compDefAccount: compDefPDA,
+      payer: owner.publicKey,
+      mxeAccount: getMXEAccAddress(program.programId),
+    })
+    .signers([owner])
+    .rpc({
+      commitment: "confirmed",
+    });
+
+  if (uploadRawCircuit) {
+    const rawCircuit = fs.readFileSync("build/settle_limit_order.arcis");
+
+    await uploadCircuit(
+      provider as anchor.AnchorProvider,
+      "settle_limit_order",
+      program.programId,
+      rawCircuit,
+      true
+    );
+  } else {
+    const finalizeTx = await buildFinalizeCompDefTx(
+      provider as anchor.AnchorProvider,
+      Buffer.from(offset).readUInt32LE(),
+      program.programId
+    );
+
+    const latestBlockhash = await provider.connection.getLatestBlockhash();
+    finalizeTx.recentBlockhash = latestBlockhash.blockhash;
+    finalizeTx.lastValidBlockHeight = latestBlockhash.lastValidBlockHeight;
+
+    finalizeTx.sign(owner);
+
+    await provider.sendAndConfirm(finalizeTx);
+  }
+  return sig;
+}
+
+export async function initMintCompleteSetCompDef(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  uploadRawCircuit: boolean
+): Promise<string> {
+  const baseSeedCompDefAcc = getArciumAccountBaseSeed(
+    "ComputationDefinitionAccount"
+  );
+  const offset = getCompDefAccOffset("mint_complete_set");
+
+  const compDefPDA = PublicKey.findProgramAddressSync(
+    [baseSeedCompDefAcc, program.programId.toBuffer(), offset],
+    getArciumProgAddress()
+  )[0];
+
+  const exists = await checkCompDefAccountExists(provider, compDefPDA);
+  if (exists) {
+    console.log(`Computation definition account ${compDefPDA.toString()} already exists. Skipping initialization.`);
+    return "Account already exists";
+  }
+
+  const sig = await program.methods
+    .initMintCompleteSetCompDef()
+    .accounts({
+      compDefAccount: compDefPDA,
+      payer: owner.publicKey,
+      mxeAccount: getMXEAccAddress(program.programId),
+    })
+    .signers([owner])
+    .rpc({
+      commitment: "confirmed",
+    });
+
+  if (uploadRawCircuit) {
+    const rawCircuit = fs.readFileSync("build/mint_complete_set.arcis");
+
+    await uploadCircuit(
+      provider as anchor.AnchorProvider,
+      "mint_complete_set",
+      program.programId,
+      rawCircuit,
+      true
+    );
+  } else {
+    const finalizeTx = await buildFinalizeCompDefTx(
+      provider as anchor.AnchorProvider,
+      Buffer.from(offset).readUInt32LE(),
+      program.programId
+    );
+
+    const latestBlockhash = await provider.connection.getLatestBlockhash();
+    finalizeTx.recentBlockhash = latestBlockhash.blockhash;
+    finalizeTx.lastValidBlockHeight = latestBlockhash.lastValidBlockHeight;
+
+    finalizeTx.sign(owner);
+
+    await provider.sendAndConfirm(finalizeTx);
+  }
+  return sig;
+}
+
+export async function initRedeemCompleteSetCompDef(
+  provider: anchor.AnchorProvider,
+  program: Program<ArxPredict>,
+  owner: anchor.web3.Keypair,
+  uploadRawCircuit: boolean
+): Promise<string> {
+  const baseSeedCompDefAcc = getArciumAccountBaseSeed(
+    "ComputationDefinitionAccount"
+  );
+  const offset = getCompDefAccOffset("redeem_complete_set");
+
+  const compDefPDA = PublicKey.findProgramAddressSync(
+    [baseSeedCompDefAcc, program.programId.toBuffer(), offset],
+    getArciumProgAddress()
+  )[0];
+
+  const exists = await checkCompDefAccountExists(provider, compDefPDA);
+  if (exists) {
+    console.log(`Computation definition account ${compDefPDA.toString()} already exists. Skipping initialization.`);
+    return "Account already exists";
+  }
+
+  const sig = await program.methods
+    .initRedeemCompleteSetCompDef()
+    .accounts({
+      compDefAccount: compDefPDA,
+      payer: owner.publicKey,
+      mxeAccount: getMXEAccAddress(program.programId),
+    })
+    .signers([owner])
+    .rpc({
+      commitment: "confirmed",
+    });
+
+  if (uploadRawCircuit) {
+    const rawCircuit = fs.readFileSync("build/redeem_complete_set.arcis");
+
+    await uploadCircuit(
+      provider as anchor.AnchorProvider,
+      "redeem_complete_set",
+      program.programId,
+      rawCircuit,
+      true
+    );
+  } else {
+    const finalizeTx = await buildFinalizeCompDefTx(
+      provider as anchor.AnchorProvider,
+      Buffer.from(offset).readUInt32LE(),
+      program.programId
+    );
+
+    const latestBlockhash = await provider.connection.getLatestBlockhash();
+    finalizeTx.recentBlockhash = latestBlockhash.blockhash;
+    finalizeTx.lastValidBlockHeight = latestBlockhash.lastValidBlockHeight;
+
+    finalizeTx.sign(owner);
+
+    await provider.sendAndConfirm(finalizeTx);
+  }
+  return sig;
+}
@@ -27,13 +27,16 @@ import {
   getProbs,
   createUserPosition,
   createMarket as createMarketHelper,
-  sendPayment,
+  deposit,
   buyShares,
   sellShares,
-  withdrawPayment,
+  withdraw,
   settleMarket,
   claimRewards,
   getMXEPublicKeyWithRetry,
+  initializeConfig,
+  getConfigAddress,
+  DEFAULT_CIRCUIT_BASE_URL,
 } from "../client/arcium_helper";
 import {
   initUserPositionCompDef,
@@ -43,6 +46,16 @@ import {
   initBuySharesCompDef,
   initSellSharesCompDef,
   initClaimRewardsCompDef,
+  initBuyWithAmountCompDef,
+  initClaimRewardsScalarCompDef,
+  initViewPositionCompDef,
+  initValuePositionCompDef,
+  initQuoteTradeCompDef,
+  initPlaceLimitOrderCompDef,
+  initMatchOrdersCompDef,
+  initSettleLimitOrderCompDef,
+  initMintCompleteSetCompDef,
+  initRedeemCompleteSetCompDef,
 } from "../client/init_comp_defs";
 import { randomBytes } from "crypto";
 import { Connection, Keypair, PublicKey } from "@solana/web3.js";
@@ -141,9 +154,34 @@ async function initCompDefHelper(setupData: SetupData, initFn: any) {
   }
 }
 
+// Comp defs read the circuit base url from the config, so it has to exist first
+export async function initConfig(setupData: SetupData) {
+  const { provider, program, wallet, mint } = setupData;
+
+  const configAccount = await provider.connection.getAccountInfo(getConfigAddress(program.programId));
+  if (configAccount) {
+    console.log("Config already initialized");
+    return null;
+  }
+  const sig = await initializeConfig(program, wallet, {
+    admins: [wallet.publicKey],
+    allowedMints: [mint],
+    circuitBaseUrl: DEFAULT_CIRCUIT_BASE_URL,
+    checkAdmins: true,
+    checkMints: true,
+    offchainCircuits: true,
+    protocolFeeBps: 50,
+    creatorFeeBps: 50,
+    lpFeeBps: 100,
+  });
+  console.log("Config initialized: ", sig);
+  return sig;
+}
+
 export async function initCompDefs(setupData: SetupData) {
   const { provider, program, wallet } = setupData;
 
+  await initConfig(setupData);
   console.log("Initializing comp defs...");
   const initFnList = [
     initMarketStatsCompDef,
@@ -153,6 +191,23 @@ export async function initCompDefs(setupData: SetupData) {
     initBuySharesCompDef,
     initSellSharesCompDef,
     initClaimRewardsCompDef,
+    initBuyWithAmountCompDef,
+    initBuySharesPrivateCompDef,
+    initSellSharesPrivateCompDef,
+    initInitBatchStatsCompDef,
+    initCommitBatchTradeCompDef,
+    initClearBatchCompDef,
+    initSettleBatchTradeCompDef,
+    initClaimRefundCompDef,
+    initClaimRewardsScalarCompDef,
+    initViewPositionCompDef,
+    initValuePositionCompDef,
+    initQuoteTradeCompDef,
+    initPlaceLimitOrderCompDef,
+    initMatchOrdersCompDef,
+    initSettleLimitOrderCompDef,
+    initMintCompleteSetCompDef,
+    initRedeemCompleteSetCompDef,
   ];
   const sigs = await Promise.all(initFnList.map(initFn => initCompDefHelper(setupData, initFn)));
   console.log("Comp defs initialized: ", sigs);
@@ -270,4 +325,21 @@ export async function uploadCompDefsCircuits(setupData: SetupData) {
   await uploadCircutHelper(setupData, "buy_shares");
   await uploadCircutHelper(setupData, "sell_shares");
   await uploadCircutHelper(setupData, "claim_rewards");
+  await uploadCircutHelper(setupData, "buy_with_amount");
+  await uploadCircutHelper(setupData, "buy_shares_private");
+  await uploadCircutHelper(setupData, "sell_shares_private");
+  await uploadCircutHelper(setupData, "init_batch_stats");
+  await uploadCircutHelper(setupData, "commit_batch_trade");
+  await uploadCircutHelper(setupData, "clear_batch");
+  await uploadCircutHelper(setupData, "settle_batch_trade");
+  await uploadCircutHelper(setupData, "claim_refund");
+  await uploadCircutHelper(setupData, "claim_rewards_scalar");
+  await uploadCircutHelper(setupData, "view_position");
+  await uploadCircutHelper(setupData, "value_position");
+  await uploadCircutHelper(setupData, "quote_trade");
+  await uploadCircutHelper(setupData, "place_limit_order");
+  await uploadCircutHelper(setupData, "match_orders");
+  await uploadCircutHelper(setupData, "settle_limit_order");
+  await uploadCircutHelper(setupData, "mint_complete_set");
+  await uploadCircutHelper(setupData, "redeem_complete_set");
 }
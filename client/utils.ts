@@ -76,4 +76,16 @@ export function transferSol(
     SystemProgram.transfer({ fromPubkey: from.publicKey, toPubkey: to, lamports: amount })
   );
   provider.connection.sendTransaction(transferSol, [from]);
-}
\ No newline at end of file
+}
+
+export function unixNow(): number {
+  return Math.floor(Date.now() / 1000);
+}
+
+// Sleeps until the given unix timestamp, plus a margin for the validator clock
+export async function sleepUntil(timestamp: number, marginSeconds: number = 2) {
+  const waitMs = (timestamp + marginSeconds - unixNow()) * 1000;
+  if (waitMs > 0) {
+    await new Promise(resolve => setTimeout(resolve, waitMs));
+  }
+}
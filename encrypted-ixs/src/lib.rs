@@ -5,57 +5,139 @@ mod circuits {
     use arcis_imports::*;
 
     const SHARES_PER_UNIT: u64 = 1000000;
-    const SHARES_PER_UNIT_INV_F64: f64 = 1.0f64 / SHARES_PER_UNIT as f64;
-    
+    // Must match MAX_OPTIONS in the program, outcomes >= num_options are unused padding
+    const MAX_OPTIONS: usize = 8;
+
+    // LMSR math is done in fixed point with 12 decimals so every node computes the exact same result
+    const FIXED_ONE: u128 = 1_000_000_000_000;
+    const LN_2_FIXED: u128 = 693_147_180_560; // ln(2) * 10^12
+    const EXP_TERMS: usize = 16;
+    const LN_TERMS: usize = 12;
+    // exp() arguments are clamped to MAX_EXP_SHIFT * ln(2) (~27.7), anything above is a rounding error for probs
+    const MAX_EXP_SHIFT: usize = 40;
+    // ln() arguments must be below 2^MAX_LN_SHIFT
+    const MAX_LN_SHIFT: usize = 48;
+
+    // Trade status, must match TRADE_STATUS_* in the program
+    const STATUS_FAILED: u8 = 0;
+    const STATUS_SUCCESS: u8 = 1;
+    const STATUS_SLIPPAGE: u8 = 2;
+
+    // buy_with_amount aims slightly below the budget so fixed point rounding never overshoots it
+    const BUDGET_MARGIN_DIVISOR: u64 = 1_000_000;
+
+    // Must match BPS_DENOMINATOR in the program
+    const BPS_DENOMINATOR: u128 = 10_000;
+
+    // Reveal policies, must match REVEAL_POLICY_* in the program
+    const REVEAL_BUCKETED_VOLUME: u8 = 1;
+    const REVEAL_NOISY_VOLUME: u8 = 2;
+    // Noisy volume adds two-sided geometric noise, the discrete Laplace: k * scale with P(k) proportional to
+    // NOISE_ALPHA^|k|. NOISE_ALPHA is e^-1 out of 2^NOISE_BITS, so each reveal is epsilon = 1 differentially private
+    // for a volume change of `scale` shares (epsilon = change / scale)
+    const NOISE_BITS: usize = 32;
+    const NOISE_ALPHA: u64 = 1_580_030_169; // e^-1 * 2^32
+    // Draws stop after this many steps, a longer run has probability e^-NOISE_MAX_STEPS
+    const NOISE_MAX_STEPS: usize = 24;
+
     pub struct VoteStats {
-        option0: u64,
-        option1: u64,
+        options: [u64; MAX_OPTIONS],
     }
 
     pub struct Probs {
-        option0: f64,
-        option1: f64,
+        options: [u64; MAX_OPTIONS], // FIXED_ONE scaled
     }
 
     pub struct MarketStats {
         vote_stats: VoteStats,
         probs: Probs,
-        cost: f64,
+        cost: u128, // FIXED_ONE scaled token units
+        total_cost_basis: u64, // sum of every position's cost basis, refunds are scaled down when the vault can't cover it
     }
 
     pub struct UserPosition {
-        option0: u64,
-        option1: u64,
+        options: [u64; MAX_OPTIONS],
+        cost_basis: [u64; MAX_OPTIONS], // net amount paid per outcome in token base units, refunded if the market is voided
+    }
+
+    // What a position would fetch if it were sold in full right now, net of the sell fee, next to what was paid for it
+    pub struct PositionValue {
+        value: u64,
+        cost_basis: u64,
+    }
+
+    // Price of trading `shares` of one outcome against the current market, fees included
+    pub struct TradeQuote {
+        buy_cost: u64,
+        sell_proceeds: u64, // zero when the market doesn't hold that many shares of the outcome
     }
 
     pub struct UserVote {
         option: u8,
     }
 
+    pub struct UserTrade {
+        option: u8,
+        shares: u64,
+    }
+
+    // Buy order resting until the marginal price of its outcome is at or below limit_price, FIXED_ONE scaled.
+    // It fills all or nothing and only once, filled_shares and cost stay zero until then
+    pub struct LimitOrder {
+        option: u8,
+        shares: u64,
+        limit_price: u64,
+        budget: u64, // escrow net of the fee, the fill's cost can't exceed it
+        filled_shares: u64,
+        cost: u64,
+    }
+
+    pub struct LimitOrderInput {
+        option: u8,
+        shares: u64,
+        limit_price: u64,
+    }
+
+    // Encrypted totals of a batch, budgets are summed per outcome until the batch is cleared
+    // and shares holds what each outcome's budget bought at the clearing
+    pub struct BatchStats {
+        budgets: [u64; MAX_OPTIONS],
+        shares: [u64; MAX_OPTIONS],
+    }
+
     #[instruction]
-    pub fn init_market_stats(mxe: Mxe, liquidity_parameter: u64) -> Enc<Mxe, MarketStats> {
-        let vote_stats = VoteStats { 
-            option0: 0,
-            option1: 0,
+    pub fn init_market_stats(
+        mxe: Mxe,
+        liquidity_parameter: u64,
+        num_options: u8,
+    ) -> Enc<Mxe, MarketStats> {
+        let vote_stats = VoteStats {
+            options: [0; MAX_OPTIONS],
         };
-        let probs = Probs {
-            option0: 0.5,
-            option1: 0.5,
+        let mut probs = Probs {
+            options: [0; MAX_OPTIONS],
         };
-        let cost = (liquidity_parameter as f64) * (2.0f64).ln(); //TODO fixed 2 as number of options is 2
+        let uniform_prob = (FIXED_ONE / num_options as u128) as u64;
+        for i in 0..MAX_OPTIONS {
+            if (i as u8) < num_options {
+                probs.options[i] = uniform_prob;
+            }
+        }
+        let cost = (liquidity_parameter as u128) * ln_fixed((num_options as u128) * FIXED_ONE);
         let market_stats = MarketStats {
             vote_stats,
             probs,
             cost,
+            total_cost_basis: 0,
         };
         mxe.from_arcis(market_stats)
     }
 
     #[instruction]
     pub fn init_user_position(mxe: Mxe) -> Enc<Mxe, UserPosition> {
-        let user_position = UserPosition { 
-            option0: 0,
-            option1: 0,
+        let user_position = UserPosition {
+            options: [0; MAX_OPTIONS],
+            cost_basis: [0; MAX_OPTIONS],
         };
         mxe.from_arcis(user_position)
     }
@@ -64,35 +146,88 @@ mod circuits {
     pub fn buy_shares(
         vote_ctxt: Enc<Shared, UserVote>,
         shares: u64,
+        max_cost: u64,
+        balance: u64, // Funds the program escrowed for this trade
+        trade_seq: u64, // Echoed back so the program can drop results of a trade it already released
+        market_version: u64, // Echoed back so the program can drop results computed on outdated market stats
         liquidity_parameter: u64,
+        num_options: u8,
+        token_unit: u64,
         market_stats_ctxt: Enc<Mxe, MarketStats>,
         user_position_ctxt: Enc<Mxe, UserPosition>,
     ) -> (
-        Enc<Mxe, MarketStats>, 
-        Enc<Mxe, UserPosition>, 
-        f64, // Amount to pay
+        Enc<Mxe, MarketStats>,
+        Enc<Mxe, UserPosition>,
+        u64, // Amount to pay in token base units
+        u8, // Status
+        u64, // Trade sequence
+        u64, // Market version
     ) {
         let user_vote = vote_ctxt.to_arcis();
-        let mut market_stats = market_stats_ctxt.to_arcis();
-        let mut user_position = user_position_ctxt.to_arcis();
+        let (market_stats, user_position, amount, status) = execute_buy(
+            market_stats_ctxt.to_arcis(),
+            user_position_ctxt.to_arcis(),
+            user_vote.option,
+            shares,
+            max_cost,
+            balance,
+            liquidity_parameter,
+            num_options,
+            token_unit,
+        );
 
-        if user_vote.option == 0 {
-            market_stats.vote_stats.option0 += shares;
-            user_position.option0 += shares;
-        } else if user_vote.option == 1 {
-            market_stats.vote_stats.option1 += shares;
-            user_position.option1 += shares;
-        }
+        (
+            market_stats_ctxt.owner.from_arcis(market_stats),
+            user_position_ctxt.owner.from_arcis(user_position),
+            amount.reveal(),
+            status.reveal(),
+            trade_seq.reveal(),
+            market_version.reveal(),
+        )
+    }
 
-        let (probs, cost) = cal_prob(&market_stats.vote_stats, &liquidity_parameter);
-        let amount = cost - market_stats.cost;
-        market_stats.probs = probs;
-        market_stats.cost = cost;
+    // Same as buy_shares but the share count is encrypted along with the option,
+    // only the resulting payment is revealed
+    #[instruction]
+    pub fn buy_shares_private(
+        trade_ctxt: Enc<Shared, UserTrade>,
+        max_cost: u64,
+        balance: u64,
+        trade_seq: u64,
+        market_version: u64,
+        liquidity_parameter: u64,
+        num_options: u8,
+        token_unit: u64,
+        market_stats_ctxt: Enc<Mxe, MarketStats>,
+        user_position_ctxt: Enc<Mxe, UserPosition>,
+    ) -> (
+        Enc<Mxe, MarketStats>,
+        Enc<Mxe, UserPosition>,
+        u64, // Amount to pay in token base units
+        u8, // Status
+        u64, // Trade sequence
+        u64, // Market version
+    ) {
+        let user_trade = trade_ctxt.to_arcis();
+        let (market_stats, user_position, amount, status) = execute_buy(
+            market_stats_ctxt.to_arcis(),
+            user_position_ctxt.to_arcis(),
+            user_trade.option,
+            user_trade.shares,
+            max_cost,
+            balance,
+            liquidity_parameter,
+            num_options,
+            token_unit,
+        );
 
         (
-            market_stats_ctxt.owner.from_arcis(market_stats), 
+            market_stats_ctxt.owner.from_arcis(market_stats),
             user_position_ctxt.owner.from_arcis(user_position),
-            amount.reveal()
+            amount.reveal(),
+            status.reveal(),
+            trade_seq.reveal(),
+            market_version.reveal(),
         )
     }
 
@@ -100,123 +235,1105 @@ mod circuits {
     pub fn sell_shares(
         vote_ctxt: Enc<Shared, UserVote>,
         shares: u64,
+        min_proceeds: u64,
+        trade_seq: u64,
+        market_version: u64,
         liquidity_parameter: u64,
+        num_options: u8,
+        token_unit: u64,
         market_stats_ctxt: Enc<Mxe, MarketStats>,
         user_position_ctxt: Enc<Mxe, UserPosition>,
     ) -> (
-        Enc<Mxe, MarketStats>, 
-        Enc<Mxe, UserPosition>, 
-        f64, // Amount to pay
+        Enc<Mxe, MarketStats>,
+        Enc<Mxe, UserPosition>,
+        u64, // Amount to receive in token base units
         u8, // Status
+        u64, // Trade sequence
+        u64, // Market version
     ) {
         let user_vote = vote_ctxt.to_arcis();
-        let mut market_stats = market_stats_ctxt.to_arcis();
-        let mut user_position = user_position_ctxt.to_arcis();
-        let mut status: u8 = 1;
+        let (market_stats, user_position, amount, status) = execute_sell(
+            market_stats_ctxt.to_arcis(),
+            user_position_ctxt.to_arcis(),
+            user_vote.option,
+            shares,
+            min_proceeds,
+            liquidity_parameter,
+            num_options,
+            token_unit,
+        );
 
-        if user_vote.option == 0 {
-            if user_position.option0 < shares {
-                status = 0;
-            } else {
-                market_stats.vote_stats.option0 -= shares;
-                user_position.option0 -= shares;
+        (
+            market_stats_ctxt.owner.from_arcis(market_stats),
+            user_position_ctxt.owner.from_arcis(user_position),
+            amount.reveal(),
+            status.reveal(),
+            trade_seq.reveal(),
+            market_version.reveal(),
+        )
+    }
+
+    #[instruction]
+    pub fn sell_shares_private(
+        trade_ctxt: Enc<Shared, UserTrade>,
+        min_proceeds: u64,
+        trade_seq: u64,
+        market_version: u64,
+        liquidity_parameter: u64,
+        num_options: u8,
+        token_unit: u64,
+        market_stats_ctxt: Enc<Mxe, MarketStats>,
+        user_position_ctxt: Enc<Mxe, UserPosition>,
+    ) -> (
+        Enc<Mxe, MarketStats>,
+        Enc<Mxe, UserPosition>,
+        u64, // Amount to receive in token base units
+        u8, // Status
+        u64, // Trade sequence
+        u64, // Market version
+    ) {
+        let user_trade = trade_ctxt.to_arcis();
+        let (market_stats, user_position, amount, status) = execute_sell(
+            market_stats_ctxt.to_arcis(),
+            user_position_ctxt.to_arcis(),
+            user_trade.option,
+            user_trade.shares,
+            min_proceeds,
+            liquidity_parameter,
+            num_options,
+            token_unit,
+        );
+
+        (
+            market_stats_ctxt.owner.from_arcis(market_stats),
+            user_position_ctxt.owner.from_arcis(user_position),
+            amount.reveal(),
+            status.reveal(),
+            trade_seq.reveal(),
+            market_version.reveal(),
+        )
+    }
+
+    fn execute_buy(
+        market_stats: MarketStats,
+        user_position: UserPosition,
+        option: u8,
+        shares: u64,
+        max_cost: u64,
+        balance: u64,
+        liquidity_parameter: u64,
+        num_options: u8,
+        token_unit: u64,
+    ) -> (MarketStats, UserPosition, u64, u8) {
+        let mut market_stats = market_stats;
+        let mut user_position = user_position;
+
+        let mut vote_stats = VoteStats {
+            options: market_stats.vote_stats.options,
+        };
+        for i in 0..MAX_OPTIONS {
+            if option == i as u8 && option < num_options {
+                vote_stats.options[i] += shares;
             }
-        } else if user_vote.option == 1 {
-            if user_position.option1 < shares {
-                status = 0;
-            } else {
-                market_stats.vote_stats.option1 -= shares;
-                user_position.option1 -= shares;
+        }
+
+        let (probs, cost) = cal_prob(&vote_stats, liquidity_parameter, num_options);
+        let mut cost_delta: u128 = 0;
+        if cost > market_stats.cost {
+            cost_delta = cost - market_stats.cost;
+        }
+        // Buyer pays rounded up
+        let amount = to_token_amount_ceil(cost_delta, token_unit);
+
+        // Market and position are returned untouched when the buyer can't pay or the price moved past their limit
+        let mut status = STATUS_SUCCESS;
+        if amount > balance {
+            status = STATUS_FAILED;
+        }
+        if amount > max_cost {
+            status = STATUS_SLIPPAGE;
+        }
+        if status == STATUS_SUCCESS {
+            market_stats.vote_stats = vote_stats;
+            market_stats.probs = probs;
+            market_stats.cost = cost;
+            market_stats.total_cost_basis += amount;
+            for i in 0..MAX_OPTIONS {
+                if option == i as u8 && option < num_options {
+                    user_position.options[i] += shares;
+                    user_position.cost_basis[i] += amount;
+                }
+            }
+        }
+
+        (market_stats, user_position, amount, status)
+    }
+
+    fn execute_sell(
+        market_stats: MarketStats,
+        user_position: UserPosition,
+        option: u8,
+        shares: u64,
+        min_proceeds: u64,
+        liquidity_parameter: u64,
+        num_options: u8,
+        token_unit: u64,
+    ) -> (MarketStats, UserPosition, u64, u8) {
+        let mut market_stats = market_stats;
+        let mut user_position = user_position;
+        let mut status = STATUS_FAILED;
+
+        let mut vote_stats = VoteStats {
+            options: market_stats.vote_stats.options,
+        };
+        for i in 0..MAX_OPTIONS {
+            // Shares minted as complete sets aren't in the stats, the market maker only takes back what it issued
+            if option == i as u8
+                && option < num_options
+                && user_position.options[i] >= shares
+                && vote_stats.options[i] >= shares
+            {
+                vote_stats.options[i] -= shares;
+                status = STATUS_SUCCESS;
             }
         }
 
-        let mut amount = 0.0;
+        let (probs, cost) = cal_prob(&vote_stats, liquidity_parameter, num_options);
+        let mut cost_delta: u128 = 0;
+        if market_stats.cost > cost {
+            cost_delta = market_stats.cost - cost;
+        }
+        // Seller receives rounded down
+        let mut amount = to_token_amount_floor(cost_delta, token_unit);
+        if status == STATUS_SUCCESS && amount < min_proceeds {
+            status = STATUS_SLIPPAGE;
+        }
+
         // Only update stats if shares were actually sold
-        if status == 1 {
-            let (probs, cost) = cal_prob(&market_stats.vote_stats, &liquidity_parameter);
-            amount = cost - market_stats.cost;
+        if status == STATUS_SUCCESS {
+            market_stats.vote_stats = vote_stats;
             market_stats.probs = probs;
             market_stats.cost = cost;
+            for i in 0..MAX_OPTIONS {
+                if option == i as u8 {
+                    user_position.options[i] -= shares;
+                    // Proceeds above what was paid are profit, the cost basis bottoms out at zero
+                    let mut basis_delta = amount;
+                    if basis_delta > user_position.cost_basis[i] {
+                        basis_delta = user_position.cost_basis[i];
+                    }
+                    user_position.cost_basis[i] -= basis_delta;
+                    market_stats.total_cost_basis -= basis_delta;
+                }
+            }
+        } else {
+            // Nothing was sold, revealing the proceeds would leak what the shares were worth
+            amount = 0;
+        }
+
+        (market_stats, user_position, amount, status)
+    }
+
+    #[instruction]
+    pub fn buy_with_amount(
+        vote_ctxt: Enc<Shared, UserVote>,
+        budget: u64,
+        trade_seq: u64,
+        market_version: u64,
+        liquidity_parameter: u64,
+        num_options: u8,
+        token_unit: u64,
+        market_stats_ctxt: Enc<Mxe, MarketStats>,
+        user_position_ctxt: Enc<Mxe, UserPosition>,
+    ) -> (
+        Enc<Mxe, MarketStats>,
+        Enc<Mxe, UserPosition>,
+        u64, // Amount to pay in token base units, <= budget
+        u8, // Status
+        u64, // Trade sequence
+        u64, // Market version
+    ) {
+        let user_vote = vote_ctxt.to_arcis();
+        let mut market_stats = market_stats_ctxt.to_arcis();
+        let mut user_position = user_position_ctxt.to_arcis();
+
+        let shares = shares_for_amount(
+            &market_stats.probs,
+            user_vote.option,
+            budget,
+            liquidity_parameter,
+            token_unit,
+        );
+
+        let mut vote_stats = VoteStats {
+            options: market_stats.vote_stats.options,
+        };
+        for i in 0..MAX_OPTIONS {
+            if user_vote.option == i as u8 && user_vote.option < num_options {
+                vote_stats.options[i] += shares;
+            }
+        }
+
+        let (probs, cost) = cal_prob(&vote_stats, liquidity_parameter, num_options);
+        let mut cost_delta: u128 = 0;
+        if cost > market_stats.cost {
+            cost_delta = cost - market_stats.cost;
+        }
+        let amount = to_token_amount_ceil(cost_delta, token_unit);
+
+        let mut status = STATUS_FAILED;
+        if shares > 0 && amount <= budget && user_vote.option < num_options {
+            status = STATUS_SUCCESS;
+            market_stats.vote_stats = vote_stats;
+            market_stats.probs = probs;
+            market_stats.cost = cost;
+            market_stats.total_cost_basis += amount;
+            for i in 0..MAX_OPTIONS {
+                if user_vote.option == i as u8 {
+                    user_position.options[i] += shares;
+                    user_position.cost_basis[i] += amount;
+                }
+            }
         }
 
         (
-            market_stats_ctxt.owner.from_arcis(market_stats), 
+            market_stats_ctxt.owner.from_arcis(market_stats),
             user_position_ctxt.owner.from_arcis(user_position),
             amount.reveal(),
             status.reveal(),
+            trade_seq.reveal(),
+            market_version.reveal(),
+        )
+    }
+
+    #[instruction]
+    pub fn init_batch_stats(mxe: Mxe) -> Enc<Mxe, BatchStats> {
+        let batch_stats = BatchStats {
+            budgets: [0; MAX_OPTIONS],
+            shares: [0; MAX_OPTIONS],
+        };
+        mxe.from_arcis(batch_stats)
+    }
+
+    // Adds a committed budget to the encrypted per outcome totals, the side never leaves the batch
+    #[instruction]
+    pub fn commit_batch_trade(
+        vote_ctxt: Enc<Shared, UserVote>,
+        amount: u64,
+        num_options: u8,
+        batch_stats_ctxt: Enc<Mxe, BatchStats>,
+    ) -> Enc<Mxe, BatchStats> {
+        let user_vote = vote_ctxt.to_arcis();
+        let mut batch_stats = batch_stats_ctxt.to_arcis();
+
+        for i in 0..MAX_OPTIONS {
+            if user_vote.option == i as u8 && user_vote.option < num_options {
+                batch_stats.budgets[i] += amount;
+            }
+        }
+
+        batch_stats_ctxt.owner.from_arcis(batch_stats)
+    }
+
+    // Spends every outcome's total budget against the market in one step, so all buyers of an
+    // outcome get the same average price and only the batch's total cost is revealed
+    #[instruction]
+    pub fn clear_batch(
+        liquidity_parameter: u64,
+        num_options: u8,
+        token_unit: u64,
+        market_version: u64,
+        batch_stats_ctxt: Enc<Mxe, BatchStats>,
+        market_stats_ctxt: Enc<Mxe, MarketStats>,
+    ) -> (
+        Enc<Mxe, MarketStats>,
+        Enc<Mxe, BatchStats>,
+        u64, // Total amount paid by the batch in token base units
+        u8, // Status
+        u64, // Market version
+    ) {
+        let mut batch_stats = batch_stats_ctxt.to_arcis();
+        let mut market_stats = market_stats_ctxt.to_arcis();
+
+        let mut vote_stats = VoteStats {
+            options: market_stats.vote_stats.options,
+        };
+        let mut probs = Probs {
+            options: market_stats.probs.options,
+        };
+        let mut cost = market_stats.cost;
+        let mut shares = [0u64; MAX_OPTIONS];
+        let mut total_budget: u64 = 0;
+
+        // LMSR cost is path independent, so buying outcome by outcome adds up to the same total
+        for i in 0..MAX_OPTIONS {
+            let outcome_shares = shares_for_amount(
+                &probs,
+                i as u8,
+                batch_stats.budgets[i],
+                liquidity_parameter,
+                token_unit,
+            );
+            if (i as u8) < num_options {
+                vote_stats.options[i] += outcome_shares;
+                shares[i] = outcome_shares;
+                total_budget += batch_stats.budgets[i];
+            }
+            let (new_probs, new_cost) = cal_prob(&vote_stats, liquidity_parameter, num_options);
+            probs = new_probs;
+            cost = new_cost;
+        }
+
+        let mut cost_delta: u128 = 0;
+        if cost > market_stats.cost {
+            cost_delta = cost - market_stats.cost;
+        }
+        let amount = to_token_amount_ceil(cost_delta, token_unit);
+
+        let mut status = STATUS_FAILED;
+        if amount <= total_budget {
+            status = STATUS_SUCCESS;
+            market_stats.vote_stats = vote_stats;
+            market_stats.probs = probs;
+            market_stats.cost = cost;
+            // Each ticket's whole budget becomes cost basis when it is settled
+            market_stats.total_cost_basis += total_budget;
+            batch_stats.shares = shares;
+        }
+
+        (
+            market_stats_ctxt.owner.from_arcis(market_stats),
+            batch_stats_ctxt.owner.from_arcis(batch_stats),
+            amount.reveal(),
+            status.reveal(),
+            market_version.reveal(),
+        )
+    }
+
+    // Credits a cleared batch trade with its pro rata part of the outcome's shares, rounded down
+    #[instruction]
+    pub fn settle_batch_trade(
+        vote_ctxt: Enc<Shared, UserVote>,
+        amount: u64,
+        trade_seq: u64,
+        batch_stats_ctxt: Enc<Mxe, BatchStats>,
+        user_position_ctxt: Enc<Mxe, UserPosition>,
+    ) -> (
+        Enc<Mxe, UserPosition>,
+        u64, // Trade sequence
+    ) {
+        let user_vote = vote_ctxt.to_arcis();
+        let batch_stats = batch_stats_ctxt.to_arcis();
+        let mut user_position = user_position_ctxt.to_arcis();
+
+        for i in 0..MAX_OPTIONS {
+            let mut budget = batch_stats.budgets[i] as u128;
+            if budget == 0 {
+                budget = 1;
+            }
+            if user_vote.option == i as u8 {
+                user_position.options[i] += ((amount as u128 * batch_stats.shares[i] as u128) / budget) as u64;
+                user_position.cost_basis[i] += amount;
+            }
+        }
+
+        (
+            user_position_ctxt.owner.from_arcis(user_position),
+            trade_seq.reveal(),
+        )
+    }
+
+    #[instruction]
+    pub fn place_limit_order(
+        order_ctxt: Enc<Shared, LimitOrderInput>,
+        budget: u64,
+        mxe: Mxe,
+    ) -> Enc<Mxe, LimitOrder> {
+        let order_input = order_ctxt.to_arcis();
+        let order = LimitOrder {
+            option: order_input.option,
+            shares: order_input.shares,
+            limit_price: order_input.limit_price,
+            budget,
+            filled_shares: 0,
+            cost: 0,
+        };
+        mxe.from_arcis(order)
+    }
+
+    // Walks up to four orders, MAX_MATCH_ORDERS in the program, in the given order. Each fill moves the market
+    // before the next one is checked. Orders at or past num_orders are padding and come back untouched, only the total
+    // amount the fills cost is revealed
+    #[instruction]
+    pub fn match_orders(
+        market_version: u64,
+        liquidity_parameter: u64,
+        num_options: u8,
+        token_unit: u64,
+        num_orders: u8,
+        market_stats_ctxt: Enc<Mxe, MarketStats>,
+        order_0_ctxt: Enc<Mxe, LimitOrder>,
+        order_1_ctxt: Enc<Mxe, LimitOrder>,
+        order_2_ctxt: Enc<Mxe, LimitOrder>,
+        order_3_ctxt: Enc<Mxe, LimitOrder>,
+    ) -> (
+        Enc<Mxe, MarketStats>,
+        Enc<Mxe, LimitOrder>,
+        Enc<Mxe, LimitOrder>,
+        Enc<Mxe, LimitOrder>,
+        Enc<Mxe, LimitOrder>,
+        u64, // Total amount the filled orders cost in token base units
+        u64, // Market version
+    ) {
+        let market_stats = market_stats_ctxt.to_arcis();
+        let (market_stats, order_0, amount_0) = fill_limit_order(
+            market_stats, order_0_ctxt.to_arcis(), 0 < num_orders, liquidity_parameter, num_options, token_unit,
+        );
+        let (market_stats, order_1, amount_1) = fill_limit_order(
+            market_stats, order_1_ctxt.to_arcis(), 1 < num_orders, liquidity_parameter, num_options, token_unit,
+        );
+        let (market_stats, order_2, amount_2) = fill_limit_order(
+            market_stats, order_2_ctxt.to_arcis(), 2 < num_orders, liquidity_parameter, num_options, token_unit,
+        );
+        let (market_stats, order_3, amount_3) = fill_limit_order(
+            market_stats, order_3_ctxt.to_arcis(), 3 < num_orders, liquidity_parameter, num_options, token_unit,
+        );
+        let amount = amount_0 + amount_1 + amount_2 + amount_3;
+
+        (
+            market_stats_ctxt.owner.from_arcis(market_stats),
+            order_0_ctxt.owner.from_arcis(order_0),
+            order_1_ctxt.owner.from_arcis(order_1),
+            order_2_ctxt.owner.from_arcis(order_2),
+            order_3_ctxt.owner.from_arcis(order_3),
+            amount.reveal(),
+            market_version.reveal(),
         )
     }
 
+    // Buys the order's shares when its outcome's current price is within the limit and the cost within its budget
+    fn fill_limit_order(
+        market_stats: MarketStats,
+        order: LimitOrder,
+        active: bool,
+        liquidity_parameter: u64,
+        num_options: u8,
+        token_unit: u64,
+    ) -> (MarketStats, LimitOrder, u64) {
+        let mut market_stats = market_stats;
+        let mut order = order;
 
-    fn cal_prob(vote_stats: &VoteStats, liquidity_parameter: &u64) -> (Probs, f64) {
-        // let exp0 = (vote_stats.option0 as f64 / *liquidity_parameter as f64).exp();
-        // let exp1 = (vote_stats.option1 as f64 / *liquidity_parameter as f64).exp();
-        // let sum_exp = exp0 + exp1;
+        let mut price: u64 = 0;
+        let mut vote_stats = VoteStats {
+            options: market_stats.vote_stats.options,
+        };
+        for i in 0..MAX_OPTIONS {
+            if order.option == i as u8 && order.option < num_options {
+                price = market_stats.probs.options[i];
+                vote_stats.options[i] += order.shares;
+            }
+        }
 
-        let liquidity_inverse = 1.0f64 / *liquidity_parameter as f64;
-        let x0 = ((vote_stats.option0 as f64) * SHARES_PER_UNIT_INV_F64) * liquidity_inverse;
-        let x1 = ((vote_stats.option1 as f64) * SHARES_PER_UNIT_INV_F64) * liquidity_inverse;
-        
-        // Subtract max for numerical stability
-        let max_x = x0.max(x1);
-        let exp0 = (x0 - max_x).exp();
-        let exp1 = (x1 - max_x).exp();
-        let sum_exp = exp0 + exp1;
+        let (probs, cost) = cal_prob(&vote_stats, liquidity_parameter, num_options);
+        let mut cost_delta: u128 = 0;
+        if cost > market_stats.cost {
+            cost_delta = cost - market_stats.cost;
+        }
+        let amount = to_token_amount_ceil(cost_delta, token_unit);
+
+        let mut filled: u64 = 0;
+        if active
+            && order.filled_shares == 0
+            && order.shares > 0
+            && order.option < num_options
+            && price <= order.limit_price
+            && amount <= order.budget
+        {
+            market_stats.vote_stats = vote_stats;
+            market_stats.probs = probs;
+            market_stats.cost = cost;
+            // The cost becomes the owner's cost basis when the order is settled
+            market_stats.total_cost_basis += amount;
+            order.filled_shares = order.shares;
+            order.cost = amount;
+            filled = amount;
+        }
+
+        (market_stats, order, filled)
+    }
+
+    // Moves a filled order's shares and cost into its owner's position, an unfilled order adds nothing and a
+    // claimed position takes no fill. Only the cost is revealed, the rest of the escrow goes back to the owner
+    #[instruction]
+    pub fn settle_limit_order(
+        trade_seq: u64,
+        position_claimed: bool,
+        order_ctxt: Enc<Mxe, LimitOrder>,
+        user_position_ctxt: Enc<Mxe, UserPosition>,
+    ) -> (
+        Enc<Mxe, UserPosition>,
+        u64, // Cost of the fill in token base units
+        u64, // Trade sequence
+    ) {
+        let order = order_ctxt.to_arcis();
+        let mut user_position = user_position_ctxt.to_arcis();
+
+        for i in 0..MAX_OPTIONS {
+            if order.option == i as u8 && !position_claimed {
+                user_position.options[i] += order.filled_shares;
+                user_position.cost_basis[i] += order.cost;
+            }
+        }
 
         (
-            Probs {
-                option0: exp0/sum_exp,
-                option1: exp1/sum_exp,
-            }, 
-            (*liquidity_parameter as f64) * (sum_exp.ln() + max_x)
+            user_position_ctxt.owner.from_arcis(user_position),
+            order.cost.reveal(),
+            trade_seq.reveal(),
         )
     }
 
+    // Credits `shares` of every outcome for `amount` of collateral the program already took. Prices and vote stats
+    // are untouched, the collateral becomes cost basis spread over the outcomes so a voided market refunds it
+    // through claim_refund like any other purchase
     #[instruction]
-    pub fn reveal_probs(market_stats_ctxt: Enc<Mxe, MarketStats>) -> (
-        [f64; 2], // probs
-        [u64; 2], // vote stats
+    pub fn mint_complete_set(
+        num_options: u8,
+        shares: u64,
+        amount: u64,
+        trade_seq: u64,
+        market_version: u64,
+        market_stats_ctxt: Enc<Mxe, MarketStats>,
+        user_position_ctxt: Enc<Mxe, UserPosition>,
+    ) -> (
+        Enc<Mxe, MarketStats>,
+        Enc<Mxe, UserPosition>,
+        u64, // Shares minted of every outcome
+        u64, // Trade sequence
+        u64, // Market version
+    ) {
+        let mut market_stats = market_stats_ctxt.to_arcis();
+        let mut user_position = user_position_ctxt.to_arcis();
+        let basis_per_option = amount / num_options as u64;
+        for i in 0..MAX_OPTIONS {
+            if (i as u8) < num_options {
+                user_position.options[i] += shares;
+                user_position.cost_basis[i] += basis_per_option;
+            }
+        }
+        // The rounding remainder goes to the first outcome so the basis adds up to the collateral
+        user_position.cost_basis[0] += amount - basis_per_option * num_options as u64;
+        market_stats.total_cost_basis += amount;
+
+        (
+            market_stats_ctxt.owner.from_arcis(market_stats),
+            user_position_ctxt.owner.from_arcis(user_position),
+            shares.reveal(),
+            trade_seq.reveal(),
+            market_version.reveal(),
+        )
+    }
+
+    // Burns `shares` of every outcome for `payout` tokens, failing unless the position holds all of them.
+    // The payout comes off the cost basis like sale proceeds, spread over the outcomes and bottoming out at zero,
+    // and total_cost_basis drops by the same amount so refunds stay scaled to what positions still hold
+    #[instruction]
+    pub fn redeem_complete_set(
+        num_options: u8,
+        shares: u64,
+        payout: u64,
+        trade_seq: u64,
+        market_version: u64,
+        market_stats_ctxt: Enc<Mxe, MarketStats>,
+        user_position_ctxt: Enc<Mxe, UserPosition>,
+    ) -> (
+        Enc<Mxe, MarketStats>,
+        Enc<Mxe, UserPosition>,
+        u8, // Status
+        u64, // Shares burned of every outcome
+        u64, // Trade sequence
+        u64, // Market version
+    ) {
+        let mut market_stats = market_stats_ctxt.to_arcis();
+        let mut user_position = user_position_ctxt.to_arcis();
+        let mut holds_set = true;
+        for i in 0..MAX_OPTIONS {
+            if (i as u8) < num_options && user_position.options[i] < shares {
+                holds_set = false;
+            }
+        }
+
+        let basis_delta_per_option = payout / num_options as u64;
+        // The rounding remainder comes off the first outcome so the deltas add up to the payout, like the mint
+        let remainder = payout - basis_delta_per_option * num_options as u64;
+        if holds_set {
+            for i in 0..MAX_OPTIONS {
+                if (i as u8) < num_options {
+                    user_position.options[i] -= shares;
+                    let mut basis_delta = basis_delta_per_option;
+                    if i == 0 {
+                        basis_delta += remainder;
+                    }
+                    if basis_delta > user_position.cost_basis[i] {
+                        basis_delta = user_position.cost_basis[i];
+                    }
+                    user_position.cost_basis[i] -= basis_delta;
+                    market_stats.total_cost_basis -= basis_delta;
+                }
+            }
+        }
+        let status = if holds_set { STATUS_SUCCESS } else { STATUS_FAILED };
+
+        (
+            market_stats_ctxt.owner.from_arcis(market_stats),
+            user_position_ctxt.owner.from_arcis(user_position),
+            status.reveal(),
+            shares.reveal(),
+            trade_seq.reveal(),
+            market_version.reveal(),
+        )
+    }
+
+    // Largest share count whose LMSR cost fits in the budget.
+    // Spending B on outcome i moves its price from p_i, solving C(q + s) - C(q) = B gives
+    // s = b * ln(1 + (e^(B/b) - 1) / p_i)
+    fn shares_for_amount(
+        probs: &Probs,
+        option: u8,
+        budget: u64,
+        liquidity_parameter: u64,
+        token_unit: u64,
+    ) -> u64 {
+        let mut p: u128 = 0;
+        for i in 0..MAX_OPTIONS {
+            if option == i as u8 {
+                p = probs.options[i] as u128;
+            }
+        }
+        if p == 0 {
+            p = 1;
+        }
+
+        let mut target = budget - budget / BUDGET_MARGIN_DIVISOR;
+        if target > 0 {
+            target -= 1;
+        }
+        let target_fixed = (target as u128) * FIXED_ONE / token_unit as u128;
+
+        let growth = exp_fixed(target_fixed / liquidity_parameter as u128);
+        let mut ratio = FIXED_ONE + (growth - FIXED_ONE) * FIXED_ONE / p;
+        let max_ratio = (1u128 << (MAX_LN_SHIFT - 1)) * FIXED_ONE;
+        if ratio > max_ratio {
+            ratio = max_ratio;
+        }
+
+        let shares_fixed = (liquidity_parameter as u128) * ln_fixed(ratio);
+        (shares_fixed * SHARES_PER_UNIT as u128 / FIXED_ONE) as u64
+    }
+
+    fn cal_prob(vote_stats: &VoteStats, liquidity_parameter: u64, num_options: u8) -> (Probs, u128) {
+        // x_i = q_i / b, with q_i scaled by SHARES_PER_UNIT
+        let x_scale = FIXED_ONE / SHARES_PER_UNIT as u128;
+        let mut x = [0u128; MAX_OPTIONS];
+        for i in 0..MAX_OPTIONS {
+            x[i] = (vote_stats.options[i] as u128) * x_scale / liquidity_parameter as u128;
+        }
+
+        // Subtract max for numerical stability, unused outcomes never move so x = 0 for them
+        let mut max_x = x[0];
+        for i in 1..MAX_OPTIONS {
+            if x[i] > max_x {
+                max_x = x[i];
+            }
+        }
+
+        let mut exps = [0u128; MAX_OPTIONS];
+        let mut sum_exp: u128 = 0;
+        for i in 0..MAX_OPTIONS {
+            if (i as u8) < num_options {
+                exps[i] = exp_neg_fixed(max_x - x[i]);
+                sum_exp += exps[i];
+            }
+        }
+
+        let mut probs = Probs {
+            options: [0; MAX_OPTIONS],
+        };
+        for i in 0..MAX_OPTIONS {
+            probs.options[i] = (exps[i] * FIXED_ONE / sum_exp) as u64;
+        }
+
+        (probs, (liquidity_parameter as u128) * (max_x + ln_fixed(sum_exp)))
+    }
+
+    // e^x for a FIXED_ONE scaled x >= 0: e^x = 2^k * e^r with x = k * ln(2) + r
+    fn exp_fixed(x: u128) -> u128 {
+        let mut clamped = x;
+        if clamped > (MAX_EXP_SHIFT as u128) * LN_2_FIXED {
+            clamped = (MAX_EXP_SHIFT as u128) * LN_2_FIXED;
+        }
+        let k = clamped / LN_2_FIXED;
+        let r = clamped - k * LN_2_FIXED;
+
+        let mut term = FIXED_ONE;
+        let mut sum = FIXED_ONE;
+        for n in 1..EXP_TERMS {
+            term = term * r / ((n as u128) * FIXED_ONE);
+            sum += term;
+        }
+
+        for j in 0..MAX_EXP_SHIFT {
+            if (j as u128) < k {
+                sum = sum * 2;
+            }
+        }
+        sum
+    }
+
+    // e^-y for a FIXED_ONE scaled y >= 0
+    fn exp_neg_fixed(y: u128) -> u128 {
+        FIXED_ONE * FIXED_ONE / exp_fixed(y)
+    }
+
+    // ln(v) for a FIXED_ONE scaled v >= 1: ln(v) = k * ln(2) + 2 * atanh((m - 1) / (m + 1)) with v = 2^k * m
+    fn ln_fixed(v: u128) -> u128 {
+        let mut m = v;
+        let mut k: u128 = 0;
+        for _i in 0..MAX_LN_SHIFT {
+            if m >= 2 * FIXED_ONE {
+                m = m / 2;
+                k += 1;
+            }
+        }
+
+        let z = (m - FIXED_ONE) * FIXED_ONE / (m + FIXED_ONE);
+        let z2 = z * z / FIXED_ONE;
+        let mut term = z;
+        let mut sum = z;
+        for n in 1..LN_TERMS {
+            term = term * z2 / FIXED_ONE;
+            sum += term / (2 * n as u128 + 1);
+        }
+        k * LN_2_FIXED + 2 * sum
+    }
+
+    fn to_token_amount_ceil(value: u128, token_unit: u64) -> u64 {
+        ((value * token_unit as u128 + FIXED_ONE - 1) / FIXED_ONE) as u64
+    }
+
+    // Fee on a trade amount, rounded up like the program's MarketAccount::trade_fee
+    fn trade_fee(amount: u64, fee_bps: u64) -> u64 {
+        ((amount as u128 * fee_bps as u128 + BPS_DENOMINATOR - 1) / BPS_DENOMINATOR) as u64
+    }
+
+    fn to_token_amount_floor(value: u128, token_unit: u64) -> u64 {
+        (value * token_unit as u128 / FIXED_ONE) as u64
+    }
+
+    // Geometric draw, the number of NOISE_ALPHA trials in a row that succeed. Drawn jointly by the nodes so no
+    // single one knows it
+    fn geometric_draw() -> u64 {
+        let mut count: u64 = 0;
+        let mut running = true;
+        for _i in 0..NOISE_MAX_STEPS {
+            let mut bits: u64 = 0;
+            for _j in 0..NOISE_BITS {
+                bits = bits * 2 + if ArcisRNG::bool() { 1 } else { 0 };
+            }
+            running = running && bits < NOISE_ALPHA;
+            if running {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    // Only the volume the market's policy allows is revealed, probs-only reveals zeros. The program reveals each
+    // market version once, so the noise isn't drawn again for stats that didn't change
+    #[instruction]
+    pub fn reveal_probs(
+        market_stats_ctxt: Enc<Mxe, MarketStats>,
+        policy: u8,
+        policy_param: u64,
+        market_version: u64,
+    ) -> (
+        [u64; MAX_OPTIONS], // probs
+        [u64; MAX_OPTIONS], // vote stats
+        u64, // Market version
     ) {
         let market_stats = market_stats_ctxt.to_arcis();
-        let probabilities = [market_stats.probs.option0.reveal(), market_stats.probs.option1.reveal()];
-        let vote_stats = [market_stats.vote_stats.option0.reveal(), market_stats.vote_stats.option1.reveal()];
-        (probabilities, vote_stats)
+        // The program only passes a zero param with probs only, keep the unused branches well defined
+        let param = if policy_param > 0 { policy_param } else { 1 };
+        let mut probabilities = [0u64; MAX_OPTIONS];
+        let mut vote_stats = [0u64; MAX_OPTIONS];
+        for i in 0..MAX_OPTIONS {
+            probabilities[i] = market_stats.probs.options[i].reveal();
+            let volume = market_stats.vote_stats.options[i];
+            let bucketed = volume / param * param;
+            // The difference of two geometric draws is the discrete Laplace, centered on the real volume and clamped at zero
+            let up = geometric_draw() * param;
+            let down = geometric_draw() * param;
+            let noisy = if volume + up > down { volume + up - down } else { 0 };
+            let revealed = if policy == REVEAL_BUCKETED_VOLUME {
+                bucketed
+            } else if policy == REVEAL_NOISY_VOLUME {
+                noisy
+            } else {
+                0
+            };
+            vote_stats[i] = revealed.reveal();
+        }
+        (probabilities, vote_stats, market_version.reveal())
     }
 
+    // The liability is what every winning position will claim, rounded down like claim_rewards and
+    // claim_rewards_scalar, so it doesn't depend on the vote totals. Those are revealed here for every market,
+    // hide_votes only keeps them out of reveal_probs while the market trades.
+    // complete_sets are shares of every outcome minted outside the market maker, each pays exactly one token
     #[instruction]
-    pub fn reveal_market(market_stats_ctxt: Enc<Mxe, MarketStats>, winner: u8) -> (
+    pub fn reveal_market(
+        market_stats_ctxt: Enc<Mxe, MarketStats>,
+        winner: u8,
+        market_version: u64,
+        scalar: bool,
+        long_payout: u64,
+        token_unit: u64,
+        complete_sets: u64,
+    ) -> (
         u8, // winning outcome
-        [f64; 2], // probs
-        [u64; 2], // vote stats
+        [u64; MAX_OPTIONS], // probs
+        [u64; MAX_OPTIONS], // vote stats
+        u64, // liability
+        u64, // market version
     ) {
         let market_stats = market_stats_ctxt.to_arcis();
-        let probs = [market_stats.probs.option0.reveal(), market_stats.probs.option1.reveal()];
-        let vote_stats = [market_stats.vote_stats.option0.reveal(), market_stats.vote_stats.option1.reveal()];
-        (winner.reveal(), probs, vote_stats)
+        let mut probs = [0u64; MAX_OPTIONS];
+        let mut vote_stats = [0u64; MAX_OPTIONS];
+        let mut winning_shares: u64 = 0;
+        for i in 0..MAX_OPTIONS {
+            let volume = market_stats.vote_stats.options[i];
+            if winner == i as u8 {
+                winning_shares = volume;
+            }
+            probs[i] = market_stats.probs.options[i].reveal();
+            vote_stats[i] = volume.reveal();
+        }
+        let scalar_payout = market_stats.vote_stats.options[0] as u128 * long_payout as u128
+            + market_stats.vote_stats.options[1] as u128 * (FIXED_ONE - long_payout as u128)
+            + complete_sets as u128 * FIXED_ONE;
+        let liability = if scalar {
+            ((scalar_payout * token_unit as u128) / (SHARES_PER_UNIT as u128 * FIXED_ONE)) as u64
+        } else {
+            (((winning_shares + complete_sets) as u128 * token_unit as u128) / SHARES_PER_UNIT as u128) as u64
+        };
+        (winner.reveal(), probs, vote_stats, liability.reveal(), market_version.reveal())
+    }
+
+    // Re-encrypts a position to its owner's key so wallets can show it, the stored position is left as is
+    #[instruction]
+    pub fn view_position(
+        owner: Shared,
+        trade_seq: u64,
+        user_position_ctxt: Enc<Mxe, UserPosition>,
+    ) -> (
+        Enc<Shared, UserPosition>,
+        u64, // Trade sequence the view was taken at
+    ) {
+        let user_position = user_position_ctxt.to_arcis();
+        (owner.from_arcis(user_position), trade_seq.reveal())
+    }
+
+    // Marks a position to market for its owner: the LMSR proceeds of selling every share at once and its total
+    // cost basis, encrypted to the owner's key. Nothing is written back
+    #[instruction]
+    pub fn value_position(
+        owner: Shared,
+        liquidity_parameter: u64,
+        num_options: u8,
+        token_unit: u64,
+        fee_bps: u64,
+        trade_seq: u64,
+        market_stats_ctxt: Enc<Mxe, MarketStats>,
+        user_position_ctxt: Enc<Mxe, UserPosition>,
+    ) -> (
+        Enc<Shared, PositionValue>,
+        u64, // Trade sequence the value was taken at
+    ) {
+        let market_stats = market_stats_ctxt.to_arcis();
+        let user_position = user_position_ctxt.to_arcis();
+
+        // Complete set shares aren't in the market's totals, the market maker can take back at most what it issued
+        let mut vote_stats = VoteStats {
+            options: market_stats.vote_stats.options,
+        };
+        let mut cost_basis: u64 = 0;
+        for i in 0..MAX_OPTIONS {
+            if vote_stats.options[i] > user_position.options[i] {
+                vote_stats.options[i] -= user_position.options[i];
+            } else {
+                vote_stats.options[i] = 0;
+            }
+            cost_basis += user_position.cost_basis[i];
+        }
+
+        let (_, cost) = cal_prob(&vote_stats, liquidity_parameter, num_options);
+        let mut cost_delta: u128 = 0;
+        if market_stats.cost > cost {
+            cost_delta = market_stats.cost - cost;
+        }
+        let proceeds = to_token_amount_floor(cost_delta, token_unit);
+
+        let position_value = PositionValue {
+            value: proceeds - trade_fee(proceeds, fee_bps),
+            cost_basis,
+        };
+        (owner.from_arcis(position_value), trade_seq.reveal())
+    }
+
+    // Prices buying and selling `shares` of the requested outcome without touching the market, the quote
+    // goes back encrypted to the requester's key
+    #[instruction]
+    pub fn quote_trade(
+        trade_ctxt: Enc<Shared, UserTrade>,
+        market_version: u64,
+        liquidity_parameter: u64,
+        num_options: u8,
+        token_unit: u64,
+        fee_bps: u64,
+        market_stats_ctxt: Enc<Mxe, MarketStats>,
+    ) -> (
+        Enc<Shared, TradeQuote>,
+        u64, // Market version the quote was priced at
+    ) {
+        let user_trade = trade_ctxt.to_arcis();
+        let market_stats = market_stats_ctxt.to_arcis();
+
+        let mut buy_stats = VoteStats {
+            options: market_stats.vote_stats.options,
+        };
+        let mut sell_stats = VoteStats {
+            options: market_stats.vote_stats.options,
+        };
+        for i in 0..MAX_OPTIONS {
+            if user_trade.option == i as u8 && user_trade.option < num_options {
+                buy_stats.options[i] += user_trade.shares;
+                if sell_stats.options[i] >= user_trade.shares {
+                    sell_stats.options[i] -= user_trade.shares;
+                }
+            }
+        }
+
+        let (_, buy_cost) = cal_prob(&buy_stats, liquidity_parameter, num_options);
+        let (_, sell_cost) = cal_prob(&sell_stats, liquidity_parameter, num_options);
+        let mut buy_delta: u128 = 0;
+        if buy_cost > market_stats.cost {
+            buy_delta = buy_cost - market_stats.cost;
+        }
+        let mut sell_delta: u128 = 0;
+        if market_stats.cost > sell_cost {
+            sell_delta = market_stats.cost - sell_cost;
+        }
+        // Rounded like execute_buy and execute_sell, buys pay the fee on top and sells out of their proceeds
+        let buy_amount = to_token_amount_ceil(buy_delta, token_unit);
+        let sell_amount = to_token_amount_floor(sell_delta, token_unit);
+
+        let quote = TradeQuote {
+            buy_cost: buy_amount + trade_fee(buy_amount, fee_bps),
+            sell_proceeds: sell_amount - trade_fee(sell_amount, fee_bps),
+        };
+        (trade_ctxt.owner.from_arcis(quote), market_version.reveal())
     }
 
     #[instruction]
     pub fn claim_rewards(
         winning_outcome: u8,
+        token_unit: u64,
+        trade_seq: u64,
         user_position_ctxt: Enc<Mxe, UserPosition>,
     ) -> (
-        Enc<Mxe, UserPosition>, 
+        Enc<Mxe, UserPosition>,
         u64, // Amount to claim
+        u64, // Trade sequence
     ) {
         let mut user_position = user_position_ctxt.to_arcis();
 
         let mut reward: u64 = 0;
-        if winning_outcome == 0 {
-            reward = (user_position.option0 * (1000000u64)) / SHARES_PER_UNIT; //num shares * 1 token * 1e6 / shares_per_unit
-        } else if winning_outcome == 1 {
-            reward = (user_position.option1 * (1000000u64)) / SHARES_PER_UNIT; //num shares * 1 token * 1e6 / shares_per_unit
+        for i in 0..MAX_OPTIONS {
+            if winning_outcome == i as u8 {
+                reward = ((user_position.options[i] as u128 * token_unit as u128) / SHARES_PER_UNIT as u128) as u64; //num shares * 1 token / shares_per_unit
+            }
+            user_position.options[i] = 0;
+            user_position.cost_basis[i] = 0;
+        }
+
+        (
+            user_position_ctxt.owner.from_arcis(user_position),
+            reward.reveal(),
+            trade_seq.reveal(),
+        )
+    }
+
+    // Scalar markets trade LONG (outcome 0) and SHORT (outcome 1) shares. long_payout is what a LONG share
+    // pays, FIXED_ONE scaled, and a SHORT share pays the rest
+    #[instruction]
+    pub fn claim_rewards_scalar(
+        long_payout: u64,
+        token_unit: u64,
+        trade_seq: u64,
+        user_position_ctxt: Enc<Mxe, UserPosition>,
+    ) -> (
+        Enc<Mxe, UserPosition>,
+        u64, // Amount to claim
+        u64, // Trade sequence
+    ) {
+        let mut user_position = user_position_ctxt.to_arcis();
+
+        let payout = user_position.options[0] as u128 * long_payout as u128
+            + user_position.options[1] as u128 * (FIXED_ONE - long_payout as u128);
+        let reward = ((payout * token_unit as u128) / (SHARES_PER_UNIT as u128 * FIXED_ONE)) as u64;
+        for i in 0..MAX_OPTIONS {
+            user_position.options[i] = 0;
+            user_position.cost_basis[i] = 0;
+        }
+
+        (
+            user_position_ctxt.owner.from_arcis(user_position),
+            reward.reveal(),
+            trade_seq.reveal(),
+        )
+    }
+
+    // Pays back the position's cost basis on a voided market. refund_pool is what the vault held
+    // for the market when it was voided, if it can't cover every cost basis each one is scaled down pro rata
+    #[instruction]
+    pub fn claim_refund(
+        refund_pool: u64,
+        trade_seq: u64,
+        market_stats_ctxt: Enc<Mxe, MarketStats>,
+        user_position_ctxt: Enc<Mxe, UserPosition>,
+    ) -> (
+        Enc<Mxe, UserPosition>,
+        u64, // Amount to refund
+        u64, // Trade sequence
+    ) {
+        let market_stats = market_stats_ctxt.to_arcis();
+        let mut user_position = user_position_ctxt.to_arcis();
+
+        let mut cost_basis: u64 = 0;
+        for i in 0..MAX_OPTIONS {
+            cost_basis += user_position.cost_basis[i];
+            user_position.options[i] = 0;
+            user_position.cost_basis[i] = 0;
+        }
+
+        let mut refund = cost_basis;
+        if market_stats.total_cost_basis > refund_pool {
+            refund = ((cost_basis as u128 * refund_pool as u128) / market_stats.total_cost_basis as u128) as u64;
         }
-        user_position.option0 = 0;
-        user_position.option1 = 0;
 
         (
             user_position_ctxt.owner.from_arcis(user_position),
-            reward.reveal()
+            refund.reveal(),
+            trade_seq.reveal(),
         )
     }
 }
@@ -1,6 +1,4 @@
-use anchor_lang::prelude::Pubkey;
 use arcium_anchor::comp_def_offset;
-use crate::define_circuit_urls;
 
 pub const COMP_DEF_OFFSET_INIT_MARKET_STATS: u32 = comp_def_offset("init_market_stats");
 pub const COMP_DEF_OFFSET_INIT_USER_POSITION: u32 = comp_def_offset("init_user_position");
@@ -8,34 +6,129 @@ pub const COMP_DEF_OFFSET_REVEAL_MARKET: u32 = comp_def_offset("reveal_market");
 pub const COMP_DEF_OFFSET_REVEAL_PROBS: u32 = comp_def_offset("reveal_probs");
 pub const COMP_DEF_OFFSET_BUY_SHARES: u32 = comp_def_offset("buy_shares");
 pub const COMP_DEF_OFFSET_SELL_SHARES: u32 = comp_def_offset("sell_shares");
+pub const COMP_DEF_OFFSET_BUY_WITH_AMOUNT: u32 = comp_def_offset("buy_with_amount");
 pub const COMP_DEF_OFFSET_CLAIM_REWARDS: u32 = comp_def_offset("claim_rewards");
+pub const COMP_DEF_OFFSET_BUY_SHARES_PRIVATE: u32 = comp_def_offset("buy_shares_private");
+pub const COMP_DEF_OFFSET_SELL_SHARES_PRIVATE: u32 = comp_def_offset("sell_shares_private");
+pub const COMP_DEF_OFFSET_INIT_BATCH_STATS: u32 = comp_def_offset("init_batch_stats");
+pub const COMP_DEF_OFFSET_COMMIT_BATCH_TRADE: u32 = comp_def_offset("commit_batch_trade");
+pub const COMP_DEF_OFFSET_CLEAR_BATCH: u32 = comp_def_offset("clear_batch");
+pub const COMP_DEF_OFFSET_SETTLE_BATCH_TRADE: u32 = comp_def_offset("settle_batch_trade");
+pub const COMP_DEF_OFFSET_CLAIM_REFUND: u32 = comp_def_offset("claim_refund");
+pub const COMP_DEF_OFFSET_CLAIM_REWARDS_SCALAR: u32 = comp_def_offset("claim_rewards_scalar");
+pub const COMP_DEF_OFFSET_VIEW_POSITION: u32 = comp_def_offset("view_position");
+pub const COMP_DEF_OFFSET_VALUE_POSITION: u32 = comp_def_offset("value_position");
+pub const COMP_DEF_OFFSET_QUOTE_TRADE: u32 = comp_def_offset("quote_trade");
+pub const COMP_DEF_OFFSET_PLACE_LIMIT_ORDER: u32 = comp_def_offset("place_limit_order");
+pub const COMP_DEF_OFFSET_MATCH_ORDERS: u32 = comp_def_offset("match_orders");
+pub const COMP_DEF_OFFSET_SETTLE_LIMIT_ORDER: u32 = comp_def_offset("settle_limit_order");
+pub const COMP_DEF_OFFSET_MINT_COMPLETE_SET: u32 = comp_def_offset("mint_complete_set");
+pub const COMP_DEF_OFFSET_REDEEM_COMPLETE_SET: u32 = comp_def_offset("redeem_complete_set");
 
-pub const MAX_OPTIONS: usize = 2;
+pub const SHARES_PER_UNIT: u64 = 1_000_000; // must match SHARES_PER_UNIT in the circuits, one share pays one token
+pub const MIN_OPTIONS: usize = 2;
+pub const MAX_OPTIONS: usize = 8;
 pub const MAX_QUESTION_LENGTH: usize = 30;
 pub const MAX_OPTION_LENGTH: usize = 10;
 
+pub const MAX_ADMINS: usize = 4;
+pub const MAX_ALLOWED_MINTS: usize = 4;
+pub const MAX_CIRCUIT_URL_LENGTH: usize = 128;
+
+pub const SCALAR_PAYOUT_ONE: u64 = 1_000_000_000_000; // must match FIXED_ONE in the circuits, a LONG share's payout is scaled by it
+
+pub const MAX_RESOLVERS: usize = 5;
+pub const NO_RESOLVER_VOTE: u8 = u8::MAX;
+
+pub const BPS_DENOMINATOR: u64 = 10_000; // must match BPS_DENOMINATOR in the circuits
+pub const MAX_FEE_BPS: u64 = 1_000; // protocol + creator + lp fees can't exceed 10%
+
 pub const MARKET_ACCOUNT_VOTE_STATS_OFFSET: u32 = 8 + 1 + 16; // 8 bytes (discriminator) + 1 byte (bump) + 16 bytes (nonce)
 pub const MARKET_ACCOUNT_VOTE_STATS_LENGTH: u32 = 32 * MAX_OPTIONS as u32;
 pub const MARKET_ACCOUNT_PROB_LENGTH: u32 = 32 * MAX_OPTIONS as u32;
 pub const MARKET_ACCOUNT_COST_LENGTH: u32 = 32;
+pub const MARKET_ACCOUNT_TOTAL_COST_BASIS_LENGTH: u32 = 32;
+// The whole Enc<Mxe, MarketStats>: vote stats, probs, cost and total cost basis
+pub const MARKET_ACCOUNT_STATS_LENGTH: u32 = MARKET_ACCOUNT_VOTE_STATS_LENGTH
+    + MARKET_ACCOUNT_PROB_LENGTH
+    + MARKET_ACCOUNT_COST_LENGTH
+    + MARKET_ACCOUNT_TOTAL_COST_BASIS_LENGTH;
 
 pub const USER_POSITION_SHARES_OFFSET: u32 = 8 + 1 + 16; // 8 bytes (discriminator) + 1 byte (bump) + 16 bytes (nonce)
-pub const USER_POSITION_SHARES_LENGTH: u32 = 32 * MAX_OPTIONS as u32;
-
-pub const MARKET_REVEAL_PROBS_TIME: u64 = 60;
-
-define_circuit_urls! {
-    "https://sapphire-literary-rat-567.mypinata.cloud/ipfs/bafybeib4prz6zcn3rhgc73sh3ogy7u4ccjhzy7cqe2ojlrccgciaa3s4ky/";
-    INIT_MARKET_STATS_CIRCUIT: "init_market_stats_testnet.arcis",
-    INIT_USER_POSITION_CIRCUIT: "init_user_position_testnet.arcis",
-    BUY_SHARES_CIRCUIT: "buy_shares_testnet.arcis",
-    CLAIM_REWARDS_CIRCUIT: "claim_rewards_testnet.arcis", 
-    REVEAL_PROBS_CIRCUIT: "reveal_probs_testnet.arcis",
-    REVEAL_MARKET_CIRCUIT: "reveal_market_testnet.arcis",
-    SELL_SHARES_CIRCUIT: "sell_shares_testnet.arcis"
-}
-
-pub const IS_DEVNET: bool = true;
-pub const LN_2_SCALED: u64 = 693_147_180_559_9453; // ln(2) * 10^16
-pub const USDC_MINT: Pubkey = Pubkey::from_str_const("4zMMC9srt5Ri5X14GAgXhaHii3GnPAEERYPJgZJDncDU"); // USDC devnet mint
-pub const ADMIN_KEY: Pubkey = Pubkey::from_str_const("9CtkxgXqNF3yvGr4u9jdVByyZknBH4SoqPgNpRbX2sjP"); // admin key
\ No newline at end of file
+pub const USER_POSITION_SHARES_LENGTH: u32 = 2 * 32 * MAX_OPTIONS as u32; // shares + cost basis
+
+pub const BATCH_STATS_OFFSET: u32 = 8 + 1 + 16; // 8 bytes (discriminator) + 1 byte (bump) + 16 bytes (nonce)
+pub const BATCH_STATS_LENGTH: u32 = 2 * 32 * MAX_OPTIONS as u32; // budgets + shares
+
+pub const LIMIT_ORDER_OFFSET: u32 = 8 + 1 + 16; // 8 bytes (discriminator) + 1 byte (bump) + 16 bytes (nonce)
+pub const LIMIT_ORDER_FIELDS: usize = 6; // option, shares, limit price, budget, filled shares, cost
+pub const LIMIT_ORDER_LENGTH: u32 = 32 * LIMIT_ORDER_FIELDS as u32;
+pub const MAX_MATCH_ORDERS: usize = 4; // must match the number of orders the match_orders circuit takes
+
+// Pyth style price account, only the fields read to settle price threshold markets
+pub const PRICE_ACCOUNT_MAGIC: u32 = 0xa1b2c3d4;
+pub const PRICE_ACCOUNT_TYPE: u32 = 3;
+pub const PRICE_STATUS_TRADING: u32 = 1;
+pub const PRICE_ACCOUNT_MAGIC_OFFSET: usize = 0;
+pub const PRICE_ACCOUNT_TYPE_OFFSET: usize = 8;
+pub const PRICE_ACCOUNT_EXPO_OFFSET: usize = 20;
+pub const PRICE_ACCOUNT_TIMESTAMP_OFFSET: usize = 96;
+pub const PRICE_ACCOUNT_AGG_PRICE_OFFSET: usize = 208;
+pub const PRICE_ACCOUNT_AGG_STATUS_OFFSET: usize = 224;
+pub const PRICE_ACCOUNT_MIN_LENGTH: usize = 240;
+pub const MAX_SETTLEMENT_DELAY: i64 = 300; // price feed markets settle on a price published at most this long after trading closed
+
+pub const MARKET_REVEAL_PROBS_TIME: i64 = 60; // shortest reveal interval a market can set
+// Reveal policies, must match REVEAL_* in the reveal_probs circuit
+pub const REVEAL_POLICY_PROBS_ONLY: u8 = 0;
+pub const REVEAL_POLICY_BUCKETED_VOLUME: u8 = 1;
+pub const REVEAL_POLICY_NOISY_VOLUME: u8 = 2;
+// A pending trade whose callback never arrived can be released after this many seconds
+pub const PENDING_TRADE_TIMEOUT: i64 = 600;
+pub const CLOSE_MARKET_GRACE_PERIOD: i64 = 30 * 24 * 3600; // after finalization, the authority may close a market with unclaimed positions
+
+// Trade status returned by the buy/sell circuits and emitted in the trade events
+pub const TRADE_STATUS_FAILED: u8 = 0;
+pub const TRADE_STATUS_SUCCESS: u8 = 1;
+pub const TRADE_STATUS_SLIPPAGE: u8 = 2;
+pub const TRADE_STATUS_STALE: u8 = 3; // market stats changed while the trade was queued, nothing was applied
+
+// Circuit file names, comp defs load them from the config's circuit_base_url when offchain_circuits is on
+pub const INIT_MARKET_STATS_CIRCUIT: &str = "init_market_stats_testnet.arcis";
+pub const INIT_USER_POSITION_CIRCUIT: &str = "init_user_position_testnet.arcis";
+pub const BUY_SHARES_CIRCUIT: &str = "buy_shares_testnet.arcis";
+pub const CLAIM_REWARDS_CIRCUIT: &str = "claim_rewards_testnet.arcis";
+pub const REVEAL_PROBS_CIRCUIT: &str = "reveal_probs_testnet.arcis";
+pub const REVEAL_MARKET_CIRCUIT: &str = "reveal_market_testnet.arcis";
+pub const SELL_SHARES_CIRCUIT: &str = "sell_shares_testnet.arcis";
+pub const BUY_WITH_AMOUNT_CIRCUIT: &str = "buy_with_amount_testnet.arcis";
+pub const BUY_SHARES_PRIVATE_CIRCUIT: &str = "buy_shares_private_testnet.arcis";
+pub const SELL_SHARES_PRIVATE_CIRCUIT: &str = "sell_shares_private_testnet.arcis";
+pub const INIT_BATCH_STATS_CIRCUIT: &str = "init_batch_stats_testnet.arcis";
+pub const COMMIT_BATCH_TRADE_CIRCUIT: &str = "commit_batch_trade_testnet.arcis";
+pub const CLEAR_BATCH_CIRCUIT: &str = "clear_batch_testnet.arcis";
+pub const SETTLE_BATCH_TRADE_CIRCUIT: &str = "settle_batch_trade_testnet.arcis";
+pub const CLAIM_REFUND_CIRCUIT: &str = "claim_refund_testnet.arcis";
+pub const CLAIM_REWARDS_SCALAR_CIRCUIT: &str = "claim_rewards_scalar_testnet.arcis";
+pub const VIEW_POSITION_CIRCUIT: &str = "view_position_testnet.arcis";
+pub const VALUE_POSITION_CIRCUIT: &str = "value_position_testnet.arcis";
+pub const QUOTE_TRADE_CIRCUIT: &str = "quote_trade_testnet.arcis";
+pub const PLACE_LIMIT_ORDER_CIRCUIT: &str = "place_limit_order_testnet.arcis";
+pub const MATCH_ORDERS_CIRCUIT: &str = "match_orders_testnet.arcis";
+pub const SETTLE_LIMIT_ORDER_CIRCUIT: &str = "settle_limit_order_testnet.arcis";
+pub const MINT_COMPLETE_SET_CIRCUIT: &str = "mint_complete_set_testnet.arcis";
+pub const REDEEM_COMPLETE_SET_CIRCUIT: &str = "redeem_complete_set_testnet.arcis";
+
+pub const LN_SCALE: u128 = 10u128.pow(16);
+// ln(n) * 10^16 indexed by number of options, used for the b * ln(n) market maker funding
+pub const LN_SCALED: [u64; MAX_OPTIONS + 1] = [
+    0,
+    0,
+    6_931_471_805_599_453,
+    10_986_122_886_681_098,
+    13_862_943_611_198_906,
+    16_094_379_124_341_003,
+    17_917_594_692_280_550,
+    19_459_101_090_932_196,
+    20_794_415_416_798_357,
+];
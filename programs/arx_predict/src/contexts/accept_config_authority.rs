@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use crate::{states::Config, ErrorCode};
+
+#[derive(Accounts)]
+pub struct AcceptConfigAuthority<'info> {
+    pub new_authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> AcceptConfigAuthority<'info> {
+    pub fn accept_config_authority(&mut self) -> Result<()> {
+        require!(
+            self.config.pending_authority == Some(self.new_authority.key()),
+            ErrorCode::InvalidAuthority
+        );
+        self.config.authority = self.new_authority.key();
+        self.config.pending_authority = None;
+        Ok(())
+    }
+}
@@ -2,7 +2,15 @@ use anchor_lang::prelude::*;
 use arcium_anchor::prelude::*;
 use arcium_client::idl::arcium::types::CallbackAccount;
 
-use crate::{callbacks::BuySharesCallback, constants::{COMP_DEF_OFFSET_BUY_SHARES, MARKET_ACCOUNT_COST_LENGTH, MARKET_ACCOUNT_PROB_LENGTH, MARKET_ACCOUNT_VOTE_STATS_LENGTH, MARKET_ACCOUNT_VOTE_STATS_OFFSET, USER_POSITION_SHARES_LENGTH, USER_POSITION_SHARES_OFFSET}, states::MarketStatus, ErrorCode, MarketAccount, SignerAccount, UserPosition, ID, ID_CONST};
+use crate::{
+    callbacks::BuySharesCallback,
+    constants::{
+        COMP_DEF_OFFSET_BUY_SHARES, MARKET_ACCOUNT_STATS_LENGTH, MARKET_ACCOUNT_VOTE_STATS_OFFSET,
+        USER_POSITION_SHARES_LENGTH, USER_POSITION_SHARES_OFFSET,
+    },
+    utils::token_unit,
+    ErrorCode, MarketAccount, SignerAccount, UserAccount, UserPosition, ID, ID_CONST,
+};
 
 #[queue_computation_accounts("buy_shares", payer)]
 #[derive(Accounts)]
@@ -80,6 +88,12 @@ pub struct BuyShares<'info> {
         bump
     )]
     pub user_position_acc: Box<Account<'info, UserPosition>>,
+    #[account(
+        mut,
+        seeds = [b"user_account", market_acc.mint.as_ref(), payer.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Box<Account<'info, UserAccount>>,
 }
 
 impl<'info> BuyShares<'info> {
@@ -90,20 +104,33 @@ impl<'info> BuyShares<'info> {
         vote_nonce: u128,
         computation_offset: u64,
         shares: u64,
+        max_cost: u64,
         sign_pda_account_bump: u8,
     ) -> Result<()> {
-        require!(self.market_acc.status == MarketStatus::Active, ErrorCode::MarketActive);
+        require!(self.market_acc.is_trading(Clock::get()?.unix_timestamp), ErrorCode::TradingClosed);
+        require!(!self.market_acc.batch_mode, ErrorCode::BatchModeEnabled);
+        // Escrow what the trade may cost, the circuit rejects anything above it and the rest is refunded in the callback
+        let locked_amount = self.user_account.balance.min(max_cost);
+        self.user_account.debit(locked_amount)?;
+        let trade_seq = self.user_position_acc.begin_trade(locked_amount, Clock::get()?.unix_timestamp)?;
         let args = vec![
             Argument::ArcisPubkey(vote_encryption_pubkey),
             Argument::PlaintextU128(vote_nonce),
             Argument::EncryptedBool(vote),
             Argument::PlaintextU64(shares),
+            // The circuit prices the trade before fees, the fee is charged on top in the callback
+            Argument::PlaintextU64(self.market_acc.cost_before_fee(max_cost)),
+            Argument::PlaintextU64(self.market_acc.cost_before_fee(locked_amount)),
+            Argument::PlaintextU64(trade_seq),
+            Argument::PlaintextU64(self.market_acc.state_version),
             Argument::PlaintextU64(self.market_acc.liquidity_parameter),
+            Argument::PlaintextU8(self.market_acc.num_options),
+            Argument::PlaintextU64(token_unit(self.market_acc.mint_decimals)?),
             Argument::PlaintextU128(self.market_acc.nonce),
             Argument::Account(
                 self.market_acc.key(),
                 MARKET_ACCOUNT_VOTE_STATS_OFFSET,
-                MARKET_ACCOUNT_VOTE_STATS_LENGTH + MARKET_ACCOUNT_PROB_LENGTH + MARKET_ACCOUNT_COST_LENGTH,
+                MARKET_ACCOUNT_STATS_LENGTH,
             ),
             Argument::PlaintextU128(self.user_position_acc.nonce),
             Argument::Account(
@@ -130,6 +157,10 @@ impl<'info> BuyShares<'info> {
                     pubkey: self.user_position_acc.key(),
                     is_writable: true,
                 },
+                CallbackAccount {
+                    pubkey: self.user_account.key(),
+                    is_writable: true,
+                },
             ])],
         )?;
         Ok(())
@@ -1,7 +1,22 @@
 use arcium_anchor::prelude::*;
 use anchor_lang::prelude::*;
 use crate::{
-    constants::{COMP_DEF_OFFSET_BUY_SHARES, COMP_DEF_OFFSET_CLAIM_REWARDS, COMP_DEF_OFFSET_SELL_SHARES}, MarketAccount, UserPosition, COMP_DEF_OFFSET_INIT_MARKET_STATS, COMP_DEF_OFFSET_INIT_USER_POSITION, COMP_DEF_OFFSET_REVEAL_MARKET, COMP_DEF_OFFSET_REVEAL_PROBS, ID_CONST
+    constants::{
+        COMP_DEF_OFFSET_BUY_SHARES, COMP_DEF_OFFSET_BUY_SHARES_PRIVATE,
+        COMP_DEF_OFFSET_BUY_WITH_AMOUNT, COMP_DEF_OFFSET_CLAIM_REFUND,
+        COMP_DEF_OFFSET_CLAIM_REWARDS, COMP_DEF_OFFSET_CLAIM_REWARDS_SCALAR,
+        COMP_DEF_OFFSET_CLEAR_BATCH, COMP_DEF_OFFSET_COMMIT_BATCH_TRADE,
+        COMP_DEF_OFFSET_INIT_BATCH_STATS, COMP_DEF_OFFSET_MATCH_ORDERS,
+        COMP_DEF_OFFSET_MINT_COMPLETE_SET, COMP_DEF_OFFSET_PLACE_LIMIT_ORDER,
+        COMP_DEF_OFFSET_QUOTE_TRADE, COMP_DEF_OFFSET_REDEEM_COMPLETE_SET,
+        COMP_DEF_OFFSET_SELL_SHARES, COMP_DEF_OFFSET_SELL_SHARES_PRIVATE,
+        COMP_DEF_OFFSET_SETTLE_BATCH_TRADE, COMP_DEF_OFFSET_SETTLE_LIMIT_ORDER,
+        COMP_DEF_OFFSET_VALUE_POSITION, COMP_DEF_OFFSET_VIEW_POSITION,
+    },
+    states::{BatchAccount, BatchTicket, LimitOrder, UserAccount},
+    MarketAccount, UserPosition, COMP_DEF_OFFSET_INIT_MARKET_STATS,
+    COMP_DEF_OFFSET_INIT_USER_POSITION, COMP_DEF_OFFSET_REVEAL_MARKET,
+    COMP_DEF_OFFSET_REVEAL_PROBS, ID_CONST,
 };
 
 #[callback_accounts("init_market_stats")]
@@ -64,6 +79,8 @@ pub struct RevealProbsCallback<'info> {
     pub instructions_sysvar: AccountInfo<'info>,
     #[account(mut)]
     pub market_acc: Account<'info, MarketAccount>,
+    #[account(mut)]
+    pub user_account: Account<'info, UserAccount>,
 }
 
 #[callback_accounts("buy_shares")]
@@ -81,6 +98,27 @@ pub struct BuySharesCallback<'info> {
     pub market_acc: Account<'info, MarketAccount>,
     #[account(mut)]
     pub user_position_acc: Account<'info, UserPosition>,
+    #[account(mut)]
+    pub user_account: Account<'info, UserAccount>,
+}
+
+#[callback_accounts("buy_with_amount")]
+#[derive(Accounts)]
+pub struct BuyWithAmountCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_BUY_WITH_AMOUNT)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(mut)]
+    pub user_position_acc: Account<'info, UserPosition>,
+    #[account(mut)]
+    pub user_account: Account<'info, UserAccount>,
 }
 
 #[callback_accounts("sell_shares")]
@@ -98,6 +136,8 @@ pub struct SellSharesCallback<'info> {
     pub market_acc: Account<'info, MarketAccount>,
     #[account(mut)]
     pub user_position_acc: Account<'info, UserPosition>,
+    #[account(mut)]
+    pub user_account: Account<'info, UserAccount>,
 }
 
 #[callback_accounts("claim_rewards")]
@@ -112,5 +152,290 @@ pub struct ClaimRewardsCallback<'info> {
     /// CHECK: instructions_sysvar, checked by the account constraint
     pub instructions_sysvar: AccountInfo<'info>,
     #[account(mut)]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(mut)]
+    pub user_position_acc: Account<'info, UserPosition>,
+    #[account(mut)]
+    pub user_account: Account<'info, UserAccount>,
+}
+
+#[callback_accounts("claim_rewards_scalar")]
+#[derive(Accounts)]
+pub struct ClaimRewardsScalarCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_CLAIM_REWARDS_SCALAR)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(mut)]
+    pub user_position_acc: Account<'info, UserPosition>,
+    #[account(mut)]
+    pub user_account: Account<'info, UserAccount>,
+}
+
+#[callback_accounts("view_position")]
+#[derive(Accounts)]
+pub struct ViewPositionCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_VIEW_POSITION)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    pub user_position_acc: Account<'info, UserPosition>,
+}
+
+#[callback_accounts("value_position")]
+#[derive(Accounts)]
+pub struct ValuePositionCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_VALUE_POSITION)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    pub user_position_acc: Account<'info, UserPosition>,
+}
+
+#[callback_accounts("quote_trade")]
+#[derive(Accounts)]
+pub struct QuoteTradeCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_QUOTE_TRADE)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    pub market_acc: Account<'info, MarketAccount>,
+}
+
+#[callback_accounts("buy_shares_private")]
+#[derive(Accounts)]
+pub struct BuySharesPrivateCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_BUY_SHARES_PRIVATE)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(mut)]
+    pub user_position_acc: Account<'info, UserPosition>,
+    #[account(mut)]
+    pub user_account: Account<'info, UserAccount>,
+}
+
+#[callback_accounts("sell_shares_private")]
+#[derive(Accounts)]
+pub struct SellSharesPrivateCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_SELL_SHARES_PRIVATE)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(mut)]
+    pub user_position_acc: Account<'info, UserPosition>,
+    #[account(mut)]
+    pub user_account: Account<'info, UserAccount>,
+}
+
+#[callback_accounts("init_batch_stats")]
+#[derive(Accounts)]
+pub struct InitBatchStatsCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_BATCH_STATS)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub batch_acc: Account<'info, BatchAccount>,
+}
+
+#[callback_accounts("commit_batch_trade")]
+#[derive(Accounts)]
+pub struct CommitBatchTradeCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_COMMIT_BATCH_TRADE)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub batch_acc: Account<'info, BatchAccount>,
+}
+
+#[callback_accounts("clear_batch")]
+#[derive(Accounts)]
+pub struct ClearBatchCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_CLEAR_BATCH)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(mut)]
+    pub batch_acc: Account<'info, BatchAccount>,
+}
+
+#[callback_accounts("settle_batch_trade")]
+#[derive(Accounts)]
+pub struct SettleBatchTradeCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_SETTLE_BATCH_TRADE)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(mut)]
     pub user_position_acc: Account<'info, UserPosition>,
-}
\ No newline at end of file
+    #[account(mut)]
+    pub batch_ticket_acc: Account<'info, BatchTicket>,
+}
+
+#[callback_accounts("place_limit_order")]
+#[derive(Accounts)]
+pub struct PlaceLimitOrderCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_PLACE_LIMIT_ORDER)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub limit_order_acc: Account<'info, LimitOrder>,
+}
+
+#[callback_accounts("match_orders")]
+#[derive(Accounts)]
+pub struct MatchOrdersCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_MATCH_ORDERS)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(mut)]
+    pub order_0: Box<Account<'info, LimitOrder>>,
+    #[account(mut)]
+    pub order_1: Option<Box<Account<'info, LimitOrder>>>,
+    #[account(mut)]
+    pub order_2: Option<Box<Account<'info, LimitOrder>>>,
+    #[account(mut)]
+    pub order_3: Option<Box<Account<'info, LimitOrder>>>,
+}
+
+#[callback_accounts("settle_limit_order")]
+#[derive(Accounts)]
+pub struct SettleLimitOrderCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_SETTLE_LIMIT_ORDER)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(mut)]
+    pub limit_order_acc: Account<'info, LimitOrder>,
+    #[account(mut)]
+    pub user_position_acc: Account<'info, UserPosition>,
+    #[account(mut)]
+    pub user_account: Account<'info, UserAccount>,
+}
+
+#[callback_accounts("mint_complete_set")]
+#[derive(Accounts)]
+pub struct MintCompleteSetCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_MINT_COMPLETE_SET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(mut)]
+    pub user_position_acc: Account<'info, UserPosition>,
+    #[account(mut)]
+    pub user_account: Account<'info, UserAccount>,
+}
+
+#[callback_accounts("redeem_complete_set")]
+#[derive(Accounts)]
+pub struct RedeemCompleteSetCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_REDEEM_COMPLETE_SET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(mut)]
+    pub user_position_acc: Account<'info, UserPosition>,
+    #[account(mut)]
+    pub user_account: Account<'info, UserAccount>,
+}
+
+#[callback_accounts("claim_refund")]
+#[derive(Accounts)]
+pub struct ClaimRefundCallback<'info> {
+    pub arcium_program: Program<'info, Arcium>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_CLAIM_REFUND)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(address = ::anchor_lang::solana_program::sysvar::instructions::ID)]
+    /// CHECK: instructions_sysvar, checked by the account constraint
+    pub instructions_sysvar: AccountInfo<'info>,
+    #[account(mut)]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(mut)]
+    pub user_position_acc: Account<'info, UserPosition>,
+    #[account(mut)]
+    pub user_account: Account<'info, UserAccount>,
+}
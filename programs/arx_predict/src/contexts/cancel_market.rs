@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::{events::MarketVoidedEvent, states::MarketAccount, ErrorCode};
+
+#[derive(Accounts)]
+#[instruction(_id: u32)]
+pub struct CancelMarket<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"market", _id.to_le_bytes().as_ref()],
+        bump = market_acc.bump
+    )]
+    pub market_acc: Account<'info, MarketAccount>,
+}
+
+impl<'info> CancelMarket<'info> {
+    pub fn cancel_market(&mut self) -> Result<()> {
+        require!(
+            self.payer.key() == self.market_acc.authority,
+            ErrorCode::InvalidAuthority
+        );
+        require!(!self.market_acc.is_finalized(), ErrorCode::MarketFinalized);
+
+        self.market_acc.void(Clock::get()?.unix_timestamp);
+
+        emit!(MarketVoidedEvent {
+            market_id: self.market_acc.id,
+            refund_pool: self.market_acc.refund_pool,
+        });
+
+        Ok(())
+    }
+}
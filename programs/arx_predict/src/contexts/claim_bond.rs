@@ -0,0 +1,40 @@
+use anchor_lang::prelude::*;
+
+use crate::{events::ClaimBondEvent, states::{MarketAccount, UserAccount}, ErrorCode};
+
+#[derive(Accounts)]
+#[instruction(_id: u32)]
+pub struct ClaimBond<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"market", _id.to_le_bytes().as_ref()],
+        bump = market_acc.bump
+    )]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(
+        mut,
+        seeds = [b"user_account", market_acc.mint.as_ref(), payer.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Box<Account<'info, UserAccount>>,
+}
+
+impl<'info> ClaimBond<'info> {
+    /// Pays out the proposal and dispute bonds once the market is settled or voided
+    pub fn claim_bond(&mut self) -> Result<()> {
+        require!(self.market_acc.is_finalized(), ErrorCode::MarketNotFinalized);
+        let amount = self.market_acc.take_bonds(self.payer.key());
+        require!(amount > 0, ErrorCode::NoBondToClaim);
+        self.user_account.credit(amount);
+
+        emit!(ClaimBondEvent {
+            market_id: self.market_acc.id,
+            claimant: self.payer.key(),
+            amount,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::{Mint, Token, TokenAccount, TransferChecked, transfer_checked}
+};
+
+use crate::{
+    check_mint,
+    events::ClaimFeesEvent,
+    states::{Config, FeeBucket, MarketAccount},
+    ErrorCode,
+};
+
+#[derive(Accounts)]
+#[instruction(id: u32)]
+pub struct ClaimFees<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", mint.key().as_ref()],
+        bump,
+        token::mint = mint,
+        token::authority = vault
+    )]
+    pub vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = payer,
+    )]
+    pub ata: Account<'info, TokenAccount>,
+
+    #[account(
+        address = market_acc.mint,
+        mint::token_program = token_program
+    )]
+    pub mint: Account<'info, Mint>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        seeds = [b"market", id.to_le_bytes().as_ref()],
+        bump = market_acc.bump,
+    )]
+    pub market_acc: Account<'info, MarketAccount>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+}
+
+impl<'info> ClaimFees<'info> {
+    pub fn claim_fees(
+        &mut self,
+        id: u32,
+        bucket: FeeBucket,
+        bump: u8
+    ) -> Result<()> {
+        check_mint!(self.config, self.mint.key());
+        let payer = self.payer.key();
+        let amount = match bucket {
+            FeeBucket::Protocol => {
+                require!(payer == self.config.authority, ErrorCode::InvalidAuthority);
+                std::mem::take(&mut self.market_acc.protocol_fees)
+            }
+            FeeBucket::Creator => {
+                require!(payer == self.market_acc.authority, ErrorCode::InvalidAuthority);
+                std::mem::take(&mut self.market_acc.creator_fees)
+            }
+            FeeBucket::Lp => {
+                require!(payer == self.market_acc.liquidity_provider, ErrorCode::InvalidAuthority);
+                std::mem::take(&mut self.market_acc.lp_fees)
+            }
+        };
+        require!(amount > 0, ErrorCode::NoFeesToClaim);
+
+        let transfer_accounts = TransferChecked {
+            from: self.vault.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to: self.ata.to_account_info(),
+            authority: self.vault.to_account_info(),
+        };
+        let mint_key = self.mint.key();
+        let signer: &[&[&[u8]]] = &[&[b"vault", mint_key.as_ref(), &[bump]]];
+
+        transfer_checked(
+            CpiContext::new_with_signer(
+                self.token_program.to_account_info(),
+                transfer_accounts,
+                signer
+            ),
+            amount,
+            self.mint.decimals
+        )?;
+
+        emit!(ClaimFeesEvent {
+            market_id: id,
+            bucket,
+            amount,
+        });
+        Ok(())
+    }
+}
@@ -4,7 +4,12 @@ use anchor_spl::{
     token::{Mint, Token, TokenAccount, TransferChecked, transfer_checked}
 };
 
-use crate::{check_mint, events::ClaimMarketFundsEvent, states::{MarketAccount, MarketStatus}, ErrorCode};
+use crate::{
+    check_mint,
+    events::ClaimMarketFundsEvent,
+    states::{Config, MarketAccount, MarketStatus},
+    ErrorCode,
+};
 
 #[derive(Accounts)]
 #[instruction(id: u32)]
@@ -14,7 +19,7 @@ pub struct ClaimMarketFunds<'info> {
     
     #[account(
         mut,
-        seeds = [b"vault", id.to_le_bytes().as_ref()],
+        seeds = [b"vault", mint.key().as_ref()],
         bump,
         token::mint = mint,
         token::authority = vault
@@ -29,9 +34,15 @@ pub struct ClaimMarketFunds<'info> {
     pub ata: Account<'info, TokenAccount>,
 
     #[account(
+        address = market_acc.mint,
         mint::token_program = token_program
     )]
     pub mint: Account<'info, Mint>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
 
     /// CHECK: Poll authority pubkey
     #[account(
@@ -39,6 +50,7 @@ pub struct ClaimMarketFunds<'info> {
     )]
     pub authority: UncheckedAccount<'info>,
     #[account(
+        mut,
         seeds = [b"market", id.to_le_bytes().as_ref()],
         bump = market_acc.bump,
         has_one = authority
@@ -61,15 +73,10 @@ impl<'info> ClaimMarketFunds<'info> {
             self.payer.key() == self.market_acc.authority,
             ErrorCode::InvalidAuthority
         );
-        check_mint!(self.mint.key());
-
-        let winning_amount  = match self.market_acc.winning_outcome {
-            0 => self.market_acc.votes_revealed[0], //TODO: shares / mint decimals scale 
-            1 => self.market_acc.votes_revealed[1], //TODO: shares / mint decimals scale
-            _ => return Err(ErrorCode::InvalidOutcome.into()),
-        };
+        check_mint!(self.config, self.mint.key());
 
-        let amount = self.market_acc.tvl - winning_amount;
+        // Unclaimed winnings stay reserved, so this withdraws the same surplus before or after the claims
+        let amount = self.market_acc.tvl.saturating_sub(self.market_acc.reserved_liability);
         require!(amount > 0, ErrorCode::InsufficientBalance);
         let transfer_accounts = TransferChecked {
             from: self.vault.to_account_info(),
@@ -77,8 +84,8 @@ impl<'info> ClaimMarketFunds<'info> {
             to: self.ata.to_account_info(),
             authority: self.vault.to_account_info(),
         };
-        let id_bytes = id.to_le_bytes();
-        let signer: &[&[&[u8]]] = &[&[b"vault", id_bytes.as_ref(), &[bump]]];
+        let mint_key = self.mint.key();
+        let signer: &[&[&[u8]]] = &[&[b"vault", mint_key.as_ref(), &[bump]]];
 
         transfer_checked(
             CpiContext::new_with_signer(
@@ -89,7 +96,7 @@ impl<'info> ClaimMarketFunds<'info> {
             amount,
             self.mint.decimals
         )?;
-        self.market_acc.tvl = 0;
+        self.market_acc.tvl -= amount;
 
         emit!(ClaimMarketFundsEvent {
             market_id: id,
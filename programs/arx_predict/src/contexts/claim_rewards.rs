@@ -2,7 +2,15 @@ use anchor_lang::prelude::*;
 use arcium_anchor::prelude::*;
 use arcium_client::idl::arcium::types::CallbackAccount;
 
-use crate::{callbacks::ClaimRewardsCallback, constants::{COMP_DEF_OFFSET_CLAIM_REWARDS, USER_POSITION_SHARES_LENGTH, USER_POSITION_SHARES_OFFSET}, states::MarketStatus, ErrorCode, MarketAccount, SignerAccount, UserPosition, ID, ID_CONST};
+use crate::{
+    callbacks::ClaimRewardsCallback,
+    constants::{
+        COMP_DEF_OFFSET_CLAIM_REWARDS, USER_POSITION_SHARES_LENGTH, USER_POSITION_SHARES_OFFSET,
+    },
+    states::MarketStatus,
+    utils::token_unit,
+    ErrorCode, MarketAccount, SignerAccount, UserAccount, UserPosition, ID, ID_CONST,
+};
 
 #[queue_computation_accounts("claim_rewards", payer)]
 #[derive(Accounts)]
@@ -68,6 +76,7 @@ pub struct ClaimRewards<'info> {
     )]
     pub authority: UncheckedAccount<'info>,
     #[account(
+        mut,
         seeds = [b"market", _id.to_le_bytes().as_ref()],
         bump = market_acc.bump,
         has_one = authority
@@ -80,6 +89,11 @@ pub struct ClaimRewards<'info> {
         bump
     )]
     pub user_position_acc: Box<Account<'info, UserPosition>>,
+    #[account(
+        seeds = [b"user_account", market_acc.mint.as_ref(), payer.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Box<Account<'info, UserAccount>>,
 }
 
 impl<'info> ClaimRewards<'info> {
@@ -89,9 +103,16 @@ impl<'info> ClaimRewards<'info> {
         sign_pda_account_bump: u8,
     ) -> Result<()> {
         require!(self.market_acc.status == MarketStatus::Settled, ErrorCode::MarketNotSettled);
+        require!(self.market_acc.scalar.is_none(), ErrorCode::ScalarMarket);
+        require!(!self.user_position_acc.claimed, ErrorCode::AlreadyClaimed);
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        let trade_seq = self.user_position_acc.begin_trade(0, current_timestamp)?;
+        self.market_acc.last_claim_at = current_timestamp;
 
         let args = vec![
             Argument::PlaintextU8(self.market_acc.winning_outcome),
+            Argument::PlaintextU64(token_unit(self.market_acc.mint_decimals)?),
+            Argument::PlaintextU64(trade_seq),
             Argument::PlaintextU128(self.user_position_acc.nonce),
             Argument::Account(
                 self.user_position_acc.key(),
@@ -112,10 +133,18 @@ impl<'info> ClaimRewards<'info> {
             args,
             None,
             vec![ClaimRewardsCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: self.market_acc.key(),
+                    is_writable: true,
+                },
                 CallbackAccount {
                     pubkey: self.user_position_acc.key(),
                     is_writable: true,
                 },
+                CallbackAccount {
+                    pubkey: self.user_account.key(),
+                    is_writable: true,
+                },
             ])],
         )?;
         Ok(())
@@ -0,0 +1,151 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::{
+    callbacks::ClearBatchCallback,
+    constants::{
+        BATCH_STATS_LENGTH, BATCH_STATS_OFFSET, COMP_DEF_OFFSET_CLEAR_BATCH,
+        MARKET_ACCOUNT_STATS_LENGTH, MARKET_ACCOUNT_VOTE_STATS_OFFSET,
+    },
+    states::{BatchAccount, BatchStatus, MarketStatus},
+    utils::token_unit,
+    ErrorCode, MarketAccount, SignerAccount, ID, ID_CONST,
+};
+
+#[queue_computation_accounts("clear_batch", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _market_id: u32, _batch_id: u64)]
+pub struct ClearBatch<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_CLEAR_BATCH)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS,
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// Sign PDA account for Arcium computations
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        seeds = [b"market", _market_id.to_le_bytes().as_ref()],
+        bump = market_acc.bump,
+    )]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(
+        mut,
+        seeds = [b"batch", _market_id.to_le_bytes().as_ref(), _batch_id.to_le_bytes().as_ref()],
+        bump = batch_acc.bump,
+    )]
+    pub batch_acc: Box<Account<'info, BatchAccount>>,
+}
+
+impl<'info> ClearBatch<'info> {
+    pub fn clear_batch(
+        &mut self,
+        computation_offset: u64,
+        sign_pda_account_bump: u8,
+    ) -> Result<()> {
+        // Batches committed before the close can still clear until the market is settled or expires
+        let now = Clock::get()?.unix_timestamp;
+        self.market_acc.refresh_status(now);
+        require!(
+            self.market_acc.status == MarketStatus::Active || self.market_acc.status == MarketStatus::Closed,
+            ErrorCode::TradingClosed
+        );
+        require!(self.batch_acc.status == BatchStatus::Open, ErrorCode::BatchNotOpen);
+        require!(!self.batch_acc.pending, ErrorCode::ComputationPending);
+        require!(self.batch_acc.num_trades > 0, ErrorCode::BatchEmpty);
+        // Every commit of the epoch clears together, new ones already go to the next epoch's batch
+        require!(now >= self.batch_acc.ends_at, ErrorCode::BatchEpochOpen);
+
+        self.batch_acc.status = BatchStatus::Clearing;
+        self.batch_acc.clearing_since = now;
+
+        let args = vec![
+            Argument::PlaintextU64(self.market_acc.liquidity_parameter),
+            Argument::PlaintextU8(self.market_acc.num_options),
+            Argument::PlaintextU64(token_unit(self.market_acc.mint_decimals)?),
+            Argument::PlaintextU64(self.market_acc.state_version),
+            Argument::PlaintextU128(self.batch_acc.nonce),
+            Argument::Account(
+                self.batch_acc.key(),
+                BATCH_STATS_OFFSET,
+                BATCH_STATS_LENGTH,
+            ),
+            Argument::PlaintextU128(self.market_acc.nonce),
+            Argument::Account(
+                self.market_acc.key(),
+                MARKET_ACCOUNT_VOTE_STATS_OFFSET,
+                MARKET_ACCOUNT_STATS_LENGTH,
+            ),
+        ];
+
+        // Set the bump for the sign_pda_account
+        self.sign_pda_account.bump = sign_pda_account_bump;
+
+        queue_computation(
+            self,
+            computation_offset,
+            args,
+            None,
+            vec![ClearBatchCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: self.market_acc.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: self.batch_acc.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,89 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{CLOSE_MARKET_GRACE_PERIOD, PENDING_TRADE_TIMEOUT},
+    events::MarketClosedEvent,
+    states::{ClosedMarket, MarketAccount, UserAccount},
+    ErrorCode,
+};
+
+#[derive(Accounts)]
+#[instruction(_id: u32)]
+pub struct CloseMarket<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        close = payer,
+        seeds = [b"market", _id.to_le_bytes().as_ref()],
+        bump = market_acc.bump
+    )]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + ClosedMarket::INIT_SPACE,
+        seeds = [b"closed_market", _id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    pub closed_market_acc: Account<'info, ClosedMarket>,
+    #[account(
+        mut,
+        seeds = [b"user_account", market_acc.mint.as_ref(), payer.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Box<Account<'info, UserAccount>>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> CloseMarket<'info> {
+    pub fn close_market(&mut self, bump: u8) -> Result<()> {
+        require!(
+            self.payer.key() == self.market_acc.authority,
+            ErrorCode::InvalidAuthority
+        );
+        require!(self.market_acc.is_finalized(), ErrorCode::MarketNotFinalized);
+        // A position stays unclaimed until its claim calls back, so once all of them claimed nothing is in flight
+        if self.market_acc.unclaimed_positions > 0 {
+            let current_timestamp = Clock::get()?.unix_timestamp;
+            require!(
+                current_timestamp >= self.market_acc.finalized_at + CLOSE_MARKET_GRACE_PERIOD,
+                ErrorCode::PositionsUnclaimed
+            );
+            // A claim callback landing on a closed market would fail, wait until the last one could have returned
+            require!(
+                current_timestamp >= self.market_acc.last_claim_at + PENDING_TRADE_TIMEOUT,
+                ErrorCode::ComputationPending
+            );
+        }
+        require!(
+            self.market_acc.protocol_fees == 0
+                && self.market_acc.creator_fees == 0
+                && self.market_acc.lp_fees == 0,
+            ErrorCode::FeesNotClaimed
+        );
+        require!(
+            self.market_acc.proposer_bond == 0 && self.market_acc.disputer_bond == 0,
+            ErrorCode::BondsNotClaimed
+        );
+        // Settling an order needs the market account, its escrow would be stuck once the market is gone
+        require!(self.market_acc.open_orders == 0, ErrorCode::OrdersOpen);
+        // Same for batch tickets, settling or refunding one reads the market
+        require!(self.market_acc.open_tickets == 0, ErrorCode::TicketsOpen);
+
+        self.closed_market_acc.bump = bump;
+
+        // Rounding dust and, past the grace period, unclaimed payouts go back to the authority
+        let remaining = self.market_acc.tvl;
+        self.market_acc.tvl = 0;
+        self.user_account.credit(remaining);
+
+        emit!(MarketClosedEvent {
+            market_id: self.market_acc.id,
+            remaining,
+            unclaimed_positions: self.market_acc.unclaimed_positions,
+        });
+
+        Ok(())
+    }
+}
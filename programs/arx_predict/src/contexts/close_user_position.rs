@@ -0,0 +1,26 @@
+use anchor_lang::prelude::*;
+
+use crate::{states::UserPosition, ErrorCode};
+
+#[derive(Accounts)]
+#[instruction(_id: u32)]
+pub struct CloseUserPosition<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        close = payer,
+        seeds = [b"user_position", _id.to_le_bytes().as_ref(), payer.key().as_ref()],
+        bump = user_position_acc.bump
+    )]
+    pub user_position_acc: Box<Account<'info, UserPosition>>,
+}
+
+impl<'info> CloseUserPosition<'info> {
+    /// Only claimed positions close, so the market doesn't need to be around anymore
+    pub fn close_user_position(&mut self) -> Result<()> {
+        require!(!self.user_position_acc.pending, ErrorCode::ComputationPending);
+        require!(self.user_position_acc.claimed, ErrorCode::PositionNotClaimed);
+        Ok(())
+    }
+}
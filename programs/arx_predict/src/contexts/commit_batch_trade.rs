@@ -0,0 +1,190 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::{
+    callbacks::CommitBatchTradeCallback,
+    constants::{BATCH_STATS_LENGTH, BATCH_STATS_OFFSET, COMP_DEF_OFFSET_COMMIT_BATCH_TRADE},
+    events::BatchTradeCommittedEvent,
+    states::{BatchAccount, BatchStatus, BatchTicket},
+    ErrorCode, MarketAccount, SignerAccount, UserAccount, UserPosition, ID, ID_CONST,
+};
+
+#[queue_computation_accounts("commit_batch_trade", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, market_id: u32, batch_id: u64)]
+pub struct CommitBatchTrade<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_COMMIT_BATCH_TRADE)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS,
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// Sign PDA account for Arcium computations
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        seeds = [b"market", market_id.to_le_bytes().as_ref()],
+        bump = market_acc.bump,
+    )]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(
+        mut,
+        seeds = [b"batch", market_id.to_le_bytes().as_ref(), batch_id.to_le_bytes().as_ref()],
+        bump = batch_acc.bump,
+    )]
+    pub batch_acc: Box<Account<'info, BatchAccount>>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + BatchTicket::INIT_SPACE,
+        seeds = [b"batch_ticket", market_id.to_le_bytes().as_ref(), batch_id.to_le_bytes().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub batch_ticket_acc: Box<Account<'info, BatchTicket>>,
+    // Settling the ticket writes the shares into the position, it has to exist before committing
+    #[account(
+        seeds = [b"user_position", market_id.to_le_bytes().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub user_position_acc: Box<Account<'info, UserPosition>>,
+    #[account(
+        mut,
+        seeds = [b"user_account", market_acc.mint.as_ref(), payer.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Box<Account<'info, UserAccount>>,
+}
+
+impl<'info> CommitBatchTrade<'info> {
+    pub fn commit_batch_trade(
+        &mut self,
+        market_id: u32,
+        batch_id: u64,
+        vote: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+        amount: u64,
+        computation_offset: u64,
+        bump: u8,
+        sign_pda_account_bump: u8,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(self.market_acc.is_trading(now), ErrorCode::TradingClosed);
+        require!(self.market_acc.batch_mode, ErrorCode::BatchModeDisabled);
+        require!(
+            self.batch_acc.status == BatchStatus::Open && now < self.batch_acc.ends_at,
+            ErrorCode::BatchNotOpen
+        );
+        // Commits are applied one at a time so none of them is lost
+        require!(!self.batch_acc.pending, ErrorCode::ComputationPending);
+        // The fee comes out of the commit up front, it is refunded with the budget if the batch fails
+        let budget = self.market_acc.cost_before_fee(amount);
+        let fee = amount - budget;
+        require!(budget > 0, ErrorCode::InvalidAmount);
+
+        // The whole budget is spent when the batch clears
+        self.user_account.debit(amount)?;
+        self.batch_acc.deposits += budget;
+        self.batch_acc.fees += fee;
+        self.batch_acc.num_trades += 1;
+        self.market_acc.open_tickets += 1;
+        self.batch_acc.pending = true;
+
+        self.batch_ticket_acc.bump = bump;
+        self.batch_ticket_acc.owner = self.payer.key();
+        self.batch_ticket_acc.market_id = market_id;
+        self.batch_ticket_acc.batch_id = batch_id;
+        self.batch_ticket_acc.vote = vote;
+        self.batch_ticket_acc.vote_encryption_pubkey = vote_encryption_pubkey;
+        self.batch_ticket_acc.vote_nonce = vote_nonce;
+        self.batch_ticket_acc.amount = budget;
+        self.batch_ticket_acc.fee = fee;
+        self.batch_ticket_acc.settled = false;
+
+        let args = vec![
+            Argument::ArcisPubkey(vote_encryption_pubkey),
+            Argument::PlaintextU128(vote_nonce),
+            Argument::EncryptedU8(vote),
+            Argument::PlaintextU64(budget),
+            Argument::PlaintextU8(self.market_acc.num_options),
+            Argument::PlaintextU128(self.batch_acc.nonce),
+            Argument::Account(
+                self.batch_acc.key(),
+                BATCH_STATS_OFFSET,
+                BATCH_STATS_LENGTH,
+            ),
+        ];
+
+        // Set the bump for the sign_pda_account
+        self.sign_pda_account.bump = sign_pda_account_bump;
+
+        queue_computation(
+            self,
+            computation_offset,
+            args,
+            None,
+            vec![CommitBatchTradeCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: self.batch_acc.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+
+        emit!(BatchTradeCommittedEvent {
+            market_id,
+            batch_id,
+            amount: budget,
+            deposits: self.batch_acc.deposits,
+        });
+
+        Ok(())
+    }
+}
@@ -2,11 +2,18 @@ use anchor_lang::prelude::*;
 use arcium_anchor::prelude::*;
 use arcium_client::idl::arcium::types::CallbackAccount;
 
-use crate::{callbacks::InitMarketStatsCallback, check_admin, constants::{LN_2_SCALED}, states::MarketStatus, ErrorCode, MarketAccount, SignerAccount, COMP_DEF_OFFSET_INIT_MARKET_STATS, ID, ID_CONST, MAX_OPTIONS};
+use crate::{
+    callbacks::InitMarketStatsCallback,
+    check_admin, check_mint,
+    constants::{LN_SCALE, LN_SCALED, MIN_OPTIONS},
+    states::{Config, MarketStatus, ResolutionSource, RevealConfig, ScalarBounds, UserAccount},
+    ErrorCode, MarketAccount, SignerAccount, COMP_DEF_OFFSET_INIT_MARKET_STATS, ID, ID_CONST,
+    MAX_OPTIONS,
+};
 
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Token, Mint, TokenAccount}
+    token::{Token, Mint}
 };
 
 #[queue_computation_accounts("init_market_stats", payer)]
@@ -77,20 +84,30 @@ pub struct CreateMarket<'info> {
         bump,
     )]
     pub market_acc: Account<'info, MarketAccount>,
+    #[account(
+        seeds = [b"closed_market", id.to_le_bytes().as_ref()],
+        bump,
+    )]
+    /// CHECK: closed_market_acc, only close_market creates it, the id is taken once it exists
+    pub closed_market_acc: UncheckedAccount<'info>,
 
+    // The creator's deposit pays for the market maker
     #[account(
         mut,
-        seeds = [b"vault", id.to_le_bytes().as_ref()],
-        bump,
-        token::mint = mint,
-        token::authority = vault
+        seeds = [b"user_account", mint.key().as_ref(), payer.key().as_ref()],
+        bump = user_account.bump,
     )]
-    pub vault: Box<Account<'info, TokenAccount>>,
+    pub user_account: Box<Account<'info, UserAccount>>,
 
     #[account(
         mint::token_program = token_program
     )]
     pub mint: Account<'info, Mint>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Box<Account<'info, Config>>,
 }
 
 impl<'info> CreateMarket<'info> {
@@ -98,9 +115,16 @@ impl<'info> CreateMarket<'info> {
         &mut self,
         id: u32,
         question: String,
-        options: [String; MAX_OPTIONS],
+        options: Vec<String>,
         liquidity_parameter: u64,
         nonce: u128,
+        batch_epoch: i64,
+        trading_ends_at: i64,
+        resolve_by: i64,
+        resolution_source: ResolutionSource,
+        resolvers: Vec<Pubkey>,
+        scalar: Option<ScalarBounds>,
+        reveal: RevealConfig,
         computation_offset: u64,
         bump: u8,
         sign_pda_account_bump: u8,
@@ -108,17 +132,32 @@ impl<'info> CreateMarket<'info> {
         // Validations
         require!(liquidity_parameter >= 10, ErrorCode::InvalidLiquidityParameter);
         require!(self.market_acc.status == MarketStatus::Inactive, ErrorCode::MarketInactive);
-        require!(options.len() == MAX_OPTIONS, ErrorCode::InvalidNumOptions);
+        require!(self.closed_market_acc.data_is_empty(), ErrorCode::MarketIdClosed);
+        require!(
+            options.len() >= MIN_OPTIONS && options.len() <= MAX_OPTIONS,
+            ErrorCode::InvalidNumOptions
+        );
         require!(!question.is_empty(), ErrorCode::InvalidQuestion);
-        check_admin!(self.payer.key());
+        require!(batch_epoch >= 0, ErrorCode::InvalidBatchEpoch);
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        require!(
+            current_timestamp < trading_ends_at && trading_ends_at < resolve_by,
+            ErrorCode::InvalidMarketTimes
+        );
+        check_admin!(self.config, self.payer.key());
+        check_mint!(self.config, self.mint.key());
         for option in &options {
             require!(!option.is_empty(), ErrorCode::EmptyOption);
         }
         
-        //Market maker has paid  b*ln(MAX_OPTIONS)
-        // Note: this assumes that fund_market and this is in the same instruction
-        let expected_funding_amount = ((liquidity_parameter as u128 * LN_2_SCALED as u128) / 10u128.pow(16)) as u64;
-        require!(self.vault.amount >= expected_funding_amount, ErrorCode::MarketNotFunded);
+        //Market maker needs b*ln(num_options) tokens, taken from the creator's deposit
+        let num_options = options.len() as u8;
+        let expected_funding_amount = (liquidity_parameter as u128
+            * LN_SCALED[num_options as usize] as u128
+            * 10u128.pow(self.mint.decimals as u32)
+            / LN_SCALE) as u64;
+        require!(self.user_account.balance >= expected_funding_amount, ErrorCode::MarketNotFunded);
+        self.user_account.debit(expected_funding_amount)?;
 
         self.market_acc.id = id;
         self.market_acc.question = question;
@@ -126,21 +165,51 @@ impl<'info> CreateMarket<'info> {
         self.market_acc.authority = self.payer.key();
         self.market_acc.nonce = nonce;
         self.market_acc.options = options;
+        self.market_acc.num_options = num_options;
         self.market_acc.vote_state = [[0; 32]; MAX_OPTIONS];
         self.market_acc.probs = [[0; 32]; MAX_OPTIONS];
         self.market_acc.cost = [0; 32];
+        self.market_acc.total_cost_basis = [0; 32];
+        self.market_acc.refund_pool = 0;
+        self.market_acc.reserved_liability = 0;
+        self.market_acc.complete_sets = 0;
+        self.market_acc.set_collateral = 0;
         self.market_acc.liquidity_parameter = liquidity_parameter;
         self.market_acc.status = MarketStatus::Active;
-        self.market_acc.tvl = self.vault.amount;
-        self.market_acc.probs_revealed = [0.0; MAX_OPTIONS];
+        self.market_acc.tvl = expected_funding_amount;
+        self.market_acc.probs_revealed = [0; MAX_OPTIONS];
         self.market_acc.mint = self.mint.key();
         self.market_acc.mint_decimals = self.mint.decimals;
+        // A batch epoch puts the market in batch mode, zero trades continuously
+        self.market_acc.batch_mode = batch_epoch > 0;
+        self.market_acc.batch_epoch = batch_epoch;
+        self.market_acc.batch_epoch_start = current_timestamp;
+        self.market_acc.state_version = 0;
+        self.market_acc.trading_ends_at = trading_ends_at;
+        self.market_acc.resolve_by = resolve_by;
+        self.market_acc.liquidity_provider = self.payer.key();
+        self.market_acc.protocol_fee_bps = self.config.protocol_fee_bps;
+        self.market_acc.creator_fee_bps = self.config.creator_fee_bps;
+        self.market_acc.lp_fee_bps = self.config.lp_fee_bps;
+        self.market_acc.protocol_fees = 0;
+        self.market_acc.creator_fees = 0;
+        self.market_acc.lp_fees = 0;
+        self.market_acc.unclaimed_positions = 0;
+        self.market_acc.open_orders = 0;
+        self.market_acc.open_tickets = 0;
+        self.market_acc.finalized_at = 0;
+        self.market_acc.last_claim_at = 0;
+        self.market_acc.set_scalar(scalar)?;
+        self.market_acc.set_resolution(resolution_source, resolvers)?;
+        self.market_acc.set_reveal(reveal)?;
+        self.market_acc.revealed_version = 0;
 
         
 
         let args = vec![
             Argument::PlaintextU128(nonce),
             Argument::PlaintextU64(liquidity_parameter),
+            Argument::PlaintextU8(num_options),
         ];
         // Set the bump for the sign_pda_account
         // Note: The bump will be handled by the Arcium program
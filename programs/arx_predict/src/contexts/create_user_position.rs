@@ -2,7 +2,10 @@ use anchor_lang::prelude::*;
 use arcium_anchor::prelude::*;
 use arcium_client::idl::arcium::types::CallbackAccount;
 
-use crate::{callbacks::InitUserPositionCallback, ErrorCode, SignerAccount, UserPosition, COMP_DEF_OFFSET_INIT_USER_POSITION, ID, ID_CONST, MAX_OPTIONS};
+use crate::{
+    callbacks::InitUserPositionCallback, ErrorCode, MarketAccount, SignerAccount, UserPosition,
+    COMP_DEF_OFFSET_INIT_USER_POSITION, ID, ID_CONST, MAX_OPTIONS,
+};
 
 #[queue_computation_accounts("init_user_position", payer)]
 #[derive(Accounts)]
@@ -70,6 +73,12 @@ pub struct CreateUserPosition<'info> {
         bump
     )]
     pub user_position_acc: Account<'info, UserPosition>,
+    #[account(
+        mut,
+        seeds = [b"market", market_id.to_le_bytes().as_ref()],
+        bump = market_acc.bump,
+    )]
+    pub market_acc: Box<Account<'info, MarketAccount>>,
 }
 
 impl<'info> CreateUserPosition<'info> {
@@ -81,16 +90,23 @@ impl<'info> CreateUserPosition<'info> {
         bump: u8,
         sign_pda_account_bump: u8,
     ) -> Result<()> {
+        require!(!self.market_acc.is_finalized(), ErrorCode::MarketFinalized);
         self.user_position_acc.bump = bump;
         self.user_position_acc.nonce = nonce;
         self.user_position_acc.shares = [[0; 32]; MAX_OPTIONS];
-        self.user_position_acc.balance = 0;
+        self.user_position_acc.cost_basis = [[0; 32]; MAX_OPTIONS];
         self.user_position_acc.market_id = market_id;
+        self.user_position_acc.mint = self.market_acc.mint;
+        self.user_position_acc.trade_seq = 0;
+        self.user_position_acc.pending = false;
+        self.user_position_acc.locked_amount = 0;
+        self.user_position_acc.pending_since = 0;
+        self.user_position_acc.claimed = false;
+        self.user_position_acc.minted_sets = 0;
+        // Counted until it claims, the market can't be closed early while a position could still claim
+        self.market_acc.unclaimed_positions += 1;
         let args = vec![Argument::PlaintextU128(nonce)];
 
-        // Set the bump for the sign_pda_account
-        // Note: The bump will be handled by the Arcium program
-        
         // Set the bump for the sign_pda_account
         self.sign_pda_account.bump = sign_pda_account_bump;
         
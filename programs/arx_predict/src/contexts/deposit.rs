@@ -4,17 +4,18 @@ use anchor_spl::{
     token::{Mint, Token, TokenAccount, TransferChecked, transfer_checked}
 };
 
-use crate::{check_mint, states::UserPosition};
+use crate::{check_mint, states::{Config, UserAccount}};
 
 #[derive(Accounts)]
-#[instruction(_id: u32)]
-pub struct SendPayment<'info> {
+pub struct Deposit<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     
+    // One vault per mint holds the deposits and the funds of every market trading it
     #[account(
-        mut,
-        seeds = [b"vault", _id.to_le_bytes().as_ref()],
+        init_if_needed,
+        payer = payer,
+        seeds = [b"vault", mint.key().as_ref()],
         bump,
         token::mint = mint,
         token::authority = vault
@@ -32,25 +33,33 @@ pub struct SendPayment<'info> {
         mint::token_program = token_program
     )]
     pub mint: Account<'info, Mint>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
 
     #[account(
-        mut,
-        seeds = [b"user_position", _id.to_le_bytes().as_ref(), payer.key().as_ref()],
+        init_if_needed,
+        payer = payer,
+        space = 8 + UserAccount::INIT_SPACE,
+        seeds = [b"user_account", mint.key().as_ref(), payer.key().as_ref()],
         bump
     )]
-    pub user_position_acc: Account<'info, UserPosition>,
+    pub user_account: Account<'info, UserAccount>,
 
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-impl<'info> SendPayment<'info> {
-    pub fn send_payment(
+impl<'info> Deposit<'info> {
+    pub fn deposit(
         &mut self,
-        amount: u64
+        amount: u64,
+        bump: u8,
     ) -> Result<()> {
-        check_mint!(self.mint.key());
+        check_mint!(self.config, self.mint.key());
         let transfer_accounts = TransferChecked {
             from: self.ata.to_account_info(),
             mint: self.mint.to_account_info(),
@@ -66,7 +75,10 @@ impl<'info> SendPayment<'info> {
             self.mint.decimals
         )?;
 
-        self.user_position_acc.balance += amount;
+        self.user_account.bump = bump;
+        self.user_account.owner = self.payer.key();
+        self.user_account.mint = self.mint.key();
+        self.user_account.credit(amount);
         Ok(())
     }
-} 
\ No newline at end of file
+}
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::OutcomeDisputedEvent,
+    states::{MarketAccount, MarketStatus, ResolutionSource, UserAccount},
+    ErrorCode,
+};
+
+#[derive(Accounts)]
+#[instruction(_id: u32)]
+pub struct DisputeOutcome<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"market", _id.to_le_bytes().as_ref()],
+        bump = market_acc.bump
+    )]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(
+        mut,
+        seeds = [b"user_account", market_acc.mint.as_ref(), payer.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Box<Account<'info, UserAccount>>,
+}
+
+impl<'info> DisputeOutcome<'info> {
+    /// Matches the proposer's bond and escalates the market to the resolvers, or the config authority without any
+    pub fn dispute_outcome(&mut self) -> Result<()> {
+        let ResolutionSource::Optimistic { bond, dispute_window, .. } = self.market_acc.resolution_source else {
+            return Err(ErrorCode::InvalidResolutionSource.into());
+        };
+        require!(self.market_acc.status == MarketStatus::Proposed, ErrorCode::NoProposal);
+        require!(
+            Clock::get()?.unix_timestamp < self.market_acc.proposed_at + dispute_window,
+            ErrorCode::DisputeWindowClosed
+        );
+
+        self.user_account.debit(bond)?;
+        self.market_acc.status = MarketStatus::Disputed;
+        self.market_acc.disputer = self.payer.key();
+        self.market_acc.disputer_bond = bond;
+
+        emit!(OutcomeDisputedEvent {
+            market_id: self.market_acc.id,
+            disputer: self.payer.key(),
+            bond,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::{MarketExpiredEvent, MarketVoidedEvent},
+    states::{MarketAccount, MarketStatus},
+    ErrorCode,
+};
+
+#[derive(Accounts)]
+#[instruction(_id: u32)]
+pub struct ExpireMarket<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"market", _id.to_le_bytes().as_ref()],
+        bump = market_acc.bump
+    )]
+    pub market_acc: Account<'info, MarketAccount>,
+}
+
+impl<'info> ExpireMarket<'info> {
+    /// Permissionless, voids a market the authority didn't settle before resolve_by so every position can claim
+    /// its cost basis refund without waiting on the authority
+    pub fn expire_market(&mut self) -> Result<()> {
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        self.market_acc.refresh_status(current_timestamp);
+        require!(self.market_acc.status == MarketStatus::Expired, ErrorCode::MarketNotExpired);
+        self.market_acc.void(current_timestamp);
+
+        emit!(MarketExpiredEvent {
+            market_id: self.market_acc.id,
+            resolve_by: self.market_acc.resolve_by,
+        });
+        emit!(MarketVoidedEvent {
+            market_id: self.market_acc.id,
+            refund_pool: self.market_acc.refund_pool,
+        });
+
+        Ok(())
+    }
+}
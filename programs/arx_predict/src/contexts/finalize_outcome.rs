@@ -0,0 +1,146 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+use crate::constants::{
+    COMP_DEF_OFFSET_REVEAL_MARKET, MARKET_ACCOUNT_STATS_LENGTH, MARKET_ACCOUNT_VOTE_STATS_OFFSET,
+    NO_RESOLVER_VOTE,
+};
+use crate::SignerAccount;
+use crate::utils::token_unit;
+use crate::{states::{MarketStatus, ResolutionSource}, ErrorCode, MarketAccount};
+use crate::{ID, ID_CONST, callbacks::RevealMarketCallback};
+
+#[queue_computation_accounts("reveal_market", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, id: u32)]
+pub struct FinalizeOutcome<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_REVEAL_MARKET)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS,
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// Sign PDA account for Arcium computations
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        seeds = [b"market", id.to_le_bytes().as_ref()],
+        bump = market_acc.bump
+    )]
+    pub market_acc: Account<'info, MarketAccount>,
+}
+
+impl<'info> FinalizeOutcome<'info> {
+    /// Settles an optimistic market with the undisputed proposal or the ruling on its dispute, anyone can call it
+    pub fn finalize_outcome(
+        &mut self,
+        computation_offset: u64,
+        sign_pda_account_bump: u8,
+    ) -> Result<()> {
+        let ResolutionSource::Optimistic { dispute_window, threshold, .. } = self.market_acc.resolution_source else {
+            return Err(ErrorCode::InvalidResolutionSource.into());
+        };
+        let winner = match self.market_acc.status {
+            MarketStatus::Proposed => {
+                require!(
+                    Clock::get()?.unix_timestamp >= self.market_acc.proposed_at + dispute_window,
+                    ErrorCode::DisputeWindowOpen
+                );
+                self.market_acc.proposed_outcome
+            }
+            MarketStatus::Disputed => {
+                // The committee's outcome is locked in here, a stale reveal can't settle on a changed vote
+                if self.market_acc.ruled_outcome == NO_RESOLVER_VOTE && !self.market_acc.resolvers.is_empty() {
+                    self.market_acc.ruled_outcome = self
+                        .market_acc
+                        .multisig_outcome(threshold)
+                        .ok_or(ErrorCode::ResolutionPending)?;
+                }
+                require!(
+                    self.market_acc.ruled_outcome != NO_RESOLVER_VOTE,
+                    ErrorCode::ResolutionPending
+                );
+                self.market_acc.ruled_outcome
+            }
+            // The last reveal came back stale, it is queued again for the outcome it locked
+            MarketStatus::Settling => self.market_acc.winning_outcome,
+            _ => return Err(ErrorCode::NoProposal.into()),
+        };
+        self.market_acc.begin_settlement(winner);
+
+        let args = vec![
+            Argument::PlaintextU128(self.market_acc.nonce),
+            Argument::Account(
+                self.market_acc.key(),
+                MARKET_ACCOUNT_VOTE_STATS_OFFSET,
+                MARKET_ACCOUNT_STATS_LENGTH,
+            ),
+            Argument::PlaintextU8(winner),
+            Argument::PlaintextU64(self.market_acc.state_version),
+            Argument::PlaintextBool(self.market_acc.scalar.is_some()),
+            Argument::PlaintextU64(self.market_acc.long_payout),
+            Argument::PlaintextU64(token_unit(self.market_acc.mint_decimals)?),
+            Argument::PlaintextU64(self.market_acc.complete_sets),
+        ];
+
+        self.sign_pda_account.bump = sign_pda_account_bump;
+
+        queue_computation(
+            self,
+            computation_offset,
+            args,
+            None,
+            vec![RevealMarketCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: self.market_acc.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+}
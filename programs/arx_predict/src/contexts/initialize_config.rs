@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::{program::ArxPredict, states::{Config, ConfigParams}, ErrorCode};
+
+#[derive(Accounts)]
+pub struct InitializeConfig<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + Config::INIT_SPACE,
+        seeds = [b"config"],
+        bump,
+    )]
+    pub config: Account<'info, Config>,
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, ArxPredict>,
+    /// Only the upgrade authority can create the config, so it can't be front-run after a deploy
+    #[account(constraint = program_data.upgrade_authority_address == Some(payer.key()) @ ErrorCode::InvalidAuthority)]
+    pub program_data: Account<'info, ProgramData>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeConfig<'info> {
+    pub fn initialize_config(&mut self, params: ConfigParams, bump: u8) -> Result<()> {
+        self.config.bump = bump;
+        self.config.authority = self.payer.key();
+        self.config.pending_authority = None;
+        self.config.set_params(params)
+    }
+}
@@ -0,0 +1,181 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::{
+    callbacks::MatchOrdersCallback,
+    constants::{
+        COMP_DEF_OFFSET_MATCH_ORDERS, LIMIT_ORDER_LENGTH, LIMIT_ORDER_OFFSET,
+        MARKET_ACCOUNT_STATS_LENGTH, MARKET_ACCOUNT_VOTE_STATS_OFFSET, MAX_MATCH_ORDERS,
+    },
+    states::{LimitOrder, OrderStatus},
+    utils::token_unit,
+    ErrorCode, MarketAccount, SignerAccount, ID, ID_CONST,
+};
+
+#[queue_computation_accounts("match_orders", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, market_id: u32)]
+pub struct MatchOrders<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_MATCH_ORDERS)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS,
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// Sign PDA account for Arcium computations
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        seeds = [b"market", market_id.to_le_bytes().as_ref()],
+        bump = market_acc.bump,
+    )]
+    pub market_acc: Account<'info, MarketAccount>,
+    // Orders are walked in this order, the optional ones fill the slots after order_0 without gaps
+    #[account(mut)]
+    pub order_0: Box<Account<'info, LimitOrder>>,
+    #[account(mut)]
+    pub order_1: Option<Box<Account<'info, LimitOrder>>>,
+    #[account(mut)]
+    pub order_2: Option<Box<Account<'info, LimitOrder>>>,
+    #[account(mut)]
+    pub order_3: Option<Box<Account<'info, LimitOrder>>>,
+}
+
+impl<'info> MatchOrders<'info> {
+    pub fn match_orders(
+        &mut self,
+        computation_offset: u64,
+        sign_pda_account_bump: u8,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(self.market_acc.is_trading(now), ErrorCode::TradingClosed);
+        require!(!self.market_acc.batch_mode, ErrorCode::BatchModeEnabled);
+        let market_id = self.market_acc.id;
+        let market_version = self.market_acc.state_version;
+
+        // Key and nonce of each order, the circuit reads them in slot order
+        let mut orders: Vec<(Pubkey, u128)> = Vec::with_capacity(MAX_MATCH_ORDERS);
+        let slots = [
+            Some(&mut self.order_0),
+            self.order_1.as_mut(),
+            self.order_2.as_mut(),
+            self.order_3.as_mut(),
+        ];
+        for (i, slot) in slots.into_iter().enumerate() {
+            let Some(order) = slot else {
+                continue;
+            };
+            require!(
+                orders.len() == i && orders.iter().all(|(key, _)| *key != order.key()),
+                ErrorCode::InvalidOrders
+            );
+            require!(
+                order.market_id == market_id
+                    && (order.status == OrderStatus::Open
+                        || (order.status == OrderStatus::Matching && order.is_stuck(now))),
+                ErrorCode::OrderNotOpen
+            );
+            order.match_version = market_version;
+            order.begin(OrderStatus::Matching, now);
+            orders.push((order.key(), order.nonce));
+        }
+        let num_orders = orders.len();
+
+        let mut args = vec![
+            Argument::PlaintextU64(market_version),
+            Argument::PlaintextU64(self.market_acc.liquidity_parameter),
+            Argument::PlaintextU8(self.market_acc.num_options),
+            Argument::PlaintextU64(token_unit(self.market_acc.mint_decimals)?),
+            Argument::PlaintextU8(num_orders as u8),
+            Argument::PlaintextU128(self.market_acc.nonce),
+            Argument::Account(
+                self.market_acc.key(),
+                MARKET_ACCOUNT_VOTE_STATS_OFFSET,
+                MARKET_ACCOUNT_STATS_LENGTH,
+            ),
+        ];
+        // Empty slots are padded with order_0, the circuit leaves them untouched
+        for i in 0..MAX_MATCH_ORDERS {
+            let (key, nonce) = orders.get(i).copied().unwrap_or(orders[0]);
+            args.push(Argument::PlaintextU128(nonce));
+            args.push(Argument::Account(key, LIMIT_ORDER_OFFSET, LIMIT_ORDER_LENGTH));
+        }
+
+        let mut callback_accounts = vec![CallbackAccount {
+            pubkey: self.market_acc.key(),
+            is_writable: true,
+        }];
+        // An absent optional account is passed as the program id
+        for i in 0..MAX_MATCH_ORDERS {
+            callback_accounts.push(match orders.get(i) {
+                Some((key, _)) => CallbackAccount {
+                    pubkey: *key,
+                    is_writable: true,
+                },
+                None => CallbackAccount {
+                    pubkey: ID,
+                    is_writable: false,
+                },
+            });
+        }
+
+        // Set the bump for the sign_pda_account
+        self.sign_pda_account.bump = sign_pda_account_bump;
+
+        queue_computation(
+            self,
+            computation_offset,
+            args,
+            None,
+            vec![MatchOrdersCallback::callback_ix(&callback_accounts)],
+        )?;
+
+        Ok(())
+    }
+}
@@ -1,29 +1,93 @@
 pub mod comp_def;
+pub mod initialize_config;
+pub mod update_config;
+pub mod transfer_config_authority;
+pub mod accept_config_authority;
 pub mod callbacks;
 pub mod create_market;
 // pub mod reveal_market;
 pub mod create_user_position;
-pub mod send_payment;
+pub mod deposit;
 pub mod reveal_probs;
 pub mod buy_shares;
 pub mod sell_shares;
-pub mod withdraw_payment;
+pub mod buy_with_amount;
+pub mod buy_shares_private;
+pub mod sell_shares_private;
+pub mod open_batch;
+pub mod commit_batch_trade;
+pub mod clear_batch;
+pub mod settle_batch_trade;
+pub mod place_limit_order;
+pub mod match_orders;
+pub mod settle_limit_order;
+pub mod mint_complete_set;
+pub mod redeem_complete_set;
+pub mod refund_batch_trade;
+pub mod release_pending_trade;
+pub mod expire_market;
+pub mod withdraw;
 pub mod settle_market;
+pub mod submit_resolution;
+pub mod propose_outcome;
+pub mod dispute_outcome;
+pub mod finalize_outcome;
 pub mod claim_rewards;
-pub mod fund_market;
+pub mod claim_rewards_scalar;
+pub mod view_position;
+pub mod value_position;
+pub mod quote_trade;
+pub mod cancel_market;
+pub mod claim_refund;
 pub mod claim_market_funds;
+pub mod claim_fees;
+pub mod claim_bond;
+pub mod close_user_position;
+pub mod close_market;
 
 pub use comp_def::*;
+pub use initialize_config::*;
+pub use update_config::*;
+pub use transfer_config_authority::*;
+pub use accept_config_authority::*;
 pub use callbacks::*;
 pub use create_market::*;
 // pub use reveal_result::*;
 pub use create_user_position::*;
-pub use send_payment::*;
+pub use deposit::*;
 pub use reveal_probs::*;
 pub use buy_shares::*;
 pub use sell_shares::*;
-pub use withdraw_payment::*;
+pub use buy_with_amount::*;
+pub use buy_shares_private::*;
+pub use sell_shares_private::*;
+pub use open_batch::*;
+pub use commit_batch_trade::*;
+pub use clear_batch::*;
+pub use settle_batch_trade::*;
+pub use place_limit_order::*;
+pub use match_orders::*;
+pub use settle_limit_order::*;
+pub use mint_complete_set::*;
+pub use redeem_complete_set::*;
+pub use refund_batch_trade::*;
+pub use release_pending_trade::*;
+pub use expire_market::*;
+pub use withdraw::*;
 pub use settle_market::*;
+pub use submit_resolution::*;
+pub use propose_outcome::*;
+pub use dispute_outcome::*;
+pub use finalize_outcome::*;
 pub use claim_rewards::*;
-pub use fund_market::*;
-pub use claim_market_funds::*;
\ No newline at end of file
+pub use claim_rewards_scalar::*;
+pub use view_position::*;
+pub use value_position::*;
+pub use quote_trade::*;
+pub use cancel_market::*;
+pub use claim_refund::*;
+pub use claim_market_funds::*;
+pub use claim_fees::*;
+pub use claim_bond::*;
+pub use close_user_position::*;
+pub use close_market::*;
\ No newline at end of file
@@ -0,0 +1,133 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::{
+    callbacks::InitBatchStatsCallback,
+    constants::{COMP_DEF_OFFSET_INIT_BATCH_STATS, MAX_OPTIONS},
+    states::{BatchAccount, BatchStatus},
+    ErrorCode, MarketAccount, SignerAccount, ID, ID_CONST,
+};
+
+#[queue_computation_accounts("init_batch_stats", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, market_id: u32, batch_id: u64)]
+pub struct OpenBatch<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_INIT_BATCH_STATS)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS,
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// Sign PDA account for Arcium computations
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        seeds = [b"market", market_id.to_le_bytes().as_ref()],
+        bump = market_acc.bump,
+    )]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + BatchAccount::INIT_SPACE,
+        seeds = [b"batch", market_id.to_le_bytes().as_ref(), batch_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub batch_acc: Box<Account<'info, BatchAccount>>,
+}
+
+impl<'info> OpenBatch<'info> {
+    pub fn open_batch(
+        &mut self,
+        market_id: u32,
+        batch_id: u64,
+        nonce: u128,
+        computation_offset: u64,
+        bump: u8,
+        sign_pda_account_bump: u8,
+    ) -> Result<()> {
+        require!(self.market_acc.is_trading(Clock::get()?.unix_timestamp), ErrorCode::TradingClosed);
+        require!(self.market_acc.batch_mode, ErrorCode::BatchModeDisabled);
+        let now = Clock::get()?.unix_timestamp;
+        require!(self.market_acc.batch_at(now) == batch_id, ErrorCode::BatchNotOpen);
+
+        self.batch_acc.bump = bump;
+        self.batch_acc.nonce = nonce;
+        self.batch_acc.budgets = [[0; 32]; MAX_OPTIONS];
+        self.batch_acc.shares = [[0; 32]; MAX_OPTIONS];
+        self.batch_acc.market_id = market_id;
+        self.batch_acc.batch_id = batch_id;
+        self.batch_acc.deposits = 0;
+        self.batch_acc.fees = 0;
+        self.batch_acc.num_trades = 0;
+        self.batch_acc.status = BatchStatus::Inactive;
+        self.batch_acc.pending = false;
+        self.batch_acc.ends_at = self.market_acc.batch_ends_at(batch_id);
+        self.batch_acc.clearing_since = 0;
+        let args = vec![Argument::PlaintextU128(nonce)];
+
+        // Set the bump for the sign_pda_account
+        self.sign_pda_account.bump = sign_pda_account_bump;
+
+        queue_computation(
+            self,
+            computation_offset,
+            args,
+            None,
+            vec![InitBatchStatsCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: self.batch_acc.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+
+        Ok(())
+    }
+}
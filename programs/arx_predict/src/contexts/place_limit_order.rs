@@ -0,0 +1,168 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::{
+    callbacks::PlaceLimitOrderCallback,
+    constants::{COMP_DEF_OFFSET_PLACE_LIMIT_ORDER, LIMIT_ORDER_FIELDS},
+    events::LimitOrderPlacedEvent,
+    states::{LimitOrder, OrderStatus},
+    ErrorCode, MarketAccount, SignerAccount, UserAccount, UserPosition, ID, ID_CONST,
+};
+
+#[queue_computation_accounts("place_limit_order", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, market_id: u32, order_id: u64)]
+pub struct PlaceLimitOrder<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_PLACE_LIMIT_ORDER)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS,
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// Sign PDA account for Arcium computations
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        seeds = [b"market", market_id.to_le_bytes().as_ref()],
+        bump = market_acc.bump,
+    )]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + LimitOrder::INIT_SPACE,
+        seeds = [b"limit_order", market_id.to_le_bytes().as_ref(), payer.key().as_ref(), order_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub limit_order_acc: Box<Account<'info, LimitOrder>>,
+    // Settling the order writes the fill into the position, it has to exist before placing
+    #[account(
+        seeds = [b"user_position", market_id.to_le_bytes().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub user_position_acc: Box<Account<'info, UserPosition>>,
+    #[account(
+        mut,
+        seeds = [b"user_account", market_acc.mint.as_ref(), payer.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Box<Account<'info, UserAccount>>,
+}
+
+impl<'info> PlaceLimitOrder<'info> {
+    pub fn place_limit_order(
+        &mut self,
+        market_id: u32,
+        order_id: u64,
+        option: [u8; 32],
+        shares: [u8; 32],
+        limit_price: [u8; 32],
+        order_encryption_pubkey: [u8; 32],
+        order_nonce: u128,
+        amount: u64,
+        nonce: u128,
+        computation_offset: u64,
+        bump: u8,
+        sign_pda_account_bump: u8,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(self.market_acc.is_trading(now), ErrorCode::TradingClosed);
+        require!(!self.market_acc.batch_mode, ErrorCode::BatchModeEnabled);
+        // The fill's fee is charged on top of its cost, so the order may only spend the escrow net of it
+        let budget = self.market_acc.cost_before_fee(amount);
+        require!(budget > 0, ErrorCode::InvalidAmount);
+        self.user_account.debit(amount)?;
+        self.market_acc.open_orders += 1;
+
+        self.limit_order_acc.bump = bump;
+        self.limit_order_acc.nonce = nonce;
+        self.limit_order_acc.order = [[0; 32]; LIMIT_ORDER_FIELDS];
+        self.limit_order_acc.owner = self.payer.key();
+        self.limit_order_acc.market_id = market_id;
+        self.limit_order_acc.order_id = order_id;
+        self.limit_order_acc.escrow = amount;
+        self.limit_order_acc.match_version = 0;
+        self.limit_order_acc.begin(OrderStatus::Placing, now);
+
+        let args = vec![
+            Argument::ArcisPubkey(order_encryption_pubkey),
+            Argument::PlaintextU128(order_nonce),
+            Argument::EncryptedU8(option),
+            Argument::EncryptedU64(shares),
+            Argument::EncryptedU64(limit_price),
+            Argument::PlaintextU64(budget),
+            Argument::PlaintextU128(nonce),
+        ];
+
+        // Set the bump for the sign_pda_account
+        self.sign_pda_account.bump = sign_pda_account_bump;
+
+        queue_computation(
+            self,
+            computation_offset,
+            args,
+            None,
+            vec![PlaceLimitOrderCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: self.limit_order_acc.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+
+        emit!(LimitOrderPlacedEvent {
+            market_id,
+            order_id,
+            escrow: amount,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    events::OutcomeProposedEvent,
+    states::{MarketAccount, MarketStatus, ResolutionSource, UserAccount},
+    ErrorCode,
+};
+
+#[derive(Accounts)]
+#[instruction(_id: u32)]
+pub struct ProposeOutcome<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"market", _id.to_le_bytes().as_ref()],
+        bump = market_acc.bump
+    )]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(
+        mut,
+        seeds = [b"user_account", market_acc.mint.as_ref(), payer.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Box<Account<'info, UserAccount>>,
+}
+
+impl<'info> ProposeOutcome<'info> {
+    /// Anyone can propose the outcome of an optimistic market by posting its bond
+    pub fn propose_outcome(&mut self, outcome: u8) -> Result<()> {
+        let ResolutionSource::Optimistic { bond, dispute_window, .. } = self.market_acc.resolution_source else {
+            return Err(ErrorCode::InvalidResolutionSource.into());
+        };
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        self.market_acc.refresh_status(current_timestamp);
+        match self.market_acc.status {
+            MarketStatus::Closed => {}
+            MarketStatus::Active => return Err(ErrorCode::TradingNotClosed.into()),
+            MarketStatus::Expired => return Err(ErrorCode::MarketExpired.into()),
+            MarketStatus::Proposed | MarketStatus::Disputed => return Err(ErrorCode::OutcomeAlreadyProposed.into()),
+            _ => return Err(ErrorCode::MarketFinalized.into()),
+        }
+        require!(outcome < self.market_acc.num_options, ErrorCode::InvalidOutcome);
+
+        self.user_account.debit(bond)?;
+        self.market_acc.status = MarketStatus::Proposed;
+        self.market_acc.proposer = self.payer.key();
+        self.market_acc.proposed_outcome = outcome;
+        self.market_acc.proposed_at = current_timestamp;
+        self.market_acc.proposer_bond = bond;
+
+        emit!(OutcomeProposedEvent {
+            market_id: self.market_acc.id,
+            proposer: self.payer.key(),
+            outcome,
+            bond,
+            dispute_ends_at: current_timestamp + dispute_window,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,127 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::{
+    callbacks::QuoteTradeCallback,
+    constants::{
+        COMP_DEF_OFFSET_QUOTE_TRADE, MARKET_ACCOUNT_STATS_LENGTH, MARKET_ACCOUNT_VOTE_STATS_OFFSET,
+    },
+    utils::token_unit,
+    ErrorCode, MarketAccount, SignerAccount, ID, ID_CONST,
+};
+
+#[queue_computation_accounts("quote_trade", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _id: u32)]
+pub struct QuoteTrade<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_QUOTE_TRADE)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS,
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// Sign PDA account for Arcium computations
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        seeds = [b"market", _id.to_le_bytes().as_ref()],
+        bump = market_acc.bump,
+    )]
+    pub market_acc: Account<'info, MarketAccount>,
+}
+
+impl<'info> QuoteTrade<'info> {
+    /// Queues a quote for an encrypted outcome and size, the result is emitted in TradeQuoteEvent encrypted to the
+    /// requester's key
+    pub fn quote_trade(
+        &mut self,
+        option: [u8; 32],
+        shares: [u8; 32],
+        trade_encryption_pubkey: [u8; 32],
+        trade_nonce: u128,
+        computation_offset: u64,
+        sign_pda_account_bump: u8,
+    ) -> Result<()> {
+        require!(!self.market_acc.is_finalized(), ErrorCode::MarketFinalized);
+        let args = vec![
+            // Enc<Shared, UserTrade>, option and shares share one nonce
+            Argument::ArcisPubkey(trade_encryption_pubkey),
+            Argument::PlaintextU128(trade_nonce),
+            Argument::EncryptedU8(option),
+            Argument::EncryptedU64(shares),
+            Argument::PlaintextU64(self.market_acc.state_version),
+            Argument::PlaintextU64(self.market_acc.liquidity_parameter),
+            Argument::PlaintextU8(self.market_acc.num_options),
+            Argument::PlaintextU64(token_unit(self.market_acc.mint_decimals)?),
+            Argument::PlaintextU64(self.market_acc.fee_bps()),
+            Argument::PlaintextU128(self.market_acc.nonce),
+            Argument::Account(
+                self.market_acc.key(),
+                MARKET_ACCOUNT_VOTE_STATS_OFFSET,
+                MARKET_ACCOUNT_STATS_LENGTH,
+            ),
+        ];
+
+        self.sign_pda_account.bump = sign_pda_account_bump;
+
+        queue_computation(
+            self,
+            computation_offset,
+            args,
+            None,
+            vec![QuoteTradeCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: self.market_acc.key(),
+                    is_writable: false,
+                },
+            ])],
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,73 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::PENDING_TRADE_TIMEOUT,
+    events::BatchTradeSettledEvent,
+    states::{BatchAccount, BatchStatus, BatchTicket, MarketAccount, MarketStatus, UserAccount},
+    ErrorCode,
+};
+
+#[derive(Accounts)]
+#[instruction(_market_id: u32, _batch_id: u64)]
+pub struct RefundBatchTrade<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"market", _market_id.to_le_bytes().as_ref()],
+        bump = market_acc.bump,
+    )]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(
+        mut,
+        seeds = [b"batch", _market_id.to_le_bytes().as_ref(), _batch_id.to_le_bytes().as_ref()],
+        bump = batch_acc.bump,
+    )]
+    pub batch_acc: Box<Account<'info, BatchAccount>>,
+    #[account(
+        mut,
+        seeds = [b"batch_ticket", _market_id.to_le_bytes().as_ref(), _batch_id.to_le_bytes().as_ref(), payer.key().as_ref()],
+        bump = batch_ticket_acc.bump,
+    )]
+    pub batch_ticket_acc: Box<Account<'info, BatchTicket>>,
+    #[account(
+        mut,
+        seeds = [b"user_account", market_acc.mint.as_ref(), payer.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Box<Account<'info, UserAccount>>,
+}
+
+impl<'info> RefundBatchTrade<'info> {
+    pub fn refund_batch_trade(&mut self) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        // A clearing that never called back fails the batch, its late result is dropped
+        if self.batch_acc.status == BatchStatus::Clearing
+            && now >= self.batch_acc.clearing_since + PENDING_TRADE_TIMEOUT
+        {
+            self.batch_acc.status = BatchStatus::Failed;
+        }
+        // A batch left open when the market settled, expired or was voided can't clear anymore
+        let market_done = self.market_acc.status == MarketStatus::Settled
+            || self.market_acc.status == MarketStatus::Voided
+            || self.market_acc.is_expired(now);
+        require!(
+            self.batch_acc.status == BatchStatus::Failed
+                || (self.batch_acc.status == BatchStatus::Open && market_done),
+            ErrorCode::BatchNotFailed
+        );
+        require!(!self.batch_ticket_acc.settled, ErrorCode::TicketSettled);
+
+        self.batch_ticket_acc.settled = true;
+        self.market_acc.open_tickets = self.market_acc.open_tickets.checked_sub(1).ok_or(ErrorCode::Overflow)?;
+        self.user_account.credit(self.batch_ticket_acc.amount + self.batch_ticket_acc.fee);
+
+        emit!(BatchTradeSettledEvent {
+            market_id: self.batch_ticket_acc.market_id,
+            batch_id: self.batch_ticket_acc.batch_id,
+            refunded: true,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::PENDING_TRADE_TIMEOUT,
+    events::PendingTradeReleasedEvent,
+    states::{UserAccount, UserPosition},
+    ErrorCode,
+};
+
+#[derive(Accounts)]
+#[instruction(_id: u32)]
+pub struct ReleasePendingTrade<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"user_position", _id.to_le_bytes().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub user_position_acc: Box<Account<'info, UserPosition>>,
+    // No market account, close_market may have closed it while the computation was in flight
+    #[account(
+        mut,
+        seeds = [b"user_account", user_position_acc.mint.as_ref(), payer.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Box<Account<'info, UserAccount>>,
+}
+
+impl<'info> ReleasePendingTrade<'info> {
+    /// Unlocks a position whose computation never called back, a result arriving later is dropped
+    pub fn release_pending_trade(&mut self) -> Result<()> {
+        require!(self.user_position_acc.pending, ErrorCode::NoPendingTrade);
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        require!(
+            current_timestamp >= self.user_position_acc.pending_since + PENDING_TRADE_TIMEOUT,
+            ErrorCode::PendingTradeNotExpired
+        );
+
+        let amount = self.user_position_acc.end_trade(0);
+        self.user_account.credit(amount);
+
+        emit!(PendingTradeReleasedEvent {
+            market_id: self.user_position_acc.market_id,
+            trade_seq: self.user_position_acc.trade_seq,
+            amount,
+        });
+
+        Ok(())
+    }
+}
@@ -3,10 +3,12 @@ use arcium_anchor::prelude::*;
 use arcium_client::idl::arcium::types::CallbackAccount;
 
 use crate::{
-    callbacks::RevealProbsCallback, constants::{
-        COMP_DEF_OFFSET_REVEAL_PROBS, MARKET_ACCOUNT_COST_LENGTH, MARKET_ACCOUNT_PROB_LENGTH,
-        MARKET_ACCOUNT_VOTE_STATS_LENGTH, MARKET_ACCOUNT_VOTE_STATS_OFFSET, MARKET_REVEAL_PROBS_TIME
-    }, states::MarketStatus, ErrorCode, MarketAccount, SignerAccount, ID, ID_CONST
+    callbacks::RevealProbsCallback,
+    constants::{
+        COMP_DEF_OFFSET_REVEAL_PROBS, MARKET_ACCOUNT_STATS_LENGTH, MARKET_ACCOUNT_VOTE_STATS_OFFSET,
+    },
+    states::{MarketStatus, UserAccount},
+    ErrorCode, MarketAccount, SignerAccount, ID, ID_CONST,
 };
 
 #[queue_computation_accounts("reveal_probs", payer)]
@@ -73,37 +75,44 @@ pub struct RevealProbs<'info> {
         bump = market_acc.bump
     )]
     pub market_acc: Account<'info, MarketAccount>,
+    // Credited the market's reveal reward once the reveal lands
+    #[account(
+        mut,
+        seeds = [b"user_account", market_acc.mint.as_ref(), payer.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Box<Account<'info, UserAccount>>,
 }
 
 impl<'info> RevealProbs<'info> {
-    pub fn reveal_probs(&self, id: u32, computation_offset: u64) -> Result<()> {
+    pub fn reveal_probs(&mut self, id: u32, computation_offset: u64, sign_pda_account_bump: u8) -> Result<()> {
         require!(
             self.market_acc.status == MarketStatus::Active,
             ErrorCode::MarketActive
         );
-        let current_timestamp = Clock::get()?.unix_timestamp as u64;
+        let current_timestamp = Clock::get()?.unix_timestamp;
         require!(
-            current_timestamp - self.market_acc.updated_at > MARKET_REVEAL_PROBS_TIME,
+            self.market_acc.can_reveal(current_timestamp),
             ErrorCode::MarketProbsRevealRateLimit
         );
+        self.market_acc.last_reveal_at = current_timestamp;
+        let (policy, policy_param) = self.market_acc.reveal.policy.circuit_args();
 
         let args = vec![
             Argument::PlaintextU128(self.market_acc.nonce),
             Argument::Account(
                 self.market_acc.key(),
                 MARKET_ACCOUNT_VOTE_STATS_OFFSET,
-                MARKET_ACCOUNT_VOTE_STATS_LENGTH
-                    + MARKET_ACCOUNT_PROB_LENGTH
-                    + MARKET_ACCOUNT_COST_LENGTH,
+                MARKET_ACCOUNT_STATS_LENGTH,
             ),
+            Argument::PlaintextU8(policy),
+            Argument::PlaintextU64(policy_param),
+            Argument::PlaintextU64(self.market_acc.state_version),
         ];
 
         // Set the bump for the sign_pda_account
-        // Note: The bump will be handled by the Arcium program
-        
-        // Note: We can't set the bump here since this is a &self method, not &mut self
-        // The bump will be set by the Arcium program
-        
+        self.sign_pda_account.bump = sign_pda_account_bump;
+
         queue_computation(
             self,
             computation_offset,
@@ -114,6 +123,10 @@ impl<'info> RevealProbs<'info> {
                     pubkey: self.market_acc.key(),
                     is_writable: true,
                 },
+                CallbackAccount {
+                    pubkey: self.user_account.key(),
+                    is_writable: true,
+                },
             ])],
         )?;
         Ok(())
@@ -2,7 +2,15 @@ use anchor_lang::prelude::*;
 use arcium_anchor::prelude::*;
 use arcium_client::idl::arcium::types::CallbackAccount;
 
-use crate::{callbacks::SellSharesCallback, constants::{COMP_DEF_OFFSET_SELL_SHARES, MARKET_ACCOUNT_COST_LENGTH, MARKET_ACCOUNT_PROB_LENGTH, MARKET_ACCOUNT_VOTE_STATS_LENGTH, MARKET_ACCOUNT_VOTE_STATS_OFFSET, USER_POSITION_SHARES_LENGTH, USER_POSITION_SHARES_OFFSET}, states::MarketStatus, ErrorCode, MarketAccount, SignerAccount, UserPosition, ID, ID_CONST};
+use crate::{
+    callbacks::SellSharesCallback,
+    constants::{
+        COMP_DEF_OFFSET_SELL_SHARES, MARKET_ACCOUNT_STATS_LENGTH, MARKET_ACCOUNT_VOTE_STATS_OFFSET,
+        USER_POSITION_SHARES_LENGTH, USER_POSITION_SHARES_OFFSET,
+    },
+    utils::token_unit,
+    ErrorCode, MarketAccount, SignerAccount, UserAccount, UserPosition, ID, ID_CONST,
+};
 
 
 #[queue_computation_accounts("sell_shares", payer)]
@@ -81,6 +89,11 @@ pub struct SellShares<'info> {
         bump
     )]
     pub user_position_acc: Box<Account<'info, UserPosition>>,
+    #[account(
+        seeds = [b"user_account", market_acc.mint.as_ref(), payer.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Box<Account<'info, UserAccount>>,
 }
 
 impl<'info> SellShares<'info> {
@@ -91,20 +104,33 @@ impl<'info> SellShares<'info> {
         vote_nonce: u128,
         computation_offset: u64,
         shares: u64,
+        min_proceeds: u64,
         sign_pda_account_bump: u8,
     ) -> Result<()> {
-        require!(self.market_acc.status == MarketStatus::Active, ErrorCode::MarketActive);
+        let current_timestamp = Clock::get()?.unix_timestamp;
+        // Selling back is the way out of a market nobody resolved
+        require!(
+            self.market_acc.is_trading(current_timestamp) || self.market_acc.is_expired(current_timestamp),
+            ErrorCode::TradingClosed
+        );
+        let trade_seq = self.user_position_acc.begin_trade(0, current_timestamp)?;
         let args = vec![
             Argument::ArcisPubkey(vote_encryption_pubkey),
             Argument::PlaintextU128(vote_nonce),
             Argument::EncryptedBool(vote),
             Argument::PlaintextU64(shares),
+            // min_proceeds is what the seller keeps, the circuit checks the amount before the fee is taken
+            Argument::PlaintextU64(self.market_acc.proceeds_before_fee(min_proceeds)),
+            Argument::PlaintextU64(trade_seq),
+            Argument::PlaintextU64(self.market_acc.state_version),
             Argument::PlaintextU64(self.market_acc.liquidity_parameter),
+            Argument::PlaintextU8(self.market_acc.num_options),
+            Argument::PlaintextU64(token_unit(self.market_acc.mint_decimals)?),
             Argument::PlaintextU128(self.market_acc.nonce),
             Argument::Account(
                 self.market_acc.key(),
                 MARKET_ACCOUNT_VOTE_STATS_OFFSET,
-                MARKET_ACCOUNT_VOTE_STATS_LENGTH + MARKET_ACCOUNT_PROB_LENGTH + MARKET_ACCOUNT_COST_LENGTH,
+                MARKET_ACCOUNT_STATS_LENGTH,
             ),
             Argument::PlaintextU128(self.user_position_acc.nonce),
             Argument::Account(
@@ -131,6 +157,10 @@ impl<'info> SellShares<'info> {
                     pubkey: self.user_position_acc.key(),
                     is_writable: true,
                 },
+                CallbackAccount {
+                    pubkey: self.user_account.key(),
+                    is_writable: true,
+                },
             ])],
         )?;
         Ok(())
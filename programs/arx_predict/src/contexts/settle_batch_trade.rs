@@ -0,0 +1,177 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::{
+    callbacks::SettleBatchTradeCallback,
+    constants::{
+        BATCH_STATS_LENGTH, BATCH_STATS_OFFSET, COMP_DEF_OFFSET_SETTLE_BATCH_TRADE,
+        USER_POSITION_SHARES_LENGTH, USER_POSITION_SHARES_OFFSET,
+    },
+    events::BatchTradeSettledEvent,
+    states::{BatchAccount, BatchStatus, BatchTicket},
+    ErrorCode, MarketAccount, SignerAccount, UserAccount, UserPosition, ID, ID_CONST,
+};
+
+#[queue_computation_accounts("settle_batch_trade", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, _market_id: u32, _batch_id: u64)]
+pub struct SettleBatchTrade<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_SETTLE_BATCH_TRADE)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS,
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// Sign PDA account for Arcium computations
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        seeds = [b"market", _market_id.to_le_bytes().as_ref()],
+        bump = market_acc.bump,
+    )]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(
+        seeds = [b"batch", _market_id.to_le_bytes().as_ref(), _batch_id.to_le_bytes().as_ref()],
+        bump = batch_acc.bump,
+    )]
+    pub batch_acc: Box<Account<'info, BatchAccount>>,
+    #[account(
+        mut,
+        seeds = [b"batch_ticket", _market_id.to_le_bytes().as_ref(), _batch_id.to_le_bytes().as_ref(), payer.key().as_ref()],
+        bump = batch_ticket_acc.bump,
+    )]
+    pub batch_ticket_acc: Box<Account<'info, BatchTicket>>,
+    #[account(
+        mut,
+        seeds = [b"user_position", _market_id.to_le_bytes().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub user_position_acc: Box<Account<'info, UserPosition>>,
+    #[account(
+        mut,
+        seeds = [b"user_account", market_acc.mint.as_ref(), payer.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Box<Account<'info, UserAccount>>,
+}
+
+impl<'info> SettleBatchTrade<'info> {
+    pub fn settle_batch_trade(
+        &mut self,
+        computation_offset: u64,
+        sign_pda_account_bump: u8,
+    ) -> Result<()> {
+        require!(self.batch_acc.status == BatchStatus::Cleared, ErrorCode::BatchNotCleared);
+        require!(!self.batch_ticket_acc.settled, ErrorCode::TicketSettled);
+        // Claimed positions are done, the ticket's shares would never be paid out so its budget comes back from the
+        // market instead. The fee was accrued when the batch cleared and stays
+        if self.user_position_acc.claimed {
+            let refund = self.batch_ticket_acc.amount;
+            self.market_acc.tvl = self.market_acc.tvl.checked_sub(refund).ok_or(ErrorCode::Overflow)?;
+            self.market_acc.open_tickets = self.market_acc.open_tickets.checked_sub(1).ok_or(ErrorCode::Overflow)?;
+            self.batch_ticket_acc.settled = true;
+            self.user_account.credit(refund);
+            emit!(BatchTradeSettledEvent {
+                market_id: self.batch_ticket_acc.market_id,
+                batch_id: self.batch_ticket_acc.batch_id,
+                refunded: true,
+            });
+            return Ok(());
+        }
+        // The position lock also keeps a ticket from being credited twice while its settlement is in flight
+        let trade_seq = self.user_position_acc.begin_trade(0, Clock::get()?.unix_timestamp)?;
+
+        let args = vec![
+            Argument::ArcisPubkey(self.batch_ticket_acc.vote_encryption_pubkey),
+            Argument::PlaintextU128(self.batch_ticket_acc.vote_nonce),
+            Argument::EncryptedU8(self.batch_ticket_acc.vote),
+            Argument::PlaintextU64(self.batch_ticket_acc.amount),
+            Argument::PlaintextU64(trade_seq),
+            Argument::PlaintextU128(self.batch_acc.nonce),
+            Argument::Account(
+                self.batch_acc.key(),
+                BATCH_STATS_OFFSET,
+                BATCH_STATS_LENGTH,
+            ),
+            Argument::PlaintextU128(self.user_position_acc.nonce),
+            Argument::Account(
+                self.user_position_acc.key(),
+                USER_POSITION_SHARES_OFFSET,
+                USER_POSITION_SHARES_LENGTH,
+            ),
+        ];
+
+        // Set the bump for the sign_pda_account
+        self.sign_pda_account.bump = sign_pda_account_bump;
+
+        queue_computation(
+            self,
+            computation_offset,
+            args,
+            None,
+            vec![SettleBatchTradeCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: self.market_acc.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: self.user_position_acc.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: self.batch_ticket_acc.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,180 @@
+use anchor_lang::prelude::*;
+use arcium_anchor::prelude::*;
+use arcium_client::idl::arcium::types::CallbackAccount;
+
+use crate::{
+    callbacks::SettleLimitOrderCallback,
+    constants::{
+        COMP_DEF_OFFSET_SETTLE_LIMIT_ORDER, LIMIT_ORDER_LENGTH, LIMIT_ORDER_OFFSET,
+        USER_POSITION_SHARES_LENGTH, USER_POSITION_SHARES_OFFSET,
+    },
+    events::LimitOrderSettledEvent,
+    states::{LimitOrder, OrderStatus},
+    ErrorCode, MarketAccount, SignerAccount, UserAccount, UserPosition, ID, ID_CONST,
+};
+
+#[queue_computation_accounts("settle_limit_order", payer)]
+#[derive(Accounts)]
+#[instruction(computation_offset: u64, market_id: u32, order_id: u64)]
+pub struct SettleLimitOrder<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        address = derive_mxe_pda!()
+    )]
+    pub mxe_account: Account<'info, MXEAccount>,
+    #[account(
+        mut,
+        address = derive_mempool_pda!()
+    )]
+    /// CHECK: mempool_account, checked by the arcium program
+    pub mempool_account: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_execpool_pda!()
+    )]
+    /// CHECK: executing_pool, checked by the arcium program
+    pub executing_pool: UncheckedAccount<'info>,
+    #[account(
+        mut,
+        address = derive_comp_pda!(computation_offset)
+    )]
+    /// CHECK: computation_account, checked by the arcium program.
+    pub computation_account: UncheckedAccount<'info>,
+    #[account(
+        address = derive_comp_def_pda!(COMP_DEF_OFFSET_SETTLE_LIMIT_ORDER)
+    )]
+    pub comp_def_account: Account<'info, ComputationDefinitionAccount>,
+    #[account(
+        mut,
+        address = derive_cluster_pda!(mxe_account)
+    )]
+    pub cluster_account: Account<'info, Cluster>,
+    #[account(
+        mut,
+        address = ARCIUM_FEE_POOL_ACCOUNT_ADDRESS,
+    )]
+    pub pool_account: Account<'info, FeePool>,
+    #[account(
+        address = ARCIUM_CLOCK_ACCOUNT_ADDRESS,
+    )]
+    pub clock_account: Account<'info, ClockAccount>,
+    pub system_program: Program<'info, System>,
+    pub arcium_program: Program<'info, Arcium>,
+    /// Sign PDA account for Arcium computations
+    #[account(
+        init_if_needed,
+        space = 9,
+        payer = payer,
+        seeds = [&SIGN_PDA_SEED],
+        bump,
+        address = derive_sign_pda!(),
+    )]
+    pub sign_pda_account: Account<'info, SignerAccount>,
+    #[account(
+        mut,
+        seeds = [b"market", market_id.to_le_bytes().as_ref()],
+        bump = market_acc.bump,
+    )]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(
+        mut,
+        seeds = [b"limit_order", market_id.to_le_bytes().as_ref(), payer.key().as_ref(), order_id.to_le_bytes().as_ref()],
+        bump = limit_order_acc.bump,
+    )]
+    pub limit_order_acc: Box<Account<'info, LimitOrder>>,
+    #[account(
+        mut,
+        seeds = [b"user_position", market_id.to_le_bytes().as_ref(), payer.key().as_ref()],
+        bump
+    )]
+    pub user_position_acc: Box<Account<'info, UserPosition>>,
+    #[account(
+        mut,
+        seeds = [b"user_account", market_acc.mint.as_ref(), payer.key().as_ref()],
+        bump = user_account.bump,
+    )]
+    pub user_account: Box<Account<'info, UserAccount>>,
+}
+
+impl<'info> SettleLimitOrder<'info> {
+    /// Collects a filled order into the position and refunds what its escrow didn't spend, an unfilled order
+    /// gets its whole escrow back. Settling ends the order either way
+    pub fn settle_limit_order(
+        &mut self,
+        computation_offset: u64,
+        sign_pda_account_bump: u8,
+    ) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        // A placement that never called back has nothing encrypted to settle, the escrow goes back as is
+        if self.limit_order_acc.status == OrderStatus::Placing && self.limit_order_acc.is_stuck(now) {
+            let refund = self.limit_order_acc.escrow;
+            self.limit_order_acc.escrow = 0;
+            self.limit_order_acc.status = OrderStatus::Settled;
+            self.market_acc.open_orders = self.market_acc.open_orders.checked_sub(1).ok_or(ErrorCode::Overflow)?;
+            self.user_account.credit(refund);
+            emit!(LimitOrderSettledEvent {
+                market_id: self.limit_order_acc.market_id,
+                order_id: self.limit_order_acc.order_id,
+                cost: 0,
+                fee: 0,
+                refund,
+            });
+            return Ok(());
+        }
+        require!(
+            self.limit_order_acc.status == OrderStatus::Open || self.limit_order_acc.is_stuck(now),
+            ErrorCode::OrderNotOpen
+        );
+        let trade_seq = self.user_position_acc.begin_trade(0, now)?;
+        self.limit_order_acc.begin(OrderStatus::Settling, now);
+
+        let args = vec![
+            Argument::PlaintextU64(trade_seq),
+            // Claimed positions are done, a fill written into them would never be paid out, only the escrow comes back
+            Argument::PlaintextBool(self.user_position_acc.claimed),
+            Argument::PlaintextU128(self.limit_order_acc.nonce),
+            Argument::Account(
+                self.limit_order_acc.key(),
+                LIMIT_ORDER_OFFSET,
+                LIMIT_ORDER_LENGTH,
+            ),
+            Argument::PlaintextU128(self.user_position_acc.nonce),
+            Argument::Account(
+                self.user_position_acc.key(),
+                USER_POSITION_SHARES_OFFSET,
+                USER_POSITION_SHARES_LENGTH,
+            ),
+        ];
+
+        // Set the bump for the sign_pda_account
+        self.sign_pda_account.bump = sign_pda_account_bump;
+
+        queue_computation(
+            self,
+            computation_offset,
+            args,
+            None,
+            vec![SettleLimitOrderCallback::callback_ix(&[
+                CallbackAccount {
+                    pubkey: self.market_acc.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: self.limit_order_acc.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: self.user_position_acc.key(),
+                    is_writable: true,
+                },
+                CallbackAccount {
+                    pubkey: self.user_account.key(),
+                    is_writable: true,
+                },
+            ])],
+        )?;
+
+        Ok(())
+    }
+}
@@ -1,9 +1,12 @@
 use anchor_lang::prelude::*;
 use arcium_anchor::prelude::*;
 use arcium_client::idl::arcium::types::CallbackAccount;
-use crate::constants::{COMP_DEF_OFFSET_REVEAL_MARKET, MARKET_ACCOUNT_COST_LENGTH, MARKET_ACCOUNT_PROB_LENGTH, MARKET_ACCOUNT_VOTE_STATS_LENGTH, MARKET_ACCOUNT_VOTE_STATS_OFFSET};
+use crate::constants::{
+    COMP_DEF_OFFSET_REVEAL_MARKET, MARKET_ACCOUNT_STATS_LENGTH, MARKET_ACCOUNT_VOTE_STATS_OFFSET,
+};
 use crate::SignerAccount;
-use crate::{states::MarketStatus, ErrorCode, MarketAccount};
+use crate::utils::token_unit;
+use crate::{oracle::settlement_price, states::{MarketStatus, ResolutionSource}, ErrorCode, MarketAccount};
 use crate::{ID, ID_CONST, callbacks::RevealMarketCallback};
 
 #[queue_computation_accounts("reveal_market", payer)]
@@ -68,6 +71,8 @@ pub struct SettleMarket<'info> {
         bump = market_acc.bump
     )]
     pub market_acc: Account<'info, MarketAccount>,
+    /// CHECK: price_feed, only read for price feed markets and checked against the feed they were created with
+    pub price_feed: Option<UncheckedAccount<'info>>,
 }
 
 impl<'info> SettleMarket<'info> {
@@ -78,27 +83,72 @@ impl<'info> SettleMarket<'info> {
         winner: u8,
         sign_pda_account_bump: u8,
     ) -> Result<()> {
-        require!(
-            self.payer.key() == self.market_acc.authority,
-            ErrorCode::InvalidAuthority
-        );
-        require!(self.market_acc.status == MarketStatus::Active, ErrorCode::MarketActive);
+        self.check_settleable()?;
+        require!(self.market_acc.scalar.is_none(), ErrorCode::ScalarMarket);
+        if self.market_acc.status == MarketStatus::Closed {
+            let winner = self.resolve_winner(winner)?;
+            require!(winner < self.market_acc.num_options, ErrorCode::InvalidOutcome);
+            self.market_acc.begin_settlement(winner);
+        }
+        self.queue_reveal(computation_offset, sign_pda_account_bump)
+    }
+
+    /// Settles a scalar market, `value` is only used when the authority resolves
+    pub fn settle_scalar_market(
+        &mut self,
+        computation_offset: u64,
+        value: u64,
+        sign_pda_account_bump: u8,
+    ) -> Result<()> {
+        self.check_settleable()?;
+        if self.market_acc.status == MarketStatus::Closed {
+            let value = match self.market_acc.resolution_source {
+                ResolutionSource::PriceFeed { feed, expo, .. } => self.read_settlement_price(feed, expo)?.max(0) as u64,
+                _ => {
+                    require!(
+                        self.payer.key() == self.market_acc.authority,
+                        ErrorCode::InvalidAuthority
+                    );
+                    value
+                }
+            };
+            let winner = self.market_acc.set_resolved_value(value)?;
+            self.market_acc.begin_settlement(winner);
+        }
+        self.queue_reveal(computation_offset, sign_pda_account_bump)
+    }
 
+    fn check_settleable(&mut self) -> Result<()> {
+        self.market_acc.refresh_status(Clock::get()?.unix_timestamp);
+        match self.market_acc.status {
+            // A settling market queues its reveal again when the last one came back stale, with the locked outcome
+            MarketStatus::Closed | MarketStatus::Settling => Ok(()),
+            MarketStatus::Active => Err(ErrorCode::TradingNotClosed.into()),
+            MarketStatus::Expired => Err(ErrorCode::MarketExpired.into()),
+            _ => Err(ErrorCode::MarketInactive.into()),
+        }
+    }
+
+    fn queue_reveal(
+        &mut self,
+        computation_offset: u64,
+        sign_pda_account_bump: u8,
+    ) -> Result<()> {
         let args = vec![
             Argument::PlaintextU128(self.market_acc.nonce),
             Argument::Account(
                 self.market_acc.key(),
                 MARKET_ACCOUNT_VOTE_STATS_OFFSET,
-                MARKET_ACCOUNT_VOTE_STATS_LENGTH
-                    + MARKET_ACCOUNT_PROB_LENGTH
-                    + MARKET_ACCOUNT_COST_LENGTH,
+                MARKET_ACCOUNT_STATS_LENGTH,
             ),
-            Argument::PlaintextU8(winner),
+            Argument::PlaintextU8(self.market_acc.winning_outcome),
+            Argument::PlaintextU64(self.market_acc.state_version),
+            Argument::PlaintextBool(self.market_acc.scalar.is_some()),
+            Argument::PlaintextU64(self.market_acc.long_payout),
+            Argument::PlaintextU64(token_unit(self.market_acc.mint_decimals)?),
+            Argument::PlaintextU64(self.market_acc.complete_sets),
         ];
 
-        // Set the bump for the sign_pda_account
-        // Note: The bump will be handled by the Arcium program
-        
         // Set the bump for the sign_pda_account
         self.sign_pda_account.bump = sign_pda_account_bump;
         
@@ -116,4 +166,35 @@ impl<'info> SettleMarket<'info> {
         )?;
         Ok(())
     }
-} 
\ No newline at end of file
+
+    /// `winner` is only used when the authority resolves, other sources settle permissionlessly
+    fn resolve_winner(&self, winner: u8) -> Result<u8> {
+        match self.market_acc.resolution_source {
+            ResolutionSource::Authority => {
+                require!(
+                    self.payer.key() == self.market_acc.authority,
+                    ErrorCode::InvalidAuthority
+                );
+                Ok(winner)
+            }
+            ResolutionSource::PriceFeed { feed, threshold, expo } => {
+                let price = self.read_settlement_price(feed, expo)?;
+                Ok(if price >= threshold { 0 } else { 1 })
+            }
+            ResolutionSource::Multisig { threshold } => self
+                .market_acc
+                .multisig_outcome(threshold)
+                .ok_or(ErrorCode::ResolutionPending.into()),
+            // Settled through propose_outcome and finalize_outcome instead
+            ResolutionSource::Optimistic { .. } => Err(ErrorCode::InvalidResolutionSource.into()),
+        }
+    }
+
+    fn read_settlement_price(&self, feed: Pubkey, expo: i32) -> Result<i64> {
+        let price_feed = self.price_feed.as_ref().ok_or(ErrorCode::InvalidPriceFeed)?;
+        require_keys_eq!(price_feed.key(), feed, ErrorCode::InvalidPriceFeed);
+        // The market is about the price once trading closed, an older price can't settle it and neither can one
+        // from after the settlement window
+        settlement_price(&price_feed.try_borrow_data()?, expo, self.market_acc.trading_ends_at)
+    }
+}
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::NO_RESOLVER_VOTE,
+    events::ResolutionSubmittedEvent,
+    states::{Config, MarketAccount, MarketStatus, ResolutionSource},
+    ErrorCode,
+};
+
+#[derive(Accounts)]
+#[instruction(_id: u32)]
+pub struct SubmitResolution<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"market", _id.to_le_bytes().as_ref()],
+        bump = market_acc.bump
+    )]
+    pub market_acc: Account<'info, MarketAccount>,
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> SubmitResolution<'info> {
+    /// Records a resolver's vote on a multisig market or a disputed optimistic one, it can be changed until the
+    /// outcome is taken. Disputes on optimistic markets without resolvers are ruled by the config authority
+    pub fn submit_resolution(&mut self, outcome: u8) -> Result<()> {
+        match self.market_acc.resolution_source {
+            ResolutionSource::Multisig { .. } => {
+                self.market_acc.refresh_status(Clock::get()?.unix_timestamp);
+                match self.market_acc.status {
+                    MarketStatus::Closed => {}
+                    MarketStatus::Active => return Err(ErrorCode::TradingNotClosed.into()),
+                    MarketStatus::Expired => return Err(ErrorCode::MarketExpired.into()),
+                    _ => return Err(ErrorCode::MarketFinalized.into()),
+                }
+            }
+            ResolutionSource::Optimistic { .. } => {
+                require!(self.market_acc.status == MarketStatus::Disputed, ErrorCode::NoProposal);
+                require!(
+                    self.market_acc.ruled_outcome == NO_RESOLVER_VOTE,
+                    ErrorCode::MarketFinalized
+                );
+            }
+            _ => return Err(ErrorCode::InvalidResolutionSource.into()),
+        }
+        require!(outcome < self.market_acc.num_options, ErrorCode::InvalidOutcome);
+
+        if self.market_acc.resolvers.is_empty() {
+            require!(
+                self.payer.key() == self.config.authority,
+                ErrorCode::InvalidAuthority
+            );
+            self.market_acc.ruled_outcome = outcome;
+        } else {
+            let resolver = self
+                .market_acc
+                .resolvers
+                .iter()
+                .position(|resolver| *resolver == self.payer.key())
+                .ok_or(ErrorCode::InvalidAuthority)?;
+            self.market_acc.resolver_votes[resolver] = outcome;
+        }
+
+        emit!(ResolutionSubmittedEvent {
+            market_id: self.market_acc.id,
+            resolver: self.payer.key(),
+            outcome,
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+
+use crate::states::Config;
+
+#[derive(Accounts)]
+pub struct TransferConfigAuthority<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> TransferConfigAuthority<'info> {
+    /// First step of the handover, the current authority stays in charge until the new one accepts.
+    /// Passing None cancels a pending transfer
+    pub fn transfer_config_authority(&mut self, new_authority: Option<Pubkey>) -> Result<()> {
+        self.config.pending_authority = new_authority;
+        Ok(())
+    }
+}
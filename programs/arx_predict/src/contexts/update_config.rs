@@ -0,0 +1,21 @@
+use anchor_lang::prelude::*;
+
+use crate::states::{Config, ConfigParams};
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> UpdateConfig<'info> {
+    pub fn update_config(&mut self, params: ConfigParams) -> Result<()> {
+        self.config.set_params(params)
+    }
+}
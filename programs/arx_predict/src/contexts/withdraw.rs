@@ -4,18 +4,16 @@ use anchor_spl::{
     token::{Mint, Token, TokenAccount, TransferChecked, transfer_checked}
 };
 
-use crate::{check_mint, states::UserPosition};
-use crate::ErrorCode;
+use crate::states::UserAccount;
 
 #[derive(Accounts)]
-#[instruction(id: u32)]
-pub struct WithdrawPayment<'info> {
+pub struct Withdraw<'info> {
     #[account(mut)]
     pub payer: Signer<'info>,
     
     #[account(
         mut,
-        seeds = [b"vault", id.to_le_bytes().as_ref()],
+        seeds = [b"vault", mint.key().as_ref()],
         bump,
         token::mint = mint,
         token::authority = vault
@@ -36,34 +34,32 @@ pub struct WithdrawPayment<'info> {
 
     #[account(
         mut,
-        seeds = [b"user_position", id.to_le_bytes().as_ref(), payer.key().as_ref()],
-        bump
+        seeds = [b"user_account", mint.key().as_ref(), payer.key().as_ref()],
+        bump = user_account.bump,
     )]
-    pub user_position_acc: Account<'info, UserPosition>,
+    pub user_account: Account<'info, UserAccount>,
 
     pub system_program: Program<'info, System>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-impl<'info> WithdrawPayment<'info> {
-    pub fn withdraw_payment(
+impl<'info> Withdraw<'info> {
+    /// Deposits stay withdrawable after their mint is removed from the allowed list
+    pub fn withdraw(
         &mut self,
         amount: u64,
-        id: u32, 
         bump: u8
     ) -> Result<()> {
-        check_mint!(self.mint.key());
-        require!(self.user_position_acc.balance >= amount, ErrorCode::InsufficientBalance);
-        self.user_position_acc.balance -= amount;
+        self.user_account.debit(amount)?;
         let transfer_accounts = TransferChecked {
             from: self.vault.to_account_info(),
             mint: self.mint.to_account_info(),
             to: self.ata.to_account_info(),
             authority: self.vault.to_account_info(),
         };
-        let id_bytes = id.to_le_bytes();
-        let signer: &[&[&[u8]]] = &[&[b"vault", id_bytes.as_ref(), &[bump]]];
+        let mint_key = self.mint.key();
+        let signer: &[&[&[u8]]] = &[&[b"vault", mint_key.as_ref(), &[bump]]];
 
         transfer_checked(
             CpiContext::new_with_signer(
@@ -75,4 +71,4 @@ impl<'info> WithdrawPayment<'info> {
             self.mint.decimals
         )
     }
-} 
\ No newline at end of file
+}
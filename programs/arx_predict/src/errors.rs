@@ -26,7 +26,7 @@ pub enum ErrorCode {
     InsufficientBalance,
     #[msg("Market not settled")]
     MarketNotSettled,
-    #[msg("Market probs reveal rate limit")]
+    #[msg("Market probs reveal rate limit, or the stats were already revealed")]
     MarketProbsRevealRateLimit,
     #[msg("Invalid amount: amount must be positive")]
     InvalidAmount,
@@ -38,4 +38,102 @@ pub enum ErrorCode {
     InvalidMint,
     #[msg("Invalid outcome")]
     InvalidOutcome,
+    #[msg("Market trades through batches")]
+    BatchModeEnabled,
+    #[msg("Market does not trade through batches")]
+    BatchModeDisabled,
+    #[msg("Batch not open")]
+    BatchNotOpen,
+    #[msg("Batch still taking commits")]
+    BatchEpochOpen,
+    #[msg("Batch is empty")]
+    BatchEmpty,
+    #[msg("Batch not cleared")]
+    BatchNotCleared,
+    #[msg("Batch not failed")]
+    BatchNotFailed,
+    #[msg("Batch ticket already settled")]
+    TicketSettled,
+    #[msg("A computation is already pending")]
+    ComputationPending,
+    #[msg("No pending trade")]
+    NoPendingTrade,
+    #[msg("Pending trade can't be released yet")]
+    PendingTradeNotExpired,
+    #[msg("Invalid market times: need now < trading_ends_at < resolve_by")]
+    InvalidMarketTimes,
+    #[msg("Trading closed")]
+    TradingClosed,
+    #[msg("Trading not closed yet")]
+    TradingNotClosed,
+    #[msg("Market expired")]
+    MarketExpired,
+    #[msg("Market not expired")]
+    MarketNotExpired,
+    #[msg("Market already settled or voided")]
+    MarketFinalized,
+    #[msg("Market not voided")]
+    MarketNotVoided,
+    #[msg("Config exceeds its size limits")]
+    InvalidConfig,
+    #[msg("Fees exceed the maximum")]
+    InvalidFee,
+    #[msg("No fees to claim")]
+    NoFeesToClaim,
+    #[msg("Position already claimed")]
+    AlreadyClaimed,
+    #[msg("Position not claimed")]
+    PositionNotClaimed,
+    #[msg("Market not settled or voided")]
+    MarketNotFinalized,
+    #[msg("Positions are still unclaimed")]
+    PositionsUnclaimed,
+    #[msg("Fees not claimed")]
+    FeesNotClaimed,
+    #[msg("Invalid resolution source")]
+    InvalidResolutionSource,
+    #[msg("Invalid price feed")]
+    InvalidPriceFeed,
+    #[msg("No trading price published since trading closed")]
+    PriceUnavailable,
+    #[msg("Not enough resolvers agree on an outcome")]
+    ResolutionPending,
+    #[msg("An outcome was already proposed")]
+    OutcomeAlreadyProposed,
+    #[msg("No outcome proposed")]
+    NoProposal,
+    #[msg("Dispute window closed")]
+    DisputeWindowClosed,
+    #[msg("Dispute window still open")]
+    DisputeWindowOpen,
+    #[msg("Bonds not claimed")]
+    BondsNotClaimed,
+    #[msg("No bond to claim")]
+    NoBondToClaim,
+    #[msg("Scalar markets need two options and a lower bound below the upper bound")]
+    InvalidScalarBounds,
+    #[msg("Scalar markets settle with a value and claim with claim_rewards_scalar")]
+    ScalarMarket,
+    #[msg("Not a scalar market")]
+    NotScalarMarket,
+    #[msg("Limit order not open or busy")]
+    OrderNotOpen,
+    #[msg("Limit orders must be distinct and fill the slots in order")]
+    InvalidOrders,
+    #[msg("Batch epoch can't be negative")]
+    InvalidBatchEpoch,
+    #[msg("Reveal policy needs a positive bucket or scale, an interval of at least MARKET_REVEAL_PROBS_TIME and probs only when votes are hidden")]
+    InvalidRevealConfig,
+    #[msg("More complete sets than were minted and not redeemed")]
+    InsufficientSets,
+    #[msg("The price was published after the settlement window")]
+    SettlementWindowClosed,
+    #[msg("Arithmetic overflow")]
+    Overflow,
+    #[msg("Limit orders still hold escrow")]
+    OrdersOpen,
+    #[msg("Batch tickets still hold a budget")]
+    TicketsOpen,
+    #[msg("The market id was used by a closed market")]
+    MarketIdClosed,
 }
@@ -1,11 +1,15 @@
 use anchor_lang::prelude::*;
+use crate::constants::MAX_OPTIONS;
+use crate::states::FeeBucket;
 
 
 #[event]
 pub struct RevealProbsEvent {
     pub market_id: u32,
-    pub probs: [f64; 2],
-    pub votes: [u64; 2],
+    pub probs: [u64; MAX_OPTIONS],
+    pub votes: [u64; MAX_OPTIONS], // as the market's reveal policy exposes them, zero for probs only
+    pub cranker: Pubkey,
+    pub reward: u64,
 }
 
 #[event]
@@ -15,6 +19,7 @@ pub struct BuySharesEvent {
     pub timestamp: i64,
     pub amount: u64,
     pub tvl: u64,
+    pub fee: u64, // paid on top of amount
 }
 
 
@@ -25,6 +30,61 @@ pub struct SellSharesEvent {
     pub timestamp: i64,
     pub amount: u64,
     pub tvl: u64,
+    pub fee: u64, // taken out of amount
+}
+
+#[event]
+pub struct CompleteSetMintedEvent {
+    pub market_id: u32,
+    pub status: u8,
+    pub shares: u64, // of every outcome
+    pub amount: u64, // collateral taken
+    pub tvl: u64,
+}
+
+#[event]
+pub struct CompleteSetRedeemedEvent {
+    pub market_id: u32,
+    pub status: u8,
+    pub shares: u64, // of every outcome
+    pub amount: u64, // collateral returned
+    pub tvl: u64,
+}
+
+// Only the position owner can decrypt `shares` and `cost_basis`, with the x25519 key the view was requested for
+#[event]
+pub struct PositionViewEvent {
+    pub market_id: u32,
+    pub user_position: Pubkey,
+    pub trade_seq: u64, // positions are rewritten by trades, a view is current while the position's trade_seq matches
+    pub encryption_key: [u8; 32],
+    pub nonce: u128,
+    pub shares: [[u8; 32]; MAX_OPTIONS],
+    pub cost_basis: [[u8; 32]; MAX_OPTIONS],
+}
+
+// `value` is the position's sell-everything proceeds net of fees, PnL is value - cost_basis. Both only decrypt with the
+// owner's x25519 key
+#[event]
+pub struct PositionValueEvent {
+    pub market_id: u32,
+    pub user_position: Pubkey,
+    pub trade_seq: u64,
+    pub encryption_key: [u8; 32],
+    pub nonce: u128,
+    pub value: [u8; 32],
+    pub cost_basis: [u8; 32],
+}
+
+// Requesters match their quote by `encryption_key`, it is priced at `market_version` and stale once a trade lands
+#[event]
+pub struct TradeQuoteEvent {
+    pub market_id: u32,
+    pub market_version: u64,
+    pub encryption_key: [u8; 32],
+    pub nonce: u128,
+    pub buy_cost: [u8; 32],
+    pub sell_proceeds: [u8; 32],
 }
 
 #[event]
@@ -42,12 +102,147 @@ pub struct InitMarketStatsEvent {
 pub struct MarketSettledEvent {
     pub market_id: u32,
     pub winning_outcome: u8,
-    pub probs: [f64; 2],
-    pub votes: [u64; 2],
+    pub probs: [u64; MAX_OPTIONS],
+    pub votes: [u64; MAX_OPTIONS],
+    pub liability: u64, // owed to winning shares, reserved out of tvl
+}
+
+#[event]
+pub struct MarketExpiredEvent {
+    pub market_id: u32,
+    pub resolve_by: i64,
+}
+
+#[event]
+pub struct MarketVoidedEvent {
+    pub market_id: u32,
+    pub refund_pool: u64,
+}
+
+#[event]
+pub struct ClaimRefundEvent {
+    pub market_id: u32,
+    pub amount: u64,
+}
+
+#[event]
+pub struct FeesAccruedEvent {
+    pub market_id: u32,
+    pub protocol: u64,
+    pub creator: u64,
+    pub lp: u64,
+}
+
+#[event]
+pub struct ClaimFeesEvent {
+    pub market_id: u32,
+    pub bucket: FeeBucket,
+    pub amount: u64,
 }
 
 #[event]
 pub struct ClaimMarketFundsEvent {
     pub market_id: u32,
     pub amount: u64,
-}
\ No newline at end of file
+}
+
+#[event]
+pub struct ResolutionSubmittedEvent {
+    pub market_id: u32,
+    pub resolver: Pubkey,
+    pub outcome: u8,
+}
+
+#[event]
+pub struct OutcomeProposedEvent {
+    pub market_id: u32,
+    pub proposer: Pubkey,
+    pub outcome: u8,
+    pub bond: u64,
+    pub dispute_ends_at: i64,
+}
+
+#[event]
+pub struct OutcomeDisputedEvent {
+    pub market_id: u32,
+    pub disputer: Pubkey,
+    pub bond: u64,
+}
+
+#[event]
+pub struct ClaimBondEvent {
+    pub market_id: u32,
+    pub claimant: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct MarketClosedEvent {
+    pub market_id: u32,
+    pub remaining: u64, // tvl credited back to the authority
+    pub unclaimed_positions: u32,
+}
+
+#[event]
+pub struct PendingTradeReleasedEvent {
+    pub market_id: u32,
+    pub trade_seq: u64,
+    pub amount: u64,
+}
+
+#[event]
+pub struct BatchOpenedEvent {
+    pub market_id: u32,
+    pub batch_id: u64,
+    pub ends_at: i64,
+}
+
+#[event]
+pub struct BatchTradeCommittedEvent {
+    pub market_id: u32,
+    pub batch_id: u64,
+    pub amount: u64,
+    pub deposits: u64,
+}
+
+#[event]
+pub struct BatchClearedEvent {
+    pub market_id: u32,
+    pub batch_id: u64,
+    pub status: u8,
+    pub amount: u64,
+    pub num_trades: u32,
+    pub tvl: u64,
+}
+
+#[event]
+pub struct BatchTradeSettledEvent {
+    pub market_id: u32,
+    pub batch_id: u64,
+    pub refunded: bool,
+}
+
+#[event]
+pub struct LimitOrderPlacedEvent {
+    pub market_id: u32,
+    pub order_id: u64,
+    pub escrow: u64,
+}
+
+#[event]
+pub struct OrdersMatchedEvent {
+    pub market_id: u32,
+    pub status: u8,
+    pub num_orders: u8,
+    pub amount: u64, // total cost of the orders that filled, which ones did stays private
+    pub tvl: u64,
+}
+
+#[event]
+pub struct LimitOrderSettledEvent {
+    pub market_id: u32,
+    pub order_id: u64,
+    pub cost: u64,
+    pub fee: u64,
+    pub refund: u64,
+}
@@ -8,6 +8,7 @@ mod contexts;
 mod events;
 mod macros;
 mod utils;
+mod oracle;
 use states::*;
 use constants::*;
 use errors::ErrorCode;
@@ -21,39 +22,168 @@ declare_id!("QwUyXjr6YcSmtzqcigtTHDy9fAStMYGxsQgn7Rzrwj9");
 pub mod arx_predict {
     use super::*;
 
+    // CONFIG
+    pub fn initialize_config(ctx: Context<InitializeConfig>, params: ConfigParams) -> Result<()> {
+        ctx.accounts.initialize_config(params, ctx.bumps.config)
+    }
+
+    pub fn update_config(ctx: Context<UpdateConfig>, params: ConfigParams) -> Result<()> {
+        ctx.accounts.update_config(params)
+    }
+
+    pub fn transfer_config_authority(
+        ctx: Context<TransferConfigAuthority>,
+        new_authority: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.transfer_config_authority(new_authority)
+    }
+
+    pub fn accept_config_authority(ctx: Context<AcceptConfigAuthority>) -> Result<()> {
+        ctx.accounts.accept_config_authority()
+    }
+
     // INIT COMP DEF
     pub fn init_market_stats_comp_def(ctx: Context<InitMarketStatsCompDef>) -> Result<()> {
-        init_comp_def(ctx.accounts, true, 0, conditional_circuit_source!(INIT_MARKET_STATS_CIRCUIT), None)?;
+        let source = conditional_circuit_source!(ctx.accounts.config, INIT_MARKET_STATS_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
         Ok(())
     }
 
     pub fn init_user_position_comp_def(ctx: Context<InitUserPositionCompDef>) -> Result<()> {
-        init_comp_def(ctx.accounts, true, 0, conditional_circuit_source!(INIT_USER_POSITION_CIRCUIT), None)?;
+        let source = conditional_circuit_source!(ctx.accounts.config, INIT_USER_POSITION_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
         Ok(())
     }
 
     pub fn init_buy_shares_comp_def(ctx: Context<InitBuySharesCompDef>) -> Result<()> {
-        init_comp_def(ctx.accounts, true, 0, conditional_circuit_source!(BUY_SHARES_CIRCUIT), None)?;
+        let source = conditional_circuit_source!(ctx.accounts.config, BUY_SHARES_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
         Ok(())
     }
 
     pub fn init_sell_shares_comp_def(ctx: Context<InitSellSharesCompDef>) -> Result<()> {
-        init_comp_def(ctx.accounts, true, 0, conditional_circuit_source!(SELL_SHARES_CIRCUIT), None)?;
+        let source = conditional_circuit_source!(ctx.accounts.config, SELL_SHARES_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
+        Ok(())
+    }
+
+    pub fn init_buy_with_amount_comp_def(ctx: Context<InitBuyWithAmountCompDef>) -> Result<()> {
+        let source = conditional_circuit_source!(ctx.accounts.config, BUY_WITH_AMOUNT_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
+        Ok(())
+    }
+
+    pub fn init_buy_shares_private_comp_def(ctx: Context<InitBuySharesPrivateCompDef>) -> Result<()> {
+        let source = conditional_circuit_source!(ctx.accounts.config, BUY_SHARES_PRIVATE_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
+        Ok(())
+    }
+
+    pub fn init_sell_shares_private_comp_def(ctx: Context<InitSellSharesPrivateCompDef>) -> Result<()> {
+        let source = conditional_circuit_source!(ctx.accounts.config, SELL_SHARES_PRIVATE_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
+        Ok(())
+    }
+
+    pub fn init_init_batch_stats_comp_def(ctx: Context<InitInitBatchStatsCompDef>) -> Result<()> {
+        let source = conditional_circuit_source!(ctx.accounts.config, INIT_BATCH_STATS_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
+        Ok(())
+    }
+
+    pub fn init_commit_batch_trade_comp_def(ctx: Context<InitCommitBatchTradeCompDef>) -> Result<()> {
+        let source = conditional_circuit_source!(ctx.accounts.config, COMMIT_BATCH_TRADE_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
+        Ok(())
+    }
+
+    pub fn init_clear_batch_comp_def(ctx: Context<InitClearBatchCompDef>) -> Result<()> {
+        let source = conditional_circuit_source!(ctx.accounts.config, CLEAR_BATCH_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
+        Ok(())
+    }
+
+    pub fn init_settle_batch_trade_comp_def(ctx: Context<InitSettleBatchTradeCompDef>) -> Result<()> {
+        let source = conditional_circuit_source!(ctx.accounts.config, SETTLE_BATCH_TRADE_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
+        Ok(())
+    }
+
+    pub fn init_place_limit_order_comp_def(ctx: Context<InitPlaceLimitOrderCompDef>) -> Result<()> {
+        let source = conditional_circuit_source!(ctx.accounts.config, PLACE_LIMIT_ORDER_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
+        Ok(())
+    }
+
+    pub fn init_match_orders_comp_def(ctx: Context<InitMatchOrdersCompDef>) -> Result<()> {
+        let source = conditional_circuit_source!(ctx.accounts.config, MATCH_ORDERS_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
+        Ok(())
+    }
+
+    pub fn init_settle_limit_order_comp_def(ctx: Context<InitSettleLimitOrderCompDef>) -> Result<()> {
+        let source = conditional_circuit_source!(ctx.accounts.config, SETTLE_LIMIT_ORDER_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
+        Ok(())
+    }
+
+    pub fn init_mint_complete_set_comp_def(ctx: Context<InitMintCompleteSetCompDef>) -> Result<()> {
+        let source = conditional_circuit_source!(ctx.accounts.config, MINT_COMPLETE_SET_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
+        Ok(())
+    }
+
+    pub fn init_redeem_complete_set_comp_def(ctx: Context<InitRedeemCompleteSetCompDef>) -> Result<()> {
+        let source = conditional_circuit_source!(ctx.accounts.config, REDEEM_COMPLETE_SET_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
         Ok(())
     }
 
     pub fn init_reveal_market_comp_def(ctx: Context<InitRevealMarketCompDef>) -> Result<()> {
-        init_comp_def(ctx.accounts, true, 0, conditional_circuit_source!(REVEAL_MARKET_CIRCUIT), None)?;
+        let source = conditional_circuit_source!(ctx.accounts.config, REVEAL_MARKET_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
         Ok(())
     }
 
     pub fn init_reveal_probs_comp_def(ctx: Context<InitRevealProbsCompDef>) -> Result<()> {
-        init_comp_def(ctx.accounts, true, 0, conditional_circuit_source!(REVEAL_PROBS_CIRCUIT), None)?;
+        let source = conditional_circuit_source!(ctx.accounts.config, REVEAL_PROBS_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
         Ok(())
     }
 
     pub fn init_claim_rewards_comp_def(ctx: Context<InitClaimRewardsCompDef>) -> Result<()> {
-        init_comp_def(ctx.accounts, true, 0, conditional_circuit_source!(CLAIM_REWARDS_CIRCUIT), None)?;
+        let source = conditional_circuit_source!(ctx.accounts.config, CLAIM_REWARDS_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
+        Ok(())
+    }
+
+    pub fn init_claim_refund_comp_def(ctx: Context<InitClaimRefundCompDef>) -> Result<()> {
+        let source = conditional_circuit_source!(ctx.accounts.config, CLAIM_REFUND_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
+        Ok(())
+    }
+
+    pub fn init_claim_rewards_scalar_comp_def(ctx: Context<InitClaimRewardsScalarCompDef>) -> Result<()> {
+        let source = conditional_circuit_source!(ctx.accounts.config, CLAIM_REWARDS_SCALAR_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
+        Ok(())
+    }
+
+    pub fn init_view_position_comp_def(ctx: Context<InitViewPositionCompDef>) -> Result<()> {
+        let source = conditional_circuit_source!(ctx.accounts.config, VIEW_POSITION_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
+        Ok(())
+    }
+
+    pub fn init_value_position_comp_def(ctx: Context<InitValuePositionCompDef>) -> Result<()> {
+        let source = conditional_circuit_source!(ctx.accounts.config, VALUE_POSITION_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
+        Ok(())
+    }
+
+    pub fn init_quote_trade_comp_def(ctx: Context<InitQuoteTradeCompDef>) -> Result<()> {
+        let source = conditional_circuit_source!(ctx.accounts.config, QUOTE_TRADE_CIRCUIT);
+        init_comp_def(ctx.accounts, true, 0, source, None)?;
         Ok(())
     }
 
@@ -69,10 +199,7 @@ pub mod arx_predict {
             _ => return Err(ErrorCode::AbortedComputation.into()),
         };
 
-        ctx.accounts.market_acc.vote_state = o.ciphertexts[0..2].try_into().unwrap();
-        ctx.accounts.market_acc.probs = o.ciphertexts[2..4].try_into().unwrap();
-        ctx.accounts.market_acc.cost = o.ciphertexts[4].try_into().unwrap();
-        ctx.accounts.market_acc.nonce = o.nonce;
+        ctx.accounts.market_acc.set_market_stats(&o.ciphertexts, o.nonce);
         ctx.accounts.market_acc.updated_at = 0;
 
         emit!(InitMarketStatsEvent {
@@ -82,8 +209,6 @@ pub mod arx_predict {
         Ok(())
     }
 
-    
-
     #[arcium_callback(encrypted_ix = "init_user_position")]
     pub fn init_user_position_callback(
         ctx: Context<InitUserPositionCallback>,
@@ -94,8 +219,7 @@ pub mod arx_predict {
             _ => return Err(ErrorCode::AbortedComputation.into()),
         };
 
-        ctx.accounts.user_position_acc.shares = o.ciphertexts;
-        ctx.accounts.user_position_acc.nonce = o.nonce;
+        ctx.accounts.user_position_acc.set_shares(&o.ciphertexts, o.nonce);
 
         Ok(())
     }
@@ -105,44 +229,80 @@ pub mod arx_predict {
         ctx: Context<BuySharesCallback>,
         output: ComputationOutputs<BuySharesOutput>,
     ) -> Result<()> {
-        require!(ctx.accounts.market_acc.status == MarketStatus::Active, ErrorCode::MarketActive);
         let o = match output {
             ComputationOutputs::Success(BuySharesOutput { field_0 }) => field_0,
             _ => return Err(ErrorCode::AbortedComputation.into()),
         };
-        let amount = convert_f64_to_token_amount(o.field_2, ctx.accounts.market_acc.mint_decimals)?;
-        let clock = Clock::get()?;
-        let current_timestamp = clock.unix_timestamp;
-
-
-        if ctx.accounts.user_position_acc.balance < amount {
-            emit!(BuySharesEvent {
-                market_id: ctx.accounts.market_acc.id,
-                status: 0,
-                timestamp: current_timestamp,
-                amount: amount,
-                tvl: ctx.accounts.market_acc.tvl,
-            });
-            return Ok(()); //TODO, cant return error here because of the callback
-        }
-        ctx.accounts.user_position_acc.balance -= amount;
-        ctx.accounts.market_acc.vote_state = o.field_0.ciphertexts[0..2].try_into().unwrap();
-        ctx.accounts.market_acc.probs = o.field_0.ciphertexts[2..4].try_into().unwrap();
-        ctx.accounts.market_acc.cost = o.field_0.ciphertexts[4].try_into().unwrap();
-        ctx.accounts.market_acc.nonce = o.field_0.nonce;
-        ctx.accounts.user_position_acc.shares = o.field_1.ciphertexts;  
-        ctx.accounts.user_position_acc.nonce = o.field_1.nonce;
-        ctx.accounts.market_acc.tvl += amount;
-        
-        emit!(BuySharesEvent {
-            market_id: ctx.accounts.market_acc.id,
-            status: 1,
-            timestamp: current_timestamp,
-            amount: amount,
-            tvl: ctx.accounts.market_acc.tvl,
-        });
 
-        Ok(())
+        apply_buy(
+            &mut ctx.accounts.market_acc,
+            &mut ctx.accounts.user_position_acc,
+            &mut ctx.accounts.user_account,
+            TradeOutput {
+                market_stats: &o.field_0.ciphertexts,
+                market_nonce: o.field_0.nonce,
+                user_position: &o.field_1.ciphertexts,
+                user_position_nonce: o.field_1.nonce,
+                amount: o.field_2,
+                status: o.field_3,
+                trade_seq: o.field_4,
+                market_version: o.field_5,
+            },
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "buy_shares_private")]
+    pub fn buy_shares_private_callback(
+        ctx: Context<BuySharesPrivateCallback>,
+        output: ComputationOutputs<BuySharesPrivateOutput>,
+    ) -> Result<()> {
+        let o = match output {
+            ComputationOutputs::Success(BuySharesPrivateOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        apply_buy(
+            &mut ctx.accounts.market_acc,
+            &mut ctx.accounts.user_position_acc,
+            &mut ctx.accounts.user_account,
+            TradeOutput {
+                market_stats: &o.field_0.ciphertexts,
+                market_nonce: o.field_0.nonce,
+                user_position: &o.field_1.ciphertexts,
+                user_position_nonce: o.field_1.nonce,
+                amount: o.field_2,
+                status: o.field_3,
+                trade_seq: o.field_4,
+                market_version: o.field_5,
+            },
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "buy_with_amount")]
+    pub fn buy_with_amount_callback(
+        ctx: Context<BuyWithAmountCallback>,
+        output: ComputationOutputs<BuyWithAmountOutput>,
+    ) -> Result<()> {
+        let o = match output {
+            ComputationOutputs::Success(BuyWithAmountOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        apply_buy(
+            &mut ctx.accounts.market_acc,
+            &mut ctx.accounts.user_position_acc,
+            &mut ctx.accounts.user_account,
+            TradeOutput {
+                market_stats: &o.field_0.ciphertexts,
+                market_nonce: o.field_0.nonce,
+                user_position: &o.field_1.ciphertexts,
+                user_position_nonce: o.field_1.nonce,
+                amount: o.field_2,
+                status: o.field_3,
+                trade_seq: o.field_4,
+                market_version: o.field_5,
+            },
+        )
     }
 
     #[arcium_callback(encrypted_ix = "sell_shares")]
@@ -150,43 +310,371 @@ pub mod arx_predict {
         ctx: Context<SellSharesCallback>,
         output: ComputationOutputs<SellSharesOutput>,
     ) -> Result<()> {
-        require!(ctx.accounts.market_acc.status == MarketStatus::Active, ErrorCode::MarketActive);
         let o = match output {
             ComputationOutputs::Success(SellSharesOutput { field_0 }) => field_0,
             _ => return Err(ErrorCode::AbortedComputation.into()),
         };
-        let status = o.field_3;
 
-        let clock = Clock::get()?;
-        let current_timestamp = clock.unix_timestamp;
-
-
-        if status == 0 { // Insufficient shares
-            emit!(SellSharesEvent {
-                market_id: ctx.accounts.market_acc.id,
-                status: 0,
-                timestamp: current_timestamp,
-                amount: 0,
-                tvl: ctx.accounts.market_acc.tvl,
-            });
-            return Ok(()); //TODO, cant return error here because of the callback
+        apply_sell(
+            &mut ctx.accounts.market_acc,
+            &mut ctx.accounts.user_position_acc,
+            &mut ctx.accounts.user_account,
+            TradeOutput {
+                market_stats: &o.field_0.ciphertexts,
+                market_nonce: o.field_0.nonce,
+                user_position: &o.field_1.ciphertexts,
+                user_position_nonce: o.field_1.nonce,
+                amount: o.field_2,
+                status: o.field_3,
+                trade_seq: o.field_4,
+                market_version: o.field_5,
+            },
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "sell_shares_private")]
+    pub fn sell_shares_private_callback(
+        ctx: Context<SellSharesPrivateCallback>,
+        output: ComputationOutputs<SellSharesPrivateOutput>,
+    ) -> Result<()> {
+        let o = match output {
+            ComputationOutputs::Success(SellSharesPrivateOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        apply_sell(
+            &mut ctx.accounts.market_acc,
+            &mut ctx.accounts.user_position_acc,
+            &mut ctx.accounts.user_account,
+            TradeOutput {
+                market_stats: &o.field_0.ciphertexts,
+                market_nonce: o.field_0.nonce,
+                user_position: &o.field_1.ciphertexts,
+                user_position_nonce: o.field_1.nonce,
+                amount: o.field_2,
+                status: o.field_3,
+                trade_seq: o.field_4,
+                market_version: o.field_5,
+            },
+        )
+    }
+
+    #[arcium_callback(encrypted_ix = "init_batch_stats")]
+    pub fn init_batch_stats_callback(
+        ctx: Context<InitBatchStatsCallback>,
+        output: ComputationOutputs<InitBatchStatsOutput>,
+    ) -> Result<()> {
+        let o = match output {
+            ComputationOutputs::Success(InitBatchStatsOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        ctx.accounts.batch_acc.set_batch_stats(&o.ciphertexts, o.nonce);
+        ctx.accounts.batch_acc.status = BatchStatus::Open;
+
+        emit!(BatchOpenedEvent {
+            market_id: ctx.accounts.batch_acc.market_id,
+            batch_id: ctx.accounts.batch_acc.batch_id,
+            ends_at: ctx.accounts.batch_acc.ends_at,
+        });
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "commit_batch_trade")]
+    pub fn commit_batch_trade_callback(
+        ctx: Context<CommitBatchTradeCallback>,
+        output: ComputationOutputs<CommitBatchTradeOutput>,
+    ) -> Result<()> {
+        let o = match output {
+            ComputationOutputs::Success(CommitBatchTradeOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        ctx.accounts.batch_acc.set_batch_stats(&o.ciphertexts, o.nonce);
+        ctx.accounts.batch_acc.pending = false;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "clear_batch")]
+    pub fn clear_batch_callback(
+        ctx: Context<ClearBatchCallback>,
+        output: ComputationOutputs<ClearBatchOutput>,
+    ) -> Result<()> {
+        let o = match output {
+            ComputationOutputs::Success(ClearBatchOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+        // A clearing that timed out already failed the batch and its tickets may be refunded
+        if ctx.accounts.batch_acc.status != BatchStatus::Clearing {
+            return Ok(());
+        }
+        let amount = o.field_2;
+        let mut status = o.field_3;
+
+        // A trade landed on the market while clearing, the batch goes back to Open and can be cleared again
+        if o.field_4 != ctx.accounts.market_acc.state_version {
+            status = TRADE_STATUS_STALE;
+            ctx.accounts.batch_acc.status = BatchStatus::Open;
+        } else if status == TRADE_STATUS_SUCCESS {
+            ctx.accounts.market_acc.set_market_stats(&o.field_0.ciphertexts, o.field_0.nonce);
+            ctx.accounts.batch_acc.set_batch_stats(&o.field_1.ciphertexts, o.field_1.nonce);
+            ctx.accounts.batch_acc.status = BatchStatus::Cleared;
+            // Rounding leftovers between the deposits and the amount stay in the market
+            ctx.accounts.market_acc.tvl += ctx.accounts.batch_acc.deposits;
+            accrue_fees(&mut ctx.accounts.market_acc, ctx.accounts.batch_acc.fees);
+        } else {
+            // Tickets of a failed batch are refunded, nothing was bought
+            ctx.accounts.batch_acc.status = BatchStatus::Failed;
         }
-        
-        let amount = convert_f64_to_token_amount(-o.field_2, ctx.accounts.market_acc.mint_decimals)?;
-        ctx.accounts.user_position_acc.balance += amount;
-        ctx.accounts.market_acc.vote_state = o.field_0.ciphertexts[0..2].try_into().unwrap();
-        ctx.accounts.market_acc.probs = o.field_0.ciphertexts[2..4].try_into().unwrap();
-        ctx.accounts.market_acc.cost = o.field_0.ciphertexts[4].try_into().unwrap();
-        ctx.accounts.market_acc.nonce = o.field_0.nonce;
-        ctx.accounts.user_position_acc.shares = o.field_1.ciphertexts;  
-        ctx.accounts.user_position_acc.nonce = o.field_1.nonce;
-        ctx.accounts.market_acc.tvl -= amount;
-        
-        emit!(SellSharesEvent {
+
+        emit!(BatchClearedEvent {
             market_id: ctx.accounts.market_acc.id,
-            status: 1,
-            timestamp: current_timestamp,
-            amount: amount,
+            batch_id: ctx.accounts.batch_acc.batch_id,
+            status,
+            amount,
+            num_trades: ctx.accounts.batch_acc.num_trades,
+            tvl: ctx.accounts.market_acc.tvl,
+        });
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "settle_batch_trade")]
+    pub fn settle_batch_trade_callback(
+        ctx: Context<SettleBatchTradeCallback>,
+        output: ComputationOutputs<SettleBatchTradeOutput>,
+    ) -> Result<()> {
+        let o = match output {
+            ComputationOutputs::Success(SettleBatchTradeOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        if !ctx.accounts.user_position_acc.is_pending_trade(o.field_1) {
+            return Ok(());
+        }
+        ctx.accounts.user_position_acc.set_shares(&o.field_0.ciphertexts, o.field_0.nonce);
+        ctx.accounts.user_position_acc.end_trade(0);
+        ctx.accounts.batch_ticket_acc.settled = true;
+        ctx.accounts.market_acc.open_tickets = ctx.accounts.market_acc.open_tickets.checked_sub(1).ok_or(ErrorCode::Overflow)?;
+
+        emit!(BatchTradeSettledEvent {
+            market_id: ctx.accounts.batch_ticket_acc.market_id,
+            batch_id: ctx.accounts.batch_ticket_acc.batch_id,
+            refunded: false,
+        });
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "place_limit_order")]
+    pub fn place_limit_order_callback(
+        ctx: Context<PlaceLimitOrderCallback>,
+        output: ComputationOutputs<PlaceLimitOrderOutput>,
+    ) -> Result<()> {
+        let o = match output {
+            ComputationOutputs::Success(PlaceLimitOrderOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        // A placement that timed out was already refunded
+        if ctx.accounts.limit_order_acc.status != OrderStatus::Placing {
+            return Ok(());
+        }
+        ctx.accounts.limit_order_acc.set_order(&o.ciphertexts, o.nonce);
+        ctx.accounts.limit_order_acc.status = OrderStatus::Open;
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "match_orders")]
+    pub fn match_orders_callback(
+        ctx: Context<MatchOrdersCallback>,
+        output: ComputationOutputs<MatchOrdersOutput>,
+    ) -> Result<()> {
+        let o = match output {
+            ComputationOutputs::Success(MatchOrdersOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+        let market_version = o.field_6;
+        let market_acc = &mut ctx.accounts.market_acc;
+        let orders = [
+            Some(&mut ctx.accounts.order_0),
+            ctx.accounts.order_1.as_mut(),
+            ctx.accounts.order_2.as_mut(),
+            ctx.accounts.order_3.as_mut(),
+        ];
+
+        // An order settled or matched again since then drops the whole result, the fills can't be split out of the market stats
+        let matching = orders.iter().flatten().all(|order| order.is_matching(market_version));
+        let market_open = matches!(market_acc.status, MarketStatus::Active | MarketStatus::Closed);
+        let status = if !matching || !market_open {
+            TRADE_STATUS_FAILED
+        } else if market_version != market_acc.state_version {
+            TRADE_STATUS_STALE
+        } else {
+            TRADE_STATUS_SUCCESS
+        };
+
+        let mut amount = 0;
+        if status == TRADE_STATUS_SUCCESS {
+            amount = o.field_5;
+            market_acc.set_market_stats(&o.field_0.ciphertexts, o.field_0.nonce);
+            // The fills were paid out of the orders' escrows, which stay outside tvl until then
+            market_acc.tvl += amount;
+        }
+        let mut num_orders = 0;
+        for (order, order_output) in orders.into_iter().zip([&o.field_1, &o.field_2, &o.field_3, &o.field_4]) {
+            let Some(order) = order else {
+                continue;
+            };
+            num_orders += 1;
+            if order.is_matching(market_version) {
+                if status == TRADE_STATUS_SUCCESS {
+                    order.set_order(&order_output.ciphertexts, order_output.nonce);
+                }
+                order.status = OrderStatus::Open;
+            }
+        }
+
+        emit!(OrdersMatchedEvent {
+            market_id: market_acc.id,
+            status,
+            num_orders,
+            amount,
+            tvl: market_acc.tvl,
+        });
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "settle_limit_order")]
+    pub fn settle_limit_order_callback(
+        ctx: Context<SettleLimitOrderCallback>,
+        output: ComputationOutputs<SettleLimitOrderOutput>,
+    ) -> Result<()> {
+        let o = match output {
+            ComputationOutputs::Success(SettleLimitOrderOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        if !ctx.accounts.user_position_acc.is_pending_trade(o.field_2)
+            || ctx.accounts.limit_order_acc.status != OrderStatus::Settling
+        {
+            return Ok(());
+        }
+        let cost = o.field_1;
+        ctx.accounts.user_position_acc.set_shares(&o.field_0.ciphertexts, o.field_0.nonce);
+        ctx.accounts.user_position_acc.end_trade(0);
+        // The circuit kept the cost within the escrow net of this fee
+        let fee = ctx.accounts.market_acc.trade_fee(cost);
+        let refund = ctx.accounts.limit_order_acc.escrow - cost - fee;
+        accrue_fees(&mut ctx.accounts.market_acc, fee);
+        ctx.accounts.user_account.credit(refund);
+        ctx.accounts.limit_order_acc.escrow = 0;
+        ctx.accounts.limit_order_acc.status = OrderStatus::Settled;
+        ctx.accounts.market_acc.open_orders = ctx.accounts.market_acc.open_orders.checked_sub(1).ok_or(ErrorCode::Overflow)?;
+
+        emit!(LimitOrderSettledEvent {
+            market_id: ctx.accounts.limit_order_acc.market_id,
+            order_id: ctx.accounts.limit_order_acc.order_id,
+            cost,
+            fee,
+            refund,
+        });
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "mint_complete_set")]
+    pub fn mint_complete_set_callback(
+        ctx: Context<MintCompleteSetCallback>,
+        output: ComputationOutputs<MintCompleteSetOutput>,
+    ) -> Result<()> {
+        let o = match output {
+            ComputationOutputs::Success(MintCompleteSetOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+        if !ctx.accounts.user_position_acc.is_pending_trade(o.field_3) {
+            return Ok(());
+        }
+        let shares = o.field_2;
+        // Settlement is only queued once trading closed, sets minted before then are in the liability it reveals
+        let status = if !ctx.accounts.market_acc.is_trading(Clock::get()?.unix_timestamp) {
+            TRADE_STATUS_FAILED
+        } else if o.field_4 != ctx.accounts.market_acc.state_version {
+            TRADE_STATUS_STALE
+        } else {
+            TRADE_STATUS_SUCCESS
+        };
+        let mut amount = 0;
+        if status == TRADE_STATUS_SUCCESS {
+            amount = ctx.accounts.user_position_acc.locked_amount;
+            ctx.accounts.market_acc.set_market_stats(&o.field_0.ciphertexts, o.field_0.nonce);
+            ctx.accounts.user_position_acc.set_shares(&o.field_1.ciphertexts, o.field_1.nonce);
+            ctx.accounts.user_position_acc.end_trade(amount);
+            ctx.accounts.user_position_acc.minted_sets += shares;
+            ctx.accounts.market_acc.tvl += amount;
+            ctx.accounts.market_acc.complete_sets += shares;
+            ctx.accounts.market_acc.set_collateral += amount;
+        } else {
+            let refund = ctx.accounts.user_position_acc.end_trade(0);
+            ctx.accounts.user_account.credit(refund);
+        }
+
+        emit!(CompleteSetMintedEvent {
+            market_id: ctx.accounts.market_acc.id,
+            status,
+            shares,
+            amount,
+            tvl: ctx.accounts.market_acc.tvl,
+        });
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "redeem_complete_set")]
+    pub fn redeem_complete_set_callback(
+        ctx: Context<RedeemCompleteSetCallback>,
+        output: ComputationOutputs<RedeemCompleteSetOutput>,
+    ) -> Result<()> {
+        let o = match output {
+            ComputationOutputs::Success(RedeemCompleteSetOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+        if !ctx.accounts.user_position_acc.is_pending_trade(o.field_4) {
+            return Ok(());
+        }
+        ctx.accounts.user_position_acc.end_trade(0);
+        let shares = o.field_3;
+        // A market finalized while the redeem was queued pays the sets through its claims, the position keeps them
+        let mut status = o.field_2;
+        if !ctx.accounts.market_acc.can_redeem_sets() {
+            status = TRADE_STATUS_FAILED;
+        } else if o.field_5 != ctx.accounts.market_acc.state_version {
+            status = TRADE_STATUS_STALE;
+        }
+        let mut amount = 0;
+        if status == TRADE_STATUS_SUCCESS {
+            amount = shares_to_amount(shares, token_unit(ctx.accounts.market_acc.mint_decimals)?);
+            ctx.accounts.user_position_acc.minted_sets = ctx
+                .accounts
+                .user_position_acc
+                .minted_sets
+                .checked_sub(shares)
+                .ok_or(ErrorCode::InsufficientSets)?;
+            ctx.accounts.market_acc.redeem_sets(shares, amount)?;
+            ctx.accounts.market_acc.set_market_stats(&o.field_0.ciphertexts, o.field_0.nonce);
+            ctx.accounts.user_position_acc.set_shares(&o.field_1.ciphertexts, o.field_1.nonce);
+            ctx.accounts.user_account.credit(amount);
+        }
+
+        emit!(CompleteSetRedeemedEvent {
+            market_id: ctx.accounts.market_acc.id,
+            status,
+            shares,
+            amount,
             tvl: ctx.accounts.market_acc.tvl,
         });
 
@@ -202,16 +690,28 @@ pub mod arx_predict {
             ComputationOutputs::Success(RevealMarketOutput { field_0 }) => field_0,
             _ => return Err(ErrorCode::AbortedComputation.into()),
         };
+        // Revealed stats must include every trade, settlement is queued again if one landed in between. The
+        // outcome was locked when settlement was first queued, the liability is only valid for that one
+        if o.field_4 != ctx.accounts.market_acc.state_version
+            || ctx.accounts.market_acc.status != MarketStatus::Settling
+            || o.field_0 != ctx.accounts.market_acc.winning_outcome
+        {
+            return Ok(());
+        }
         ctx.accounts.market_acc.status = MarketStatus::Settled;
-        ctx.accounts.market_acc.winning_outcome = o.field_0;
+        ctx.accounts.market_acc.finalized_at = Clock::get()?.unix_timestamp;
         ctx.accounts.market_acc.probs_revealed = o.field_1;
         ctx.accounts.market_acc.votes_revealed = o.field_2;
+        // Each winning share pays one token, scalar shares their part of it. Computed inside the MPC so it doesn't
+        // need the vote totals, the per position floors in the claims never add up to more
+        ctx.accounts.market_acc.reserved_liability = o.field_3;
 
         emit!(MarketSettledEvent { 
             market_id: ctx.accounts.market_acc.id,
             winning_outcome: ctx.accounts.market_acc.winning_outcome,
             probs: ctx.accounts.market_acc.probs_revealed,
             votes: ctx.accounts.market_acc.votes_revealed,
+            liability: ctx.accounts.market_acc.reserved_liability,
         });
 
         Ok(())
@@ -231,12 +731,18 @@ pub mod arx_predict {
         let current_timestamp = clock.unix_timestamp as u64;
         ctx.accounts.market_acc.probs_revealed = o.field_0;
         ctx.accounts.market_acc.votes_revealed = o.field_1;
+        ctx.accounts.market_acc.revealed_version = o.field_2;
         ctx.accounts.market_acc.updated_at = current_timestamp;
+        // Reveals are queued at most once per interval, so the reward is too
+        let reward = ctx.accounts.market_acc.take_reveal_reward();
+        ctx.accounts.user_account.credit(reward);
 
         emit!(RevealProbsEvent { 
             market_id: ctx.accounts.market_acc.id,
             probs: o.field_0,
             votes: o.field_1,
+            cranker: ctx.accounts.user_account.owner,
+            reward,
         });
 
         Ok(())
@@ -251,12 +757,19 @@ pub mod arx_predict {
             ComputationOutputs::Success(ClaimRewardsOutput { field_0 }) => field_0,
             _ => return Err(ErrorCode::AbortedComputation.into()),
         };
+        if !ctx.accounts.user_position_acc.is_pending_trade(o.field_2) {
+            return Ok(());
+        }
         let amount = o.field_1;
-        ctx.accounts.user_position_acc.balance += amount;
-        ctx.accounts.user_position_acc.shares = o.field_0.ciphertexts;  
-        ctx.accounts.user_position_acc.nonce = o.field_0.nonce;
-        
-        
+        ctx.accounts.user_position_acc.end_trade(0);
+        ctx.accounts.user_position_acc.claimed = true;
+        ctx.accounts.market_acc.unclaimed_positions = ctx.accounts.market_acc.unclaimed_positions.checked_sub(1).ok_or(ErrorCode::Overflow)?;
+        ctx.accounts.user_account.credit(amount);
+        ctx.accounts.user_position_acc.set_shares(&o.field_0.ciphertexts, o.field_0.nonce);
+        // The payout moves from the market's reserve to the user's account
+        ctx.accounts.market_acc.reserved_liability = ctx.accounts.market_acc.reserved_liability.saturating_sub(amount);
+        ctx.accounts.market_acc.tvl = ctx.accounts.market_acc.tvl.checked_sub(amount).ok_or(ErrorCode::Overflow)?;
+
         emit!(ClaimRewardsEvent {
             market_id: ctx.accounts.user_position_acc.market_id,
             amount: amount,
@@ -265,14 +778,147 @@ pub mod arx_predict {
         Ok(())
     }
 
+    #[arcium_callback(encrypted_ix = "claim_rewards_scalar")]
+    pub fn claim_rewards_scalar_callback(
+        ctx: Context<ClaimRewardsScalarCallback>,
+        output: ComputationOutputs<ClaimRewardsScalarOutput>,
+    ) -> Result<()> {
+        let o = match output {
+            ComputationOutputs::Success(ClaimRewardsScalarOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+        if !ctx.accounts.user_position_acc.is_pending_trade(o.field_2) {
+            return Ok(());
+        }
+        let amount = o.field_1;
+        ctx.accounts.user_position_acc.end_trade(0);
+        ctx.accounts.user_position_acc.claimed = true;
+        ctx.accounts.market_acc.unclaimed_positions = ctx.accounts.market_acc.unclaimed_positions.checked_sub(1).ok_or(ErrorCode::Overflow)?;
+        ctx.accounts.user_account.credit(amount);
+        ctx.accounts.user_position_acc.set_shares(&o.field_0.ciphertexts, o.field_0.nonce);
+        ctx.accounts.market_acc.reserved_liability = ctx.accounts.market_acc.reserved_liability.saturating_sub(amount);
+        ctx.accounts.market_acc.tvl = ctx.accounts.market_acc.tvl.checked_sub(amount).ok_or(ErrorCode::Overflow)?;
+
+        emit!(ClaimRewardsEvent {
+            market_id: ctx.accounts.user_position_acc.market_id,
+            amount: amount,
+        });
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "view_position")]
+    pub fn view_position_callback(
+        ctx: Context<ViewPositionCallback>,
+        output: ComputationOutputs<ViewPositionOutput>,
+    ) -> Result<()> {
+        let o = match output {
+            ComputationOutputs::Success(ViewPositionOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        emit!(PositionViewEvent {
+            market_id: ctx.accounts.user_position_acc.market_id,
+            user_position: ctx.accounts.user_position_acc.key(),
+            trade_seq: o.field_1,
+            encryption_key: o.field_0.encryption_key,
+            nonce: o.field_0.nonce,
+            shares: o.field_0.ciphertexts[0..MAX_OPTIONS].try_into().unwrap(),
+            cost_basis: o.field_0.ciphertexts[MAX_OPTIONS..2 * MAX_OPTIONS].try_into().unwrap(),
+        });
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "value_position")]
+    pub fn value_position_callback(
+        ctx: Context<ValuePositionCallback>,
+        output: ComputationOutputs<ValuePositionOutput>,
+    ) -> Result<()> {
+        let o = match output {
+            ComputationOutputs::Success(ValuePositionOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        emit!(PositionValueEvent {
+            market_id: ctx.accounts.user_position_acc.market_id,
+            user_position: ctx.accounts.user_position_acc.key(),
+            trade_seq: o.field_1,
+            encryption_key: o.field_0.encryption_key,
+            nonce: o.field_0.nonce,
+            value: o.field_0.ciphertexts[0],
+            cost_basis: o.field_0.ciphertexts[1],
+        });
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "quote_trade")]
+    pub fn quote_trade_callback(
+        ctx: Context<QuoteTradeCallback>,
+        output: ComputationOutputs<QuoteTradeOutput>,
+    ) -> Result<()> {
+        let o = match output {
+            ComputationOutputs::Success(QuoteTradeOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+
+        emit!(TradeQuoteEvent {
+            market_id: ctx.accounts.market_acc.id,
+            market_version: o.field_1,
+            encryption_key: o.field_0.encryption_key,
+            nonce: o.field_0.nonce,
+            buy_cost: o.field_0.ciphertexts[0],
+            sell_proceeds: o.field_0.ciphertexts[1],
+        });
+
+        Ok(())
+    }
+
+    #[arcium_callback(encrypted_ix = "claim_refund")]
+    pub fn claim_refund_callback(
+        ctx: Context<ClaimRefundCallback>,
+        output: ComputationOutputs<ClaimRefundOutput>,
+    ) -> Result<()> {
+        let o = match output {
+            ComputationOutputs::Success(ClaimRefundOutput { field_0 }) => field_0,
+            _ => return Err(ErrorCode::AbortedComputation.into()),
+        };
+        if !ctx.accounts.user_position_acc.is_pending_trade(o.field_2) {
+            return Ok(());
+        }
+        let amount = o.field_1;
+        ctx.accounts.user_position_acc.end_trade(0);
+        ctx.accounts.user_position_acc.claimed = true;
+        ctx.accounts.market_acc.unclaimed_positions = ctx.accounts.market_acc.unclaimed_positions.checked_sub(1).ok_or(ErrorCode::Overflow)?;
+        ctx.accounts.user_account.credit(amount);
+        ctx.accounts.user_position_acc.set_shares(&o.field_0.ciphertexts, o.field_0.nonce);
+        // Refunds are capped to refund_pool inside the circuit, which was the tvl when the market was voided
+        ctx.accounts.market_acc.tvl = ctx.accounts.market_acc.tvl.checked_sub(amount).ok_or(ErrorCode::Overflow)?;
+
+        emit!(ClaimRefundEvent {
+            market_id: ctx.accounts.user_position_acc.market_id,
+            amount,
+        });
+
+        Ok(())
+    }
+
     pub fn create_market(
         ctx: Context<CreateMarket>,
         computation_offset: u64,
         id: u32,
         question: String,
-        options: [String; MAX_OPTIONS],
+        options: Vec<String>,
         liquidity_parameter: u64,
         nonce: u128,
+        batch_epoch: i64,
+        trading_ends_at: i64,
+        resolve_by: i64,
+        resolution_source: ResolutionSource,
+        resolvers: Vec<Pubkey>,
+        scalar: Option<ScalarBounds>,
+        reveal: RevealConfig,
     ) -> Result<()> {
         ctx.accounts.create_market(
             id,
@@ -280,6 +926,13 @@ pub mod arx_predict {
             options,
             liquidity_parameter,
             nonce,
+            batch_epoch,
+            trading_ends_at,
+            resolve_by,
+            resolution_source,
+            resolvers,
+            scalar,
+            reveal,
             computation_offset,
             ctx.bumps.market_acc,
             ctx.bumps.sign_pda_account,
@@ -309,6 +962,7 @@ pub mod arx_predict {
         vote_encryption_pubkey: [u8; 32],
         vote_nonce: u128,
         shares: u64,
+        max_cost: u64,
     ) -> Result<()> {
         ctx.accounts.buy_shares(
             vote,
@@ -316,6 +970,47 @@ pub mod arx_predict {
             vote_nonce,
             computation_offset,
             shares,
+            max_cost,
+            ctx.bumps.sign_pda_account,
+        )
+    }
+
+    pub fn buy_with_amount(
+        ctx: Context<BuyWithAmount>,
+        computation_offset: u64,
+        _id: u32,
+        vote: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.buy_with_amount(
+            vote,
+            vote_encryption_pubkey,
+            vote_nonce,
+            computation_offset,
+            amount,
+            ctx.bumps.sign_pda_account,
+        )
+    }
+
+    pub fn buy_shares_private(
+        ctx: Context<BuySharesPrivate>,
+        computation_offset: u64,
+        _id: u32,
+        vote: [u8; 32],
+        shares: [u8; 32],
+        trade_encryption_pubkey: [u8; 32],
+        trade_nonce: u128,
+        max_cost: u64,
+    ) -> Result<()> {
+        ctx.accounts.buy_shares_private(
+            vote,
+            shares,
+            trade_encryption_pubkey,
+            trade_nonce,
+            computation_offset,
+            max_cost,
             ctx.bumps.sign_pda_account,
         )
     }
@@ -328,6 +1023,7 @@ pub mod arx_predict {
         vote_encryption_pubkey: [u8; 32],
         vote_nonce: u128,
         shares: u64,
+        min_proceeds: u64,
     ) -> Result<()> {
         ctx.accounts.sell_shares(
             vote,
@@ -335,10 +1031,169 @@ pub mod arx_predict {
             vote_nonce,
             computation_offset,
             shares,
+            min_proceeds,
+            ctx.bumps.sign_pda_account,
+        )
+    }
+
+    pub fn sell_shares_private(
+        ctx: Context<SellSharesPrivate>,
+        computation_offset: u64,
+        _id: u32,
+        vote: [u8; 32],
+        shares: [u8; 32],
+        trade_encryption_pubkey: [u8; 32],
+        trade_nonce: u128,
+        min_proceeds: u64,
+    ) -> Result<()> {
+        ctx.accounts.sell_shares_private(
+            vote,
+            shares,
+            trade_encryption_pubkey,
+            trade_nonce,
+            computation_offset,
+            min_proceeds,
+            ctx.bumps.sign_pda_account,
+        )
+    }
+
+    pub fn release_pending_trade(
+        ctx: Context<ReleasePendingTrade>,
+        _id: u32,
+    ) -> Result<()> {
+        ctx.accounts.release_pending_trade()
+    }
+
+    pub fn open_batch(
+        ctx: Context<OpenBatch>,
+        computation_offset: u64,
+        market_id: u32,
+        batch_id: u64,
+        nonce: u128,
+    ) -> Result<()> {
+        ctx.accounts.open_batch(
+            market_id,
+            batch_id,
+            nonce,
+            computation_offset,
+            ctx.bumps.batch_acc,
+            ctx.bumps.sign_pda_account,
+        )
+    }
+
+    pub fn commit_batch_trade(
+        ctx: Context<CommitBatchTrade>,
+        computation_offset: u64,
+        market_id: u32,
+        batch_id: u64,
+        vote: [u8; 32],
+        vote_encryption_pubkey: [u8; 32],
+        vote_nonce: u128,
+        amount: u64,
+    ) -> Result<()> {
+        ctx.accounts.commit_batch_trade(
+            market_id,
+            batch_id,
+            vote,
+            vote_encryption_pubkey,
+            vote_nonce,
+            amount,
+            computation_offset,
+            ctx.bumps.batch_ticket_acc,
+            ctx.bumps.sign_pda_account,
+        )
+    }
+
+    pub fn clear_batch(
+        ctx: Context<ClearBatch>,
+        computation_offset: u64,
+        _market_id: u32,
+        _batch_id: u64,
+    ) -> Result<()> {
+        ctx.accounts.clear_batch(computation_offset, ctx.bumps.sign_pda_account)
+    }
+
+    pub fn settle_batch_trade(
+        ctx: Context<SettleBatchTrade>,
+        computation_offset: u64,
+        _market_id: u32,
+        _batch_id: u64,
+    ) -> Result<()> {
+        ctx.accounts.settle_batch_trade(computation_offset, ctx.bumps.sign_pda_account)
+    }
+
+    pub fn refund_batch_trade(
+        ctx: Context<RefundBatchTrade>,
+        _market_id: u32,
+        _batch_id: u64,
+    ) -> Result<()> {
+        ctx.accounts.refund_batch_trade()
+    }
+
+    pub fn place_limit_order(
+        ctx: Context<PlaceLimitOrder>,
+        computation_offset: u64,
+        market_id: u32,
+        order_id: u64,
+        option: [u8; 32],
+        shares: [u8; 32],
+        limit_price: [u8; 32],
+        order_encryption_pubkey: [u8; 32],
+        order_nonce: u128,
+        amount: u64,
+        nonce: u128,
+    ) -> Result<()> {
+        ctx.accounts.place_limit_order(
+            market_id,
+            order_id,
+            option,
+            shares,
+            limit_price,
+            order_encryption_pubkey,
+            order_nonce,
+            amount,
+            nonce,
+            computation_offset,
+            ctx.bumps.limit_order_acc,
             ctx.bumps.sign_pda_account,
         )
     }
 
+    pub fn match_orders(
+        ctx: Context<MatchOrders>,
+        computation_offset: u64,
+        _market_id: u32,
+    ) -> Result<()> {
+        ctx.accounts.match_orders(computation_offset, ctx.bumps.sign_pda_account)
+    }
+
+    pub fn settle_limit_order(
+        ctx: Context<SettleLimitOrder>,
+        computation_offset: u64,
+        _market_id: u32,
+        _order_id: u64,
+    ) -> Result<()> {
+        ctx.accounts.settle_limit_order(computation_offset, ctx.bumps.sign_pda_account)
+    }
+
+    pub fn mint_complete_set(
+        ctx: Context<MintCompleteSet>,
+        computation_offset: u64,
+        _id: u32,
+        shares: u64,
+    ) -> Result<()> {
+        ctx.accounts.mint_complete_set(computation_offset, shares, ctx.bumps.sign_pda_account)
+    }
+
+    pub fn redeem_complete_set(
+        ctx: Context<RedeemCompleteSet>,
+        computation_offset: u64,
+        _id: u32,
+        shares: u64,
+    ) -> Result<()> {
+        ctx.accounts.redeem_complete_set(computation_offset, shares, ctx.bumps.sign_pda_account)
+    }
+
     // pub fn reveal_result(
     //     ctx: Context<RevealVotingResult>,
     //     computation_offset: u64,
@@ -352,23 +1207,21 @@ pub mod arx_predict {
         computation_offset: u64,
         id: u32,
     ) -> Result<()> {
-        ctx.accounts.reveal_probs(id, computation_offset)
+        ctx.accounts.reveal_probs(id, computation_offset, ctx.bumps.sign_pda_account)
     }
 
-    pub fn send_payment(
-        ctx: Context<SendPayment>,
-        _id: u32,
+    pub fn deposit(
+        ctx: Context<Deposit>,
         amount: u64,
     ) -> Result<()> {
-        ctx.accounts.send_payment(amount)
+        ctx.accounts.deposit(amount, ctx.bumps.user_account)
     }
 
-    pub fn withdraw_payment(
-        ctx: Context<WithdrawPayment>,
-        id: u32,
+    pub fn withdraw(
+        ctx: Context<Withdraw>,
         amount: u64,
     ) -> Result<()> {
-        ctx.accounts.withdraw_payment(amount, id, ctx.bumps.vault)
+        ctx.accounts.withdraw(amount, ctx.bumps.vault)
     }
 
     pub fn settle_market(
@@ -380,6 +1233,53 @@ pub mod arx_predict {
         ctx.accounts.settle_market(computation_offset, id, winner, ctx.bumps.sign_pda_account)
     }
 
+    pub fn settle_scalar_market(
+        ctx: Context<SettleMarket>,
+        computation_offset: u64,
+        _id: u32,
+        value: u64,
+    ) -> Result<()> {
+        ctx.accounts.settle_scalar_market(computation_offset, value, ctx.bumps.sign_pda_account)
+    }
+
+    pub fn submit_resolution(
+        ctx: Context<SubmitResolution>,
+        _id: u32,
+        outcome: u8,
+    ) -> Result<()> {
+        ctx.accounts.submit_resolution(outcome)
+    }
+
+    pub fn propose_outcome(
+        ctx: Context<ProposeOutcome>,
+        _id: u32,
+        outcome: u8,
+    ) -> Result<()> {
+        ctx.accounts.propose_outcome(outcome)
+    }
+
+    pub fn dispute_outcome(
+        ctx: Context<DisputeOutcome>,
+        _id: u32,
+    ) -> Result<()> {
+        ctx.accounts.dispute_outcome()
+    }
+
+    pub fn finalize_outcome(
+        ctx: Context<FinalizeOutcome>,
+        computation_offset: u64,
+        _id: u32,
+    ) -> Result<()> {
+        ctx.accounts.finalize_outcome(computation_offset, ctx.bumps.sign_pda_account)
+    }
+
+    pub fn expire_market(
+        ctx: Context<ExpireMarket>,
+        _id: u32,
+    ) -> Result<()> {
+        ctx.accounts.expire_market()
+    }
+
     pub fn claim_rewards(
         ctx: Context<ClaimRewards>,
         computation_offset: u64,
@@ -388,12 +1288,76 @@ pub mod arx_predict {
         ctx.accounts.claim_rewards(computation_offset, ctx.bumps.sign_pda_account)
     }
 
-    pub fn fund_market(
-        ctx: Context<FundMarket>,
+    pub fn view_position(
+        ctx: Context<ViewPosition>,
+        computation_offset: u64,
+        _id: u32,
+        owner_encryption_pubkey: [u8; 32],
+        owner_nonce: u128,
+    ) -> Result<()> {
+        ctx.accounts.view_position(
+            owner_encryption_pubkey,
+            owner_nonce,
+            computation_offset,
+            ctx.bumps.sign_pda_account,
+        )
+    }
+
+    pub fn value_position(
+        ctx: Context<ValuePosition>,
+        computation_offset: u64,
+        _id: u32,
+        owner_encryption_pubkey: [u8; 32],
+        owner_nonce: u128,
+    ) -> Result<()> {
+        ctx.accounts.value_position(
+            owner_encryption_pubkey,
+            owner_nonce,
+            computation_offset,
+            ctx.bumps.sign_pda_account,
+        )
+    }
+
+    pub fn quote_trade(
+        ctx: Context<QuoteTrade>,
+        computation_offset: u64,
+        _id: u32,
+        option: [u8; 32],
+        shares: [u8; 32],
+        trade_encryption_pubkey: [u8; 32],
+        trade_nonce: u128,
+    ) -> Result<()> {
+        ctx.accounts.quote_trade(
+            option,
+            shares,
+            trade_encryption_pubkey,
+            trade_nonce,
+            computation_offset,
+            ctx.bumps.sign_pda_account,
+        )
+    }
+
+    pub fn claim_rewards_scalar(
+        ctx: Context<ClaimRewardsScalar>,
+        computation_offset: u64,
+        _id: u32,
+    ) -> Result<()> {
+        ctx.accounts.claim_rewards_scalar(computation_offset, ctx.bumps.sign_pda_account)
+    }
+
+    pub fn cancel_market(
+        ctx: Context<CancelMarket>,
         _id: u32,
-        amount: u64,
     ) -> Result<()> {
-        ctx.accounts.fund_market(amount)
+        ctx.accounts.cancel_market()
+    }
+
+    pub fn claim_refund(
+        ctx: Context<ClaimRefund>,
+        computation_offset: u64,
+        _id: u32,
+    ) -> Result<()> {
+        ctx.accounts.claim_refund(computation_offset, ctx.bumps.sign_pda_account)
     }
 
     pub fn claim_market_funds(
@@ -403,4 +1367,33 @@ pub mod arx_predict {
         ctx.accounts.claim_market_funds(id, ctx.bumps.vault)
     }
 
+    pub fn claim_fees(
+        ctx: Context<ClaimFees>,
+        id: u32,
+        bucket: FeeBucket,
+    ) -> Result<()> {
+        ctx.accounts.claim_fees(id, bucket, ctx.bumps.vault)
+    }
+
+    pub fn claim_bond(
+        ctx: Context<ClaimBond>,
+        _id: u32,
+    ) -> Result<()> {
+        ctx.accounts.claim_bond()
+    }
+
+    pub fn close_user_position(
+        ctx: Context<CloseUserPosition>,
+        _id: u32,
+    ) -> Result<()> {
+        ctx.accounts.close_user_position()
+    }
+
+    pub fn close_market(
+        ctx: Context<CloseMarket>,
+        _id: u32,
+    ) -> Result<()> {
+        ctx.accounts.close_market(ctx.bumps.closed_market_acc)
+    }
+
 }
\ No newline at end of file
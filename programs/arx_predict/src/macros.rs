@@ -1,10 +1,10 @@
 #[macro_export]
 macro_rules! conditional_circuit_source {
-    ($circuit_name:expr) => {
-        if $crate::constants::IS_DEVNET {
+    ($config:expr, $circuit_name:expr) => {
+        if $config.offchain_circuits {
             Some(arcium_client::idl::arcium::types::CircuitSource::OffChain(
                 arcium_client::idl::arcium::types::OffChainCircuitSource {
-                    source: $circuit_name.to_string(),
+                    source: format!("{}{}", $config.circuit_base_url, $circuit_name),
                     hash: [0; 32], // Just use zeros for now - hash verification isn't enforced yet
                 }
             ))
@@ -16,30 +16,18 @@ macro_rules! conditional_circuit_source {
 
 #[macro_export]
 macro_rules! check_mint {
-    ($mint:expr) => {
-        if $crate::constants::IS_DEVNET {
-            require!($mint == $crate::constants::USDC_MINT, $crate::errors::ErrorCode::InvalidMint);
+    ($config:expr, $mint:expr) => {
+        if $config.check_mints {
+            require!($config.allowed_mints.contains(&$mint), $crate::errors::ErrorCode::InvalidMint);
         }
     };
 }
 
 #[macro_export]
 macro_rules! check_admin {
-    ($payer:expr) => {
-        if $crate::constants::IS_DEVNET {
-            require!($payer == $crate::constants::ADMIN_KEY, $crate::errors::ErrorCode::InvalidAuthority);
+    ($config:expr, $payer:expr) => {
+        if $config.check_admins {
+            require!($config.admins.contains(&$payer), $crate::errors::ErrorCode::InvalidAuthority);
         }
     };
 }
-
-#[macro_export]
-macro_rules! define_circuit_urls {
-    ($base_url:expr; $($name:ident: $file:expr),* $(,)?) => {
-        $(
-            pub const $name: &str = concat!(
-                $base_url,
-                $file
-            );
-        )*
-    };
-}
\ No newline at end of file
@@ -0,0 +1,135 @@
+use anchor_lang::prelude::*;
+use crate::{constants::*, errors::ErrorCode};
+
+/// Aggregate price read from a Pyth style price account
+#[derive(Debug)]
+pub struct PriceData {
+    pub price: i64,
+    pub expo: i32,
+    pub publish_time: i64,
+}
+
+fn read_bytes<const N: usize>(data: &[u8], offset: usize) -> [u8; N] {
+    data[offset..offset + N].try_into().unwrap()
+}
+
+/// Parses the aggregate price, feeds that aren't trading can't settle a market
+pub fn read_price(data: &[u8]) -> Result<PriceData> {
+    require!(data.len() >= PRICE_ACCOUNT_MIN_LENGTH, ErrorCode::InvalidPriceFeed);
+    require!(
+        u32::from_le_bytes(read_bytes(data, PRICE_ACCOUNT_MAGIC_OFFSET)) == PRICE_ACCOUNT_MAGIC
+            && u32::from_le_bytes(read_bytes(data, PRICE_ACCOUNT_TYPE_OFFSET)) == PRICE_ACCOUNT_TYPE,
+        ErrorCode::InvalidPriceFeed
+    );
+    require!(
+        u32::from_le_bytes(read_bytes(data, PRICE_ACCOUNT_AGG_STATUS_OFFSET)) == PRICE_STATUS_TRADING,
+        ErrorCode::PriceUnavailable
+    );
+    Ok(PriceData {
+        price: i64::from_le_bytes(read_bytes(data, PRICE_ACCOUNT_AGG_PRICE_OFFSET)),
+        expo: i32::from_le_bytes(read_bytes(data, PRICE_ACCOUNT_EXPO_OFFSET)),
+        publish_time: i64::from_le_bytes(read_bytes(data, PRICE_ACCOUNT_TIMESTAMP_OFFSET)),
+    })
+}
+
+/// Price a market settles on, the feed must use the market's exponent and have published within
+/// MAX_SETTLEMENT_DELAY seconds after `trading_ends_at`
+pub fn settlement_price(data: &[u8], expo: i32, trading_ends_at: i64) -> Result<i64> {
+    let price = read_price(data)?;
+    require!(price.expo == expo, ErrorCode::InvalidPriceFeed);
+    require!(price.publish_time >= trading_ends_at, ErrorCode::PriceUnavailable);
+    // Anyone can settle, without a bound they could wait for whichever later price suits them
+    require!(
+        price.publish_time <= trading_ends_at + MAX_SETTLEMENT_DELAY,
+        ErrorCode::SettlementWindowClosed
+    );
+    Ok(price.price)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PRICE: i64 = 6_500_000_000_000;
+    const EXPO: i32 = -8;
+    const PUBLISH_TIME: i64 = 1_700_000_000;
+
+    fn error(code: ErrorCode) -> anchor_lang::error::Error {
+        code.into()
+    }
+
+    fn price_account(status: u32) -> Vec<u8> {
+        let mut data = vec![0u8; PRICE_ACCOUNT_MIN_LENGTH];
+        let mut write = |offset: usize, bytes: &[u8]| data[offset..offset + bytes.len()].copy_from_slice(bytes);
+        write(PRICE_ACCOUNT_MAGIC_OFFSET, &PRICE_ACCOUNT_MAGIC.to_le_bytes());
+        write(PRICE_ACCOUNT_TYPE_OFFSET, &PRICE_ACCOUNT_TYPE.to_le_bytes());
+        write(PRICE_ACCOUNT_EXPO_OFFSET, &EXPO.to_le_bytes());
+        write(PRICE_ACCOUNT_TIMESTAMP_OFFSET, &PUBLISH_TIME.to_le_bytes());
+        write(PRICE_ACCOUNT_AGG_PRICE_OFFSET, &PRICE.to_le_bytes());
+        write(PRICE_ACCOUNT_AGG_STATUS_OFFSET, &status.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn read_price_parses_the_aggregate_price() {
+        let price = read_price(&price_account(PRICE_STATUS_TRADING)).unwrap();
+        assert_eq!(price.price, PRICE);
+        assert_eq!(price.expo, EXPO);
+        assert_eq!(price.publish_time, PUBLISH_TIME);
+    }
+
+    #[test]
+    fn read_price_rejects_other_accounts() {
+        let data = price_account(PRICE_STATUS_TRADING);
+        assert_eq!(
+            read_price(&data[..PRICE_ACCOUNT_MIN_LENGTH - 1]).unwrap_err(),
+            error(ErrorCode::InvalidPriceFeed)
+        );
+        let mut wrong_magic = data.clone();
+        wrong_magic[PRICE_ACCOUNT_MAGIC_OFFSET] ^= 1;
+        assert_eq!(read_price(&wrong_magic).unwrap_err(), error(ErrorCode::InvalidPriceFeed));
+        let mut wrong_type = data;
+        wrong_type[PRICE_ACCOUNT_TYPE_OFFSET] ^= 1;
+        assert_eq!(read_price(&wrong_type).unwrap_err(), error(ErrorCode::InvalidPriceFeed));
+    }
+
+    #[test]
+    fn read_price_rejects_feeds_that_arent_trading() {
+        assert_eq!(
+            read_price(&price_account(PRICE_STATUS_TRADING + 1)).unwrap_err(),
+            error(ErrorCode::PriceUnavailable)
+        );
+    }
+
+    #[test]
+    fn settlement_price_rejects_stale_prices() {
+        let data = price_account(PRICE_STATUS_TRADING);
+        assert_eq!(settlement_price(&data, EXPO, PUBLISH_TIME).unwrap(), PRICE);
+        assert_eq!(
+            settlement_price(&data, EXPO, PUBLISH_TIME + 1).unwrap_err(),
+            error(ErrorCode::PriceUnavailable)
+        );
+    }
+
+    #[test]
+    fn settlement_price_rejects_prices_after_the_window() {
+        let data = price_account(PRICE_STATUS_TRADING);
+        assert_eq!(
+            settlement_price(&data, EXPO, PUBLISH_TIME - MAX_SETTLEMENT_DELAY).unwrap(),
+            PRICE
+        );
+        assert_eq!(
+            settlement_price(&data, EXPO, PUBLISH_TIME - MAX_SETTLEMENT_DELAY - 1).unwrap_err(),
+            error(ErrorCode::SettlementWindowClosed)
+        );
+    }
+
+    #[test]
+    fn settlement_price_rejects_another_exponent() {
+        let data = price_account(PRICE_STATUS_TRADING);
+        assert_eq!(
+            settlement_price(&data, EXPO - 1, PUBLISH_TIME).unwrap_err(),
+            error(ErrorCode::InvalidPriceFeed)
+        );
+    }
+}
@@ -1,5 +1,61 @@
 use anchor_lang::prelude::*;
 use crate::constants::*;
+use crate::errors::ErrorCode;
+
+#[account]
+#[derive(InitSpace)]
+pub struct Config {
+    pub bump: u8,
+    pub authority: Pubkey, // updates the config
+    pub pending_authority: Option<Pubkey>, // must accept before it becomes the authority
+    #[max_len(MAX_ADMINS)]
+    pub admins: Vec<Pubkey>, // allowed to create and fund markets
+    #[max_len(MAX_ALLOWED_MINTS)]
+    pub allowed_mints: Vec<Pubkey>,
+    #[max_len(MAX_CIRCUIT_URL_LENGTH)]
+    pub circuit_base_url: String,
+    pub check_admins: bool,
+    pub check_mints: bool,
+    pub offchain_circuits: bool, // comp defs point at circuit_base_url instead of circuits uploaded on chain
+    pub protocol_fee_bps: u16, // fee rates are copied into each market when it is created
+    pub creator_fee_bps: u16,
+    pub lp_fee_bps: u16,
+}
+
+impl Config {
+    pub fn set_params(&mut self, params: ConfigParams) -> Result<()> {
+        require!(params.admins.len() <= MAX_ADMINS, ErrorCode::InvalidConfig);
+        require!(params.allowed_mints.len() <= MAX_ALLOWED_MINTS, ErrorCode::InvalidConfig);
+        require!(params.circuit_base_url.len() <= MAX_CIRCUIT_URL_LENGTH, ErrorCode::InvalidConfig);
+        require!(
+            params.protocol_fee_bps as u64 + params.creator_fee_bps as u64 + params.lp_fee_bps as u64 <= MAX_FEE_BPS,
+            ErrorCode::InvalidFee
+        );
+        self.admins = params.admins;
+        self.allowed_mints = params.allowed_mints;
+        self.circuit_base_url = params.circuit_base_url;
+        self.check_admins = params.check_admins;
+        self.check_mints = params.check_mints;
+        self.offchain_circuits = params.offchain_circuits;
+        self.protocol_fee_bps = params.protocol_fee_bps;
+        self.creator_fee_bps = params.creator_fee_bps;
+        self.lp_fee_bps = params.lp_fee_bps;
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ConfigParams {
+    pub admins: Vec<Pubkey>,
+    pub allowed_mints: Vec<Pubkey>,
+    pub circuit_base_url: String,
+    pub check_admins: bool,
+    pub check_mints: bool,
+    pub offchain_circuits: bool,
+    pub protocol_fee_bps: u16,
+    pub creator_fee_bps: u16,
+    pub lp_fee_bps: u16,
+}
 
 #[account]
 #[derive(InitSpace)]
@@ -9,21 +65,312 @@ pub struct MarketAccount {
     pub vote_state: [[u8; 32]; MAX_OPTIONS],
     pub probs: [[u8; 32]; MAX_OPTIONS],
     pub cost: [u8; 32],
+    pub total_cost_basis: [u8; 32],
     pub liquidity_parameter: u64,
     pub id: u32,
     pub authority: Pubkey,
     #[max_len(MAX_QUESTION_LENGTH)]
     pub question: String,
-    #[max_len(MAX_OPTION_LENGTH)]
-    pub options: [String; MAX_OPTIONS],
-    pub probs_revealed: [f64; MAX_OPTIONS],
+    #[max_len(MAX_OPTIONS, MAX_OPTION_LENGTH)]
+    pub options: Vec<String>,
+    pub num_options: u8,
+    pub probs_revealed: [u64; MAX_OPTIONS], // fixed point, 12 decimals
     pub updated_at: u64,
+    pub reveal: RevealConfig, // what reveal_probs exposes, how often and what the cranker is paid
+    pub last_reveal_at: i64, // when the last reveal was queued, the interval counts from here so one pending reveal is paid once
+    pub revealed_version: u64, // state_version probs_revealed and votes_revealed were computed at, each version is revealed once
     pub winning_outcome: u8,
     pub status: MarketStatus,
     pub tvl: u64,
     pub mint: Pubkey,
     pub mint_decimals: u8,
     pub votes_revealed: [u64; MAX_OPTIONS],
+    pub batch_mode: bool, // buys go through batches instead of trading one by one
+    pub batch_epoch: i64, // seconds each batch takes commits, batch n covers the n-th epoch since batch_epoch_start
+    pub batch_epoch_start: i64,
+    pub state_version: u64, // bumped every time the encrypted market stats are written
+    pub trading_ends_at: i64,
+    pub resolve_by: i64, // anyone can expire the market once this passes unsettled
+    pub refund_pool: u64, // tvl when the market was voided, cost basis refunds are scaled to it
+    pub liquidity_provider: Pubkey, // funded the market maker, collects the lp fees
+    pub protocol_fee_bps: u16,
+    pub creator_fee_bps: u16,
+    pub lp_fee_bps: u16,
+    // Accrued fees, held in the mint's vault next to tvl until claimed
+    pub protocol_fees: u64,
+    pub creator_fees: u64,
+    pub lp_fees: u64,
+    pub reserved_liability: u64, // winning payouts not claimed yet, set at settlement and part of tvl
+    pub complete_sets: u64, // shares of every outcome minted as complete sets and not redeemed, outside the LMSR stats
+    pub set_collateral: u64, // collateral taken for complete_sets, part of tvl and the bound on what redeems pay out
+    pub unclaimed_positions: u32, // positions that haven't claimed rewards or a refund yet
+    pub open_orders: u32, // limit orders placed and not settled, their escrow is outside tvl
+    pub open_tickets: u32, // batch tickets not settled or refunded yet
+    pub finalized_at: i64, // when the market was settled or voided
+    pub last_claim_at: i64, // a claim queued after this may still call back
+    pub resolution_source: ResolutionSource,
+    #[max_len(MAX_RESOLVERS)]
+    pub resolvers: Vec<Pubkey>,
+    pub resolver_votes: [u8; MAX_RESOLVERS], // outcome each resolver voted for, NO_RESOLVER_VOTE until it votes
+    // Optimistic resolution, bonds are debited from the proposer's and disputer's accounts and held until claim_bond
+    pub proposer: Pubkey,
+    pub proposed_outcome: u8,
+    pub proposed_at: i64,
+    pub proposer_bond: u64,
+    pub disputer: Pubkey,
+    pub disputer_bond: u64,
+    pub ruled_outcome: u8, // outcome a dispute was decided for, NO_RESOLVER_VOTE until then
+    pub scalar: Option<ScalarBounds>, // scalar markets trade LONG (option 0) and SHORT (option 1) on a value between the bounds
+    pub resolved_value: u64,
+    pub long_payout: u64, // SCALAR_PAYOUT_ONE scaled payout of a LONG share, SHORT shares get the rest
+}
+
+impl MarketAccount {
+    /// Stores an `Enc<Mxe, MarketStats>` output: vote stats, probs and cost in that order
+    pub fn set_market_stats(&mut self, ciphertexts: &[[u8; 32]], nonce: u128) {
+        self.vote_state = ciphertexts[0..MAX_OPTIONS].try_into().unwrap();
+        self.probs = ciphertexts[MAX_OPTIONS..2 * MAX_OPTIONS].try_into().unwrap();
+        self.cost = ciphertexts[2 * MAX_OPTIONS];
+        self.total_cost_basis = ciphertexts[2 * MAX_OPTIONS + 1];
+        self.nonce = nonce;
+        self.state_version += 1;
+    }
+
+    pub fn fee_bps(&self) -> u64 {
+        self.protocol_fee_bps as u64 + self.creator_fee_bps as u64 + self.lp_fee_bps as u64
+    }
+
+    /// Fee on a trade amount, rounded up
+    pub fn trade_fee(&self, amount: u64) -> u64 {
+        ((amount as u128 * self.fee_bps() as u128).div_ceil(BPS_DENOMINATOR as u128)) as u64
+    }
+
+    /// Largest trade amount that still fits in `funds` once its fee is added
+    pub fn cost_before_fee(&self, funds: u64) -> u64 {
+        (funds as u128 * BPS_DENOMINATOR as u128 / (BPS_DENOMINATOR + self.fee_bps()) as u128) as u64
+    }
+
+    /// Smallest trade amount that leaves at least `proceeds` once its fee is taken
+    pub fn proceeds_before_fee(&self, proceeds: u64) -> u64 {
+        let gross = (proceeds as u128 * BPS_DENOMINATOR as u128).div_ceil((BPS_DENOMINATOR - self.fee_bps()) as u128);
+        u64::try_from(gross).unwrap_or(u64::MAX)
+    }
+
+    /// Splits a fee between the buckets by their rates, rounding leftovers go to the lp.
+    /// Returns the protocol, creator and lp parts
+    pub fn accrue_fees(&mut self, fee: u64) -> (u64, u64, u64) {
+        let fee_bps = self.fee_bps();
+        if fee == 0 || fee_bps == 0 {
+            return (0, 0, 0);
+        }
+        let protocol = (fee as u128 * self.protocol_fee_bps as u128 / fee_bps as u128) as u64;
+        let creator = (fee as u128 * self.creator_fee_bps as u128 / fee_bps as u128) as u64;
+        let lp = fee - protocol - creator;
+        self.protocol_fees += protocol;
+        self.creator_fees += creator;
+        self.lp_fees += lp;
+        (protocol, creator, lp)
+    }
+
+    /// Batch taking commits at `now`, commits are grouped by epoch so trades within one can't be told apart by their timing
+    pub fn batch_at(&self, now: i64) -> u64 {
+        ((now - self.batch_epoch_start) / self.batch_epoch) as u64
+    }
+
+    /// When the batch stops taking commits and can be cleared
+    pub fn batch_ends_at(&self, batch_id: u64) -> i64 {
+        self.batch_epoch_start + (batch_id as i64 + 1) * self.batch_epoch
+    }
+
+    /// Buys are only accepted before trading_ends_at
+    pub fn is_trading(&self, now: i64) -> bool {
+        self.status == MarketStatus::Active && now < self.trading_ends_at
+    }
+
+    /// Unsettled past resolve_by, sells stay open so holders can exit
+    pub fn is_expired(&self, now: i64) -> bool {
+        match self.status {
+            MarketStatus::Expired => true,
+            MarketStatus::Active | MarketStatus::Closed | MarketStatus::Settling => now > self.resolve_by,
+            _ => false,
+        }
+    }
+
+    /// Sets redeem until settlement is queued, then claim_rewards pays them as winning shares and
+    /// claim_refund returns their collateral through the cost basis they were minted with
+    pub fn can_redeem_sets(&self) -> bool {
+        !self.is_finalized() && self.status != MarketStatus::Settling
+    }
+
+    /// Takes redeemed sets and the collateral they pay out off the outstanding ones
+    pub fn redeem_sets(&mut self, shares: u64, amount: u64) -> Result<()> {
+        self.complete_sets = self.complete_sets.checked_sub(shares).ok_or(ErrorCode::InsufficientSets)?;
+        self.set_collateral = self.set_collateral.checked_sub(amount).ok_or(ErrorCode::InsufficientSets)?;
+        self.tvl = self.tvl.checked_sub(amount).ok_or(ErrorCode::InsufficientSets)?;
+        Ok(())
+    }
+
+    /// Validates and stores the reveal policy
+    pub fn set_reveal(&mut self, reveal: RevealConfig) -> Result<()> {
+        let valid_policy = match reveal.policy {
+            RevealPolicy::ProbsOnly => true,
+            RevealPolicy::BucketedVolume { bucket } => bucket > 0,
+            RevealPolicy::NoisyVolume { scale } => scale > 0,
+        };
+        require!(
+            valid_policy
+                && reveal.min_interval >= MARKET_REVEAL_PROBS_TIME
+                && (!reveal.hide_votes || reveal.policy == RevealPolicy::ProbsOnly),
+            ErrorCode::InvalidRevealConfig
+        );
+        self.reveal = reveal;
+        self.last_reveal_at = 0;
+        Ok(())
+    }
+
+    /// Reveals are rate limited per market, and stats already revealed aren't revealed again so noisy volume
+    /// can't be averaged out by revealing the same version repeatedly
+    pub fn can_reveal(&self, now: i64) -> bool {
+        now - self.last_reveal_at >= self.reveal.min_interval && self.state_version != self.revealed_version
+    }
+
+    /// Takes the cranker reward out of the creator fees, capped by what has accrued
+    pub fn take_reveal_reward(&mut self) -> u64 {
+        let reward = self.reveal.reward.min(self.creator_fees);
+        self.creator_fees -= reward;
+        reward
+    }
+
+    /// Validates and stores the bounds of a scalar market, its options are LONG and SHORT
+    pub fn set_scalar(&mut self, scalar: Option<ScalarBounds>) -> Result<()> {
+        if let Some(bounds) = &scalar {
+            require!(
+                self.num_options == 2 && bounds.lower_bound < bounds.upper_bound,
+                ErrorCode::InvalidScalarBounds
+            );
+        }
+        self.scalar = scalar;
+        self.resolved_value = 0;
+        self.long_payout = 0;
+        Ok(())
+    }
+
+    /// Records the resolution value of a scalar market, a LONG share pays (v - lo) / (hi - lo) with v clamped to the bounds.
+    /// Returns the outcome paid the most, kept as the winning outcome
+    pub fn set_resolved_value(&mut self, value: u64) -> Result<u8> {
+        let bounds = self.scalar.clone().ok_or(ErrorCode::NotScalarMarket)?;
+        let clamped = value.clamp(bounds.lower_bound, bounds.upper_bound);
+        self.resolved_value = value;
+        self.long_payout = ((clamped - bounds.lower_bound) as u128 * SCALAR_PAYOUT_ONE as u128
+            / (bounds.upper_bound - bounds.lower_bound) as u128) as u64;
+        Ok(if self.long_payout * 2 >= SCALAR_PAYOUT_ONE { 0 } else { 1 })
+    }
+
+    /// Validates and stores how the winner will be decided
+    pub fn set_resolution(&mut self, source: ResolutionSource, resolvers: Vec<Pubkey>) -> Result<()> {
+        let unique = resolvers.iter().enumerate().all(|(i, resolver)| !resolvers[..i].contains(resolver));
+        // A strict majority, two outcomes can't both reach it
+        let valid_committee = |threshold: u8| {
+            resolvers.len() <= MAX_RESOLVERS
+                && unique
+                && threshold as usize <= resolvers.len()
+                && threshold as usize * 2 > resolvers.len()
+        };
+        let valid = match source {
+            // Scalar markets need a value, only the authority and price feeds give one
+            _ if self.scalar.is_some() => {
+                resolvers.is_empty() && matches!(source, ResolutionSource::Authority | ResolutionSource::PriceFeed { .. })
+            }
+            ResolutionSource::Authority => resolvers.is_empty(),
+            ResolutionSource::PriceFeed { .. } => resolvers.is_empty() && self.num_options == 2,
+            ResolutionSource::Multisig { threshold } => valid_committee(threshold),
+            // Without resolvers disputes go to the config authority
+            ResolutionSource::Optimistic { bond, dispute_window, threshold } => {
+                bond > 0
+                    && dispute_window > 0
+                    && ((resolvers.is_empty() && threshold == 0) || valid_committee(threshold))
+            }
+        };
+        require!(valid, ErrorCode::InvalidResolutionSource);
+        self.resolution_source = source;
+        self.resolvers = resolvers;
+        self.resolver_votes = [NO_RESOLVER_VOTE; MAX_RESOLVERS];
+        self.proposer = Pubkey::default();
+        self.proposed_outcome = NO_RESOLVER_VOTE;
+        self.proposed_at = 0;
+        self.proposer_bond = 0;
+        self.disputer = Pubkey::default();
+        self.disputer_bond = 0;
+        self.ruled_outcome = NO_RESOLVER_VOTE;
+        Ok(())
+    }
+
+    /// The outcome at least `threshold` resolvers voted for
+    pub fn multisig_outcome(&self, threshold: u8) -> Option<u8> {
+        (0..self.num_options).find(|&outcome| {
+            self.resolver_votes.iter().filter(|&&vote| vote == outcome).count() >= threshold as usize
+        })
+    }
+
+    /// Clears and returns the bonds owed to `claimant` once the market is finalized. The side the settled outcome
+    /// agrees with takes both bonds, a voided market returns each bond to whoever posted it
+    pub fn take_bonds(&mut self, claimant: Pubkey) -> u64 {
+        let mut payout = 0;
+        if self.status == MarketStatus::Settled {
+            let upheld = self.winning_outcome == self.proposed_outcome;
+            if (upheld && claimant == self.proposer) || (!upheld && claimant == self.disputer) {
+                payout = self.proposer_bond + self.disputer_bond;
+                self.proposer_bond = 0;
+                self.disputer_bond = 0;
+            }
+        } else if self.status == MarketStatus::Voided {
+            if claimant == self.proposer {
+                payout += self.proposer_bond;
+                self.proposer_bond = 0;
+            }
+            if claimant == self.disputer {
+                payout += self.disputer_bond;
+                self.disputer_bond = 0;
+            }
+        }
+        payout
+    }
+
+    /// Locks the outcome a queued settlement reveals, retrying a stale reveal settles on the same one
+    pub fn begin_settlement(&mut self, winner: u8) {
+        self.status = MarketStatus::Settling;
+        self.winning_outcome = winner;
+    }
+
+    /// Settled and voided markets only take claims
+    pub fn is_finalized(&self) -> bool {
+        matches!(self.status, MarketStatus::Settled | MarketStatus::Voided)
+    }
+
+    /// Voids the market, what the vault holds for it becomes the pool cost basis refunds are paid from
+    pub fn void(&mut self, now: i64) {
+        self.status = MarketStatus::Voided;
+        self.finalized_at = now;
+        self.refund_pool = self.tvl;
+        // Trades and batch clearings still queued come back stale and are refunded
+        self.state_version += 1;
+    }
+
+    /// Moves the stored status along the timeline, Active -> Closed -> Expired
+    pub fn refresh_status(&mut self, now: i64) {
+        if self.is_expired(now) {
+            self.status = MarketStatus::Expired;
+        } else if self.status == MarketStatus::Active && now >= self.trading_ends_at {
+            self.status = MarketStatus::Closed;
+        }
+    }
+}
+
+/// Left behind by close_market so the id is never reused, positions of the closed market would count in a new one
+#[account]
+#[derive(InitSpace)]
+pub struct ClosedMarket {
+    pub bump: u8,
 }
 
 #[account]
@@ -32,8 +379,119 @@ pub struct UserPosition {
     pub bump: u8,
     pub nonce: u128,
     pub shares: [[u8; 32]; MAX_OPTIONS],
-    pub balance: u64,    
+    pub cost_basis: [[u8; 32]; MAX_OPTIONS],
+    pub market_id: u32,
+    pub mint: Pubkey, // the market's mint, escrow is released to the owner's user account for it even once the market is closed
+    pub trade_seq: u64, // bumped for every queued computation that rewrites the position
+    pub pending: bool,
+    pub locked_amount: u64, // escrowed from the user account while a buy is pending
+    pub pending_since: i64,
+    pub claimed: bool, // rewards or refund collected, the position can only be closed after this
+    pub minted_sets: u64, // complete sets this position minted and hasn't redeemed, the most it can redeem
+}
+
+impl UserPosition {
+    /// Records `locked_amount`, already debited from the user account, as the escrow of a queued computation and
+    /// returns the sequence its callback must echo back. Only one computation per position is in flight, so its
+    /// result always applies to the current shares
+    pub fn begin_trade(&mut self, locked_amount: u64, now: i64) -> Result<u64> {
+        require!(!self.pending, ErrorCode::ComputationPending);
+        self.locked_amount = locked_amount;
+        self.pending = true;
+        self.pending_since = now;
+        self.trade_seq += 1;
+        Ok(self.trade_seq)
+    }
+
+    /// False once the pending computation was released, its late result must be dropped
+    pub fn is_pending_trade(&self, trade_seq: u64) -> bool {
+        self.pending && self.trade_seq == trade_seq
+    }
+
+    /// Clears the pending trade and returns the unspent part of the escrow, owed back to the user account
+    pub fn end_trade(&mut self, spent: u64) -> u64 {
+        let refund = self.locked_amount - spent;
+        self.locked_amount = 0;
+        self.pending = false;
+        refund
+    }
+
+    /// Stores an `Enc<Mxe, UserPosition>` output: shares and cost basis in that order
+    pub fn set_shares(&mut self, ciphertexts: &[[u8; 32]], nonce: u128) {
+        self.shares = ciphertexts[0..MAX_OPTIONS].try_into().unwrap();
+        self.cost_basis = ciphertexts[MAX_OPTIONS..2 * MAX_OPTIONS].try_into().unwrap();
+        self.nonce = nonce;
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct UserAccount {
+    pub bump: u8,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub balance: u64, // deposited into the mint's vault, shared by every market trading that mint
+}
+
+impl UserAccount {
+    pub fn debit(&mut self, amount: u64) -> Result<()> {
+        require!(self.balance >= amount, ErrorCode::InsufficientBalance);
+        self.balance -= amount;
+        Ok(())
+    }
+
+    pub fn credit(&mut self, amount: u64) {
+        self.balance += amount;
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct BatchAccount {
+    pub bump: u8,
+    pub nonce: u128,
+    pub budgets: [[u8; 32]; MAX_OPTIONS],
+    pub shares: [[u8; 32]; MAX_OPTIONS],
     pub market_id: u32,
+    pub batch_id: u64,
+    pub deposits: u64, // sum of committed budgets, public
+    pub fees: u64, // fees paid with the commits, accrued to the market once the batch clears
+    pub num_trades: u32,
+    pub status: BatchStatus,
+    pub pending: bool, // a commit is being applied to the encrypted totals
+    pub ends_at: i64, // commits are taken until the batch's epoch ends, it can only be cleared after
+    pub clearing_since: i64, // a clearing that never called back fails the batch after PENDING_TRADE_TIMEOUT
+}
+
+impl BatchAccount {
+    /// Stores an `Enc<Mxe, BatchStats>` output: budgets and shares in that order
+    pub fn set_batch_stats(&mut self, ciphertexts: &[[u8; 32]], nonce: u128) {
+        self.budgets = ciphertexts[0..MAX_OPTIONS].try_into().unwrap();
+        self.shares = ciphertexts[MAX_OPTIONS..2 * MAX_OPTIONS].try_into().unwrap();
+        self.nonce = nonce;
+    }
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct BatchTicket {
+    pub bump: u8,
+    pub owner: Pubkey,
+    pub market_id: u32,
+    pub batch_id: u64,
+    pub vote: [u8; 32], // Enc<Shared, UserVote>, reused when the ticket is settled
+    pub vote_encryption_pubkey: [u8; 32],
+    pub vote_nonce: u128,
+    pub amount: u64, // budget spent in the batch, the fee is paid on top
+    pub fee: u64,
+    pub settled: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum FeeBucket {
+    Protocol, // claimed by the config authority
+    Creator, // claimed by the market authority
+    Lp, // claimed by the market's liquidity provider
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
@@ -41,4 +499,369 @@ pub enum MarketStatus {
     Inactive,
     Active,
     Settled,
-}
\ No newline at end of file
+    Closed, // past trading_ends_at, waiting for settlement
+    Expired, // missed resolve_by
+    Voided, // cancelled by the authority, positions are refunded their cost basis
+    Proposed, // optimistic markets, an outcome was proposed and can be disputed until the window ends
+    Disputed, // the proposal was disputed, waiting for the config authority or the resolvers to rule
+    Settling, // a settlement was queued, its winner and scalar payout are locked until the reveal lands
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum ResolutionSource {
+    Authority, // the market authority names the winner
+    PriceFeed { feed: Pubkey, threshold: i64, expo: i32 }, // binary markets, option 0 wins when the price is at or above threshold
+    Multisig { threshold: u8 }, // the outcome `threshold` of the market's resolvers voted for
+    Optimistic { bond: u64, dispute_window: i64, threshold: u8 }, // a bonded proposal stands unless disputed, disputes go to the resolvers or the config authority
+}
+
+// Volume revealed next to the probs, the exact vote stats never leave the MPC
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum RevealPolicy {
+    ProbsOnly,
+    BucketedVolume { bucket: u64 }, // rounded down to a multiple of bucket shares
+    NoisyVolume { scale: u64 }, // plus discrete Laplace noise drawn inside the MPC, epsilon = 1 per `scale` shares of volume
+}
+
+impl RevealPolicy {
+    /// Policy id and its parameter as passed to the reveal_probs circuit
+    pub fn circuit_args(&self) -> (u8, u64) {
+        match self {
+            RevealPolicy::ProbsOnly => (REVEAL_POLICY_PROBS_ONLY, 0),
+            RevealPolicy::BucketedVolume { bucket } => (REVEAL_POLICY_BUCKETED_VOLUME, *bucket),
+            RevealPolicy::NoisyVolume { scale } => (REVEAL_POLICY_NOISY_VOLUME, *scale),
+        }
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct RevealConfig {
+    pub policy: RevealPolicy,
+    pub min_interval: i64, // seconds between reveals, at least MARKET_REVEAL_PROBS_TIME
+    pub reward: u64, // paid to whoever queued a reveal once it lands, out of the creator fees
+    // Vote totals stay encrypted until settlement reveals them next to the payout owed. Needs ProbsOnly
+    pub hide_votes: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub struct ScalarBounds {
+    pub lower_bound: u64,
+    pub upper_bound: u64,
+}
+
+#[account]
+#[derive(InitSpace)]
+pub struct LimitOrder {
+    pub bump: u8,
+    pub nonce: u128,
+    pub order: [[u8; 32]; LIMIT_ORDER_FIELDS], // Enc<Mxe, LimitOrder>, side, size and limit price stay private
+    pub owner: Pubkey,
+    pub market_id: u32,
+    pub order_id: u64,
+    pub escrow: u64, // debited from the user account when placed, the fill's cost and fee come out of it
+    pub status: OrderStatus,
+    pub pending_since: i64, // when the computation the status waits on was queued
+    pub match_version: u64, // market state_version the pending match was queued at
+}
+
+impl LimitOrder {
+    /// Stores an `Enc<Mxe, LimitOrder>` output
+    pub fn set_order(&mut self, ciphertexts: &[[u8; 32]], nonce: u128) {
+        self.order = ciphertexts[0..LIMIT_ORDER_FIELDS].try_into().unwrap();
+        self.nonce = nonce;
+    }
+
+    /// Moves the order to a status that waits on a queued computation
+    pub fn begin(&mut self, status: OrderStatus, now: i64) {
+        self.status = status;
+        self.pending_since = now;
+    }
+
+    /// A computation that never called back stops blocking the order after PENDING_TRADE_TIMEOUT, its late
+    /// result is dropped
+    pub fn is_stuck(&self, now: i64) -> bool {
+        matches!(self.status, OrderStatus::Placing | OrderStatus::Matching | OrderStatus::Settling)
+            && now >= self.pending_since + PENDING_TRADE_TIMEOUT
+    }
+
+    /// True while the match queued at `market_version` is still the one the order waits on
+    pub fn is_matching(&self, market_version: u64) -> bool {
+        self.status == OrderStatus::Matching && self.match_version == market_version
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum OrderStatus {
+    Placing,
+    Open,
+    Matching,
+    Settling,
+    Settled,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq, InitSpace)]
+pub enum BatchStatus {
+    Inactive,
+    Open,
+    Clearing,
+    Cleared,
+    Failed,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRADING_ENDS_AT: i64 = 1_000;
+    const RESOLVE_BY: i64 = 2_000;
+
+    /// Active two-outcome market trading until TRADING_ENDS_AT, without fees
+    struct MarketBuilder {
+        market_acc: MarketAccount,
+    }
+
+    impl MarketBuilder {
+        fn new() -> Self {
+            Self {
+                market_acc: MarketAccount {
+                    bump: 0,
+                    nonce: 0,
+                    vote_state: [[0; 32]; MAX_OPTIONS],
+                    probs: [[0; 32]; MAX_OPTIONS],
+                    cost: [0; 32],
+                    total_cost_basis: [0; 32],
+                    liquidity_parameter: 10,
+                    id: 1,
+                    authority: Pubkey::default(),
+                    question: String::new(),
+                    options: vec!["Yes".to_string(), "No".to_string()],
+                    num_options: 2,
+                    probs_revealed: [0; MAX_OPTIONS],
+                    updated_at: 0,
+                    reveal: RevealConfig {
+                        policy: RevealPolicy::ProbsOnly,
+                        min_interval: MARKET_REVEAL_PROBS_TIME,
+                        reward: 0,
+                        hide_votes: false,
+                    },
+                    last_reveal_at: 0,
+                    revealed_version: 0,
+                    winning_outcome: 0,
+                    status: MarketStatus::Active,
+                    tvl: 0,
+                    mint: Pubkey::default(),
+                    mint_decimals: 6,
+                    votes_revealed: [0; MAX_OPTIONS],
+                    batch_mode: false,
+                    batch_epoch: 0,
+                    batch_epoch_start: 0,
+                    state_version: 1,
+                    trading_ends_at: TRADING_ENDS_AT,
+                    resolve_by: RESOLVE_BY,
+                    refund_pool: 0,
+                    liquidity_provider: Pubkey::default(),
+                    protocol_fee_bps: 0,
+                    creator_fee_bps: 0,
+                    lp_fee_bps: 0,
+                    protocol_fees: 0,
+                    creator_fees: 0,
+                    lp_fees: 0,
+                    reserved_liability: 0,
+                    complete_sets: 0,
+                    set_collateral: 0,
+                    unclaimed_positions: 0,
+                    open_orders: 0,
+                    open_tickets: 0,
+                    finalized_at: 0,
+                    last_claim_at: 0,
+                    resolution_source: ResolutionSource::Authority,
+                    resolvers: vec![],
+                    resolver_votes: [NO_RESOLVER_VOTE; MAX_RESOLVERS],
+                    proposer: Pubkey::default(),
+                    proposed_outcome: 0,
+                    proposed_at: 0,
+                    proposer_bond: 0,
+                    disputer: Pubkey::default(),
+                    disputer_bond: 0,
+                    ruled_outcome: NO_RESOLVER_VOTE,
+                    scalar: None,
+                    resolved_value: 0,
+                    long_payout: 0,
+                },
+            }
+        }
+
+        fn fees(mut self, protocol_fee_bps: u16, creator_fee_bps: u16, lp_fee_bps: u16) -> Self {
+            self.market_acc.protocol_fee_bps = protocol_fee_bps;
+            self.market_acc.creator_fee_bps = creator_fee_bps;
+            self.market_acc.lp_fee_bps = lp_fee_bps;
+            self
+        }
+
+        fn scalar(mut self, lower_bound: u64, upper_bound: u64) -> Self {
+            self.market_acc.scalar = Some(ScalarBounds {
+                lower_bound,
+                upper_bound,
+            });
+            self
+        }
+
+        fn build(self) -> MarketAccount {
+            self.market_acc
+        }
+    }
+
+    #[test]
+    fn trade_fee_rounds_up() {
+        let market_acc = MarketBuilder::new().fees(100, 50, 50).build();
+        assert_eq!(market_acc.trade_fee(0), 0);
+        assert_eq!(market_acc.trade_fee(1), 1);
+        assert_eq!(market_acc.trade_fee(50), 1);
+        assert_eq!(market_acc.trade_fee(51), 2);
+        assert_eq!(market_acc.trade_fee(10_000), 200);
+        assert_eq!(MarketBuilder::new().build().trade_fee(10_000), 0);
+    }
+
+    #[test]
+    fn cost_before_fee_is_the_largest_cost_that_fits() {
+        let market_acc = MarketBuilder::new().fees(100, 50, 50).build();
+        assert_eq!(market_acc.cost_before_fee(10_200), 10_000);
+        for funds in 1..20_000 {
+            let cost = market_acc.cost_before_fee(funds);
+            assert!(cost + market_acc.trade_fee(cost) <= funds);
+            assert!(cost + 1 + market_acc.trade_fee(cost + 1) > funds);
+        }
+    }
+
+    #[test]
+    fn proceeds_before_fee_is_the_smallest_amount_that_leaves_proceeds() {
+        let market_acc = MarketBuilder::new().fees(100, 50, 50).build();
+        assert_eq!(market_acc.proceeds_before_fee(9_800), 10_000);
+        for proceeds in 1..20_000 {
+            let amount = market_acc.proceeds_before_fee(proceeds);
+            assert!(amount - market_acc.trade_fee(amount) >= proceeds);
+            assert!(amount - 1 - market_acc.trade_fee(amount - 1) < proceeds);
+        }
+        assert_eq!(market_acc.proceeds_before_fee(u64::MAX), u64::MAX);
+    }
+
+    #[test]
+    fn accrue_fees_gives_rounding_leftovers_to_the_lp() {
+        let mut market_acc = MarketBuilder::new().fees(100, 50, 50).build();
+        assert_eq!(market_acc.accrue_fees(3), (1, 0, 2));
+        assert_eq!(market_acc.accrue_fees(200), (100, 50, 50));
+        assert_eq!(
+            (market_acc.protocol_fees, market_acc.creator_fees, market_acc.lp_fees),
+            (101, 50, 52)
+        );
+        assert_eq!(MarketBuilder::new().build().accrue_fees(100), (0, 0, 0));
+    }
+
+    #[test]
+    fn batches_follow_the_epochs() {
+        let mut market_acc = MarketBuilder::new().build();
+        market_acc.batch_epoch = 60;
+        market_acc.batch_epoch_start = 1_000;
+        assert_eq!(market_acc.batch_at(1_000), 0);
+        assert_eq!(market_acc.batch_at(1_059), 0);
+        assert_eq!(market_acc.batch_at(1_060), 1);
+        assert_eq!(market_acc.batch_ends_at(0), 1_060);
+        assert_eq!(market_acc.batch_ends_at(1), 1_120);
+        // A batch takes commits until the next one starts
+        for now in 1_000..1_200 {
+            let batch_id = market_acc.batch_at(now);
+            assert!(now < market_acc.batch_ends_at(batch_id));
+            assert!(now >= market_acc.batch_ends_at(batch_id) - market_acc.batch_epoch);
+        }
+    }
+
+    #[test]
+    fn is_expired_only_past_resolve_by() {
+        let mut market_acc = MarketBuilder::new().build();
+        assert!(!market_acc.is_expired(RESOLVE_BY));
+        assert!(market_acc.is_expired(RESOLVE_BY + 1));
+        market_acc.status = MarketStatus::Closed;
+        assert!(market_acc.is_expired(RESOLVE_BY + 1));
+        market_acc.status = MarketStatus::Expired;
+        assert!(market_acc.is_expired(0));
+        market_acc.status = MarketStatus::Proposed;
+        assert!(!market_acc.is_expired(RESOLVE_BY + 1));
+    }
+
+    #[test]
+    fn set_resolved_value_splits_the_payout_linearly() {
+        let mut market_acc = MarketBuilder::new().scalar(100, 200).build();
+        assert_eq!(market_acc.set_resolved_value(125).unwrap(), 1);
+        assert_eq!(market_acc.long_payout, SCALAR_PAYOUT_ONE / 4);
+        assert_eq!(market_acc.set_resolved_value(150).unwrap(), 0);
+        assert_eq!(market_acc.long_payout, SCALAR_PAYOUT_ONE / 2);
+        assert_eq!(market_acc.resolved_value, 150);
+    }
+
+    #[test]
+    fn set_resolved_value_clamps_to_the_bounds() {
+        let mut market_acc = MarketBuilder::new().scalar(100, 200).build();
+        assert_eq!(market_acc.set_resolved_value(50).unwrap(), 1);
+        assert_eq!(market_acc.long_payout, 0);
+        assert_eq!(market_acc.set_resolved_value(250).unwrap(), 0);
+        assert_eq!(market_acc.long_payout, SCALAR_PAYOUT_ONE);
+        // The raw value is kept, only the payout is clamped
+        assert_eq!(market_acc.resolved_value, 250);
+    }
+
+    #[test]
+    fn set_resolved_value_needs_a_scalar_market() {
+        let mut market_acc = MarketBuilder::new().build();
+        assert!(market_acc.set_resolved_value(150).is_err());
+    }
+
+    #[test]
+    fn settlement_locks_the_outcome_until_resolve_by() {
+        let mut market_acc = MarketBuilder::new().build();
+        market_acc.refresh_status(TRADING_ENDS_AT);
+        market_acc.begin_settlement(1);
+        assert!(market_acc.status == MarketStatus::Settling);
+        assert_eq!(market_acc.winning_outcome, 1);
+        assert!(!market_acc.can_redeem_sets());
+        // A reveal that never lands doesn't keep the market from expiring
+        market_acc.refresh_status(RESOLVE_BY + 1);
+        assert!(market_acc.status == MarketStatus::Expired);
+    }
+
+    #[test]
+    fn void_pools_the_tvl_for_refunds() {
+        let mut market_acc = MarketBuilder::new().build();
+        market_acc.tvl = 5_000_000;
+        market_acc.void(RESOLVE_BY + 1);
+        assert!(market_acc.status == MarketStatus::Voided);
+        assert_eq!(market_acc.refund_pool, 5_000_000);
+        assert_eq!(market_acc.finalized_at, RESOLVE_BY + 1);
+        assert_eq!(market_acc.state_version, 2);
+    }
+
+    #[test]
+    fn refresh_status_follows_the_timeline() {
+        let mut market_acc = MarketBuilder::new().build();
+        market_acc.refresh_status(TRADING_ENDS_AT - 1);
+        assert!(market_acc.status == MarketStatus::Active);
+        assert!(market_acc.is_trading(TRADING_ENDS_AT - 1));
+
+        market_acc.refresh_status(TRADING_ENDS_AT);
+        assert!(market_acc.status == MarketStatus::Closed);
+        assert!(!market_acc.is_trading(TRADING_ENDS_AT));
+
+        market_acc.refresh_status(RESOLVE_BY);
+        assert!(market_acc.status == MarketStatus::Closed);
+        market_acc.refresh_status(RESOLVE_BY + 1);
+        assert!(market_acc.status == MarketStatus::Expired);
+    }
+
+    #[test]
+    fn refresh_status_leaves_finalized_markets() {
+        let mut market_acc = MarketBuilder::new().build();
+        market_acc.status = MarketStatus::Settled;
+        market_acc.refresh_status(RESOLVE_BY + 1);
+        assert!(market_acc.status == MarketStatus::Settled);
+        assert!(!market_acc.is_expired(RESOLVE_BY + 1));
+    }
+}
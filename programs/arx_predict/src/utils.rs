@@ -1,16 +1,152 @@
 use anchor_lang::prelude::*;
-use crate::errors::ErrorCode;
+use crate::{
+    constants::{SHARES_PER_UNIT, TRADE_STATUS_FAILED, TRADE_STATUS_STALE, TRADE_STATUS_SUCCESS},
+    errors::ErrorCode,
+    events::{BuySharesEvent, FeesAccruedEvent, SellSharesEvent},
+    states::{MarketAccount, MarketStatus, UserAccount, UserPosition},
+};
 
-pub fn convert_f64_to_token_amount(amount_f64: f64, decimals: u8) -> Result<u64> {
-    if amount_f64 < 0.0 {
-        return Err(ErrorCode::InvalidAmount.into());
+/// 10^decimals, the number of base units in one whole token
+pub fn token_unit(decimals: u8) -> Result<u64> {
+    10u64
+        .checked_pow(decimals as u32)
+        .ok_or(ErrorCode::AmountTooLarge.into())
+}
+
+/// Token base units `shares` pay out, rounded down
+pub fn shares_to_amount(shares: u64, token_unit: u64) -> u64 {
+    (shares as u128 * token_unit as u128 / SHARES_PER_UNIT as u128) as u64
+}
+
+/// Token base units `shares` cost as complete set collateral, rounded up
+pub fn shares_to_amount_ceil(shares: u64, token_unit: u64) -> u64 {
+    (shares as u128 * token_unit as u128).div_ceil(SHARES_PER_UNIT as u128) as u64
+}
+
+/// Adds a collected fee to the market's buckets
+pub fn accrue_fees(market_acc: &mut MarketAccount, fee: u64) {
+    let (protocol, creator, lp) = market_acc.accrue_fees(fee);
+    if fee > 0 {
+        emit!(FeesAccruedEvent {
+            market_id: market_acc.id,
+            protocol,
+            creator,
+            lp,
+        });
+    }
+}
+
+/// Encrypted state and revealed values returned by the buy/sell circuits
+pub struct TradeOutput<'a> {
+    pub market_stats: &'a [[u8; 32]],
+    pub market_nonce: u128,
+    pub user_position: &'a [[u8; 32]],
+    pub user_position_nonce: u128,
+    pub amount: u64,
+    pub status: u8,
+    pub trade_seq: u64,
+    pub market_version: u64,
+}
+
+/// Refunds the escrow and returns the status to report when a trade result no longer applies
+fn reject_trade(
+    market_acc: &MarketAccount,
+    user_position_acc: &mut UserPosition,
+    user_account: &mut UserAccount,
+    output: &TradeOutput,
+    market_open: bool,
+) -> Option<u8> {
+    // Released trades already got their escrow back
+    if !user_position_acc.is_pending_trade(output.trade_seq) {
+        return Some(TRADE_STATUS_FAILED);
     }
-    
-    let multiplier = 10u64.pow(decimals as u32);
-    let rounded_amount = (amount_f64 * multiplier as f64).round();
-    if rounded_amount > u64::MAX as f64 {
-        return Err(ErrorCode::AmountTooLarge.into());
+    // The market was settled while the trade was queued
+    if !market_open {
+        user_account.credit(user_position_acc.end_trade(0));
+        return Some(TRADE_STATUS_FAILED);
     }
-    
-    Ok(rounded_amount as u64)
-}
\ No newline at end of file
+    // Another trade changed the market since this one was queued, applying it would overwrite that trade
+    if output.market_version != market_acc.state_version {
+        user_account.credit(user_position_acc.end_trade(0));
+        return Some(TRADE_STATUS_STALE);
+    }
+    None
+}
+
+/// Applies a buy circuit result. The circuit already checked the escrowed balance net of fees, a rejected
+/// trade leaves the encrypted state as it was and refunds the whole escrow
+pub fn apply_buy(
+    market_acc: &mut MarketAccount,
+    user_position_acc: &mut UserPosition,
+    user_account: &mut UserAccount,
+    output: TradeOutput,
+) -> Result<()> {
+    // Trades queued before trading_ends_at still land while the market waits for settlement
+    let market_open = matches!(market_acc.status, MarketStatus::Active | MarketStatus::Closed);
+    let (status, amount, fee) = match reject_trade(market_acc, user_position_acc, user_account, &output, market_open) {
+        Some(status) => (status, 0, 0),
+        None => {
+            let mut fee = 0;
+            if output.status == TRADE_STATUS_SUCCESS {
+                fee = market_acc.trade_fee(output.amount);
+                market_acc.set_market_stats(output.market_stats, output.market_nonce);
+                user_position_acc.set_shares(output.user_position, output.user_position_nonce);
+                market_acc.tvl += output.amount;
+                accrue_fees(market_acc, fee);
+                user_account.credit(user_position_acc.end_trade(output.amount + fee));
+            } else {
+                user_account.credit(user_position_acc.end_trade(0));
+            }
+            (output.status, output.amount, fee)
+        }
+    };
+
+    emit!(BuySharesEvent {
+        market_id: market_acc.id,
+        status,
+        timestamp: Clock::get()?.unix_timestamp,
+        amount,
+        tvl: market_acc.tvl,
+        fee,
+    });
+    Ok(())
+}
+
+/// Applies a sell circuit result, status is not success when shares were insufficient or below min_proceeds
+pub fn apply_sell(
+    market_acc: &mut MarketAccount,
+    user_position_acc: &mut UserPosition,
+    user_account: &mut UserAccount,
+    output: TradeOutput,
+) -> Result<()> {
+    let market_open = matches!(
+        market_acc.status,
+        MarketStatus::Active | MarketStatus::Closed | MarketStatus::Expired
+    );
+    let (status, amount, fee) = match reject_trade(market_acc, user_position_acc, user_account, &output, market_open) {
+        Some(status) => (status, 0, 0),
+        None => {
+            user_position_acc.end_trade(0);
+            let mut fee = 0;
+            if output.status == TRADE_STATUS_SUCCESS {
+                fee = market_acc.trade_fee(output.amount);
+                user_account.credit(output.amount - fee);
+                market_acc.set_market_stats(output.market_stats, output.market_nonce);
+                user_position_acc.set_shares(output.user_position, output.user_position_nonce);
+                market_acc.tvl -= output.amount;
+                accrue_fees(market_acc, fee);
+            }
+            (output.status, output.amount, fee)
+        }
+    };
+
+    emit!(SellSharesEvent {
+        market_id: market_acc.id,
+        status,
+        timestamp: Clock::get()?.unix_timestamp,
+        amount,
+        tvl: market_acc.tvl,
+        fee,
+    });
+    Ok(())
+}
@@ -18,22 +18,55 @@ import * as fs from "fs";
 import * as os from "os";
 import { expect } from "chai";
 
-import { createTokenMint, generateKeypairFromSeed, getRequiredATA, readKpJson } from "../client/utils";
+import { createTokenMint, generateKeypairFromSeed, getRequiredATA, readKpJson, sleepUntil, unixNow, transferSol } from "../client/utils";
 import {
   getMXEPublicKeyWithRetry,
+  initializeConfig,
+  DEFAULT_CIRCUIT_BASE_URL,
+  claimFees,
+  closeUserPosition,
+  closeMarket,
+  getMarketData,
   getProbs,
+  DEFAULT_REVEAL_CONFIG,
   createUserPosition,
   createMarket,
-  sendPayment,
+  deposit,
   // revealResult,
   buyShares,
+  buyWithAmount,
+  buySharesPrivate,
   sellShares,
-  withdrawPayment,
+  sellSharesPrivate,
+  withdraw,
   settleMarket,
+  settleScalarMarket,
+  submitResolution,
+  proposeOutcome,
+  disputeOutcome,
+  finalizeOutcome,
+  claimBond,
   claimRewards,
-  fundMarket,
+  cancelMarket,
+  claimRefund,
+  getUserAccount,
   claimMarketFunds,
   getUserPosition,
+  viewPosition,
+  valuePosition,
+  quoteTrade,
+  placeLimitOrder,
+  matchOrders,
+  settleLimitOrder,
+  mintCompleteSet,
+  redeemCompleteSet,
+  getLimitOrderAddress,
+  getCurrentBatchId,
+  openBatch,
+  commitBatchTrade,
+  clearBatch,
+  settleBatchTrade,
+  refundBatchTrade,
 } from "../client/arcium_helper";
 import {
   initUserPositionCompDef,
@@ -42,7 +75,23 @@ import {
   initMarketStatsCompDef,
   initBuySharesCompDef,
   initSellSharesCompDef,
+  initBuyWithAmountCompDef,
+  initBuySharesPrivateCompDef,
+  initSellSharesPrivateCompDef,
   initClaimRewardsCompDef,
+  initClaimRefundCompDef,
+  initViewPositionCompDef,
+  initValuePositionCompDef,
+  initQuoteTradeCompDef,
+  initPlaceLimitOrderCompDef,
+  initMatchOrdersCompDef,
+  initSettleLimitOrderCompDef,
+  initMintCompleteSetCompDef,
+  initRedeemCompleteSetCompDef,
+  initInitBatchStatsCompDef,
+  initCommitBatchTradeCompDef,
+  initClearBatchCompDef,
+  initSettleBatchTradeCompDef,
 } from "../client/init_comp_defs";
 import { randomBytes } from "crypto";
 
@@ -111,6 +160,17 @@ const formatUSDC = (amount: number) => {
   return `${(amount / 1e6).toFixed(2)} USDC`;
 };
 
+// Expects an instruction to be rejected with the given program error
+const expectProgramError = async (promise: Promise<any>, errorCode: string) => {
+  try {
+    await promise;
+  } catch (error) {
+    expect(error.error?.errorCode?.code ?? error.toString()).to.contain(errorCode);
+    return;
+  }
+  expect.fail(`Expected ${errorCode}`);
+};
+
 // Helper function to format timestamp
 const formatTimestamp = (timestamp: any) => {
   try {
@@ -278,6 +338,23 @@ describe("Voting", () => {
 
   it("can vote on polls!", async () => {
     const POLL_IDS = [420];
+    const PRICE_FEED_MARKET_ID = 421;
+    const OPTIMISTIC_MARKET_ID = 422;
+    const OPTIMISTIC_BOND = 1_000_000;
+    const DISPUTE_WINDOW = 10;
+    const SCALAR_MARKET_ID = 423;
+    const CANCELLED_MARKET_ID = 424;
+    const MULTISIG_MARKET_ID = 425;
+    const DISPUTED_MARKET_ID = 426;
+    const BATCH_MARKET_ID = 427;
+    const BATCH_EPOCH = 60;
+    // The trading markets reveal volume rounded to whole shares and pay whoever cranks the reveal
+    const REVEAL_BUCKET = 1_000_000;
+    const REVEAL_REWARD = 1_000;
+    // The first optimistic market keeps its vote totals encrypted, settlement only reveals what its No shares are owed
+    const HIDDEN_VOTE_SHARES = 5_000_000;
+    // Mock Pyth price account loaded by the test validator, SOL at $150 published in 2100, long after any settlement window
+    const PRICE_FEED = new anchor.web3.PublicKey("2et1icpTE9vM3NwhTxbAo5wEsebD9EwNyBraxTPv458k");
     const owner = readKpJson(`${os.homedir()}/.config/solana/id.json`);
 
     // Set up global event listener to catch any unexpected events
@@ -313,6 +390,13 @@ describe("Voting", () => {
       owner,
       100000 * 1e6
     )));
+    // The voters sign their own trades and claims
+    await Promise.all(voters.map(voter => transferSol(
+      provider as anchor.AnchorProvider,
+      owner,
+      voter.publicKey,
+      1 * 1e9
+    )));
     logSuccess(`Voter ATAs, Funded`);
 
     logStep("Fetching MXE public key");
@@ -322,6 +406,20 @@ describe("Voting", () => {
     );
     logSuccess(`MXE x25519 pubkey: ${mxePublicKey.toString()}`);
 
+    logStep("Initializing protocol config");
+    await initializeConfig(program, owner, {
+      admins: [owner.publicKey],
+      allowedMints: [mint],
+      circuitBaseUrl: DEFAULT_CIRCUIT_BASE_URL,
+      checkAdmins: true,
+      checkMints: true,
+      offchainCircuits: true,
+      protocolFeeBps: 50,
+      creatorFeeBps: 50,
+      lpFeeBps: 100,
+    });
+    logSuccess("Config initialized");
+
     logSection("Computation Definitions Initialization");
     logInfo("Initializing all required computation definitions...");
     
@@ -367,6 +465,24 @@ describe("Voting", () => {
       false
     );
     logSuccess("Sell shares computation definition initialized");
+
+    await initBuyWithAmountCompDef(
+      provider as anchor.AnchorProvider,
+      program,
+      owner,
+      false
+    );
+    logSuccess("Buy with amount computation definition initialized");
+
+    for (const initPrivateTradeCompDef of [initBuySharesPrivateCompDef, initSellSharesPrivateCompDef]) {
+      await initPrivateTradeCompDef(
+        provider as anchor.AnchorProvider,
+        program,
+        owner,
+        false
+      );
+    }
+    logSuccess("Private size trade computation definitions initialized");
     
     const initClaimRewardsSig = await initClaimRewardsCompDef(
       provider as anchor.AnchorProvider,
@@ -376,6 +492,77 @@ describe("Voting", () => {
     );
     logSuccess("Claim rewards computation definition initialized");
 
+    await initClaimRefundCompDef(
+      provider as anchor.AnchorProvider,
+      program,
+      owner,
+      false
+    );
+    logSuccess("Claim refund computation definition initialized");
+
+    await initViewPositionCompDef(
+      provider as anchor.AnchorProvider,
+      program,
+      owner,
+      false
+    );
+    logSuccess("View position computation definition initialized");
+
+    await initValuePositionCompDef(
+      provider as anchor.AnchorProvider,
+      program,
+      owner,
+      false
+    );
+    logSuccess("Value position computation definition initialized");
+
+    await initQuoteTradeCompDef(
+      provider as anchor.AnchorProvider,
+      program,
+      owner,
+      false
+    );
+    logSuccess("Quote trade computation definition initialized");
+
+    for (const initLimitOrderCompDef of [
+      initPlaceLimitOrderCompDef,
+      initMatchOrdersCompDef,
+      initSettleLimitOrderCompDef,
+    ]) {
+      await initLimitOrderCompDef(
+        provider as anchor.AnchorProvider,
+        program,
+        owner,
+        false
+      );
+    }
+    logSuccess("Limit order computation definitions initialized");
+
+    for (const initCompleteSetCompDef of [initMintCompleteSetCompDef, initRedeemCompleteSetCompDef]) {
+      await initCompleteSetCompDef(
+        provider as anchor.AnchorProvider,
+        program,
+        owner,
+        false
+      );
+    }
+    logSuccess("Complete set computation definitions initialized");
+
+    for (const initBatchCompDef of [
+      initInitBatchStatsCompDef,
+      initCommitBatchTradeCompDef,
+      initClearBatchCompDef,
+      initSettleBatchTradeCompDef,
+    ]) {
+      await initBatchCompDef(
+        provider as anchor.AnchorProvider,
+        program,
+        owner,
+        false
+      );
+    }
+    logSuccess("Batch computation definitions initialized");
+
     logSection("Market Creation");
     const privateKey = x25519.utils.randomSecretKey();
     const publicKey = x25519.getPublicKey(privateKey);
@@ -389,6 +576,11 @@ describe("Voting", () => {
     logInfo(`   Options: ${options.join(', ')}`);
     logInfo(`   Liquidity parameter: ${liquidityParameter}`);
     
+    // Markets must be closed before they can be settled, keep the window just long enough for the trades below
+    const tradingEndsAt = unixNow() + 420;
+    const resolveBy = tradingEndsAt + 3600;
+    logInfo(`   Trading ends at: ${new Date(tradingEndsAt * 1000).toISOString()}`);
+
     // Listen for InitMarketStatsEvent during market creation
     const marketCreationListener = await listenForEvents("Market Creation", ["initMarketStatsEvent"]);
     
@@ -401,8 +593,8 @@ describe("Voting", () => {
 
       const fundingAmount = liquidityParameter * Math.log(options.length) * 1e6;
       logStep(`Funding market ${POLL_ID}, amount: ${fundingAmount} `);
-      await fundMarket(program, owner, ata, mint, POLL_ID, fundingAmount);
-      logSuccess(`Market ${POLL_ID} funded successfully`);
+      await deposit(program, owner, ata, mint, fundingAmount);
+      logSuccess(`Funding for market ${POLL_ID} deposited successfully`);
 
       logStep(`Creating market ${POLL_ID}`);
       await createMarket(
@@ -413,7 +605,19 @@ describe("Voting", () => {
         question,
         options,
         liquidityParameter,
-        mint
+        mint,
+        tradingEndsAt,
+        resolveBy,
+        0,
+        { authority: {} },
+        [],
+        null,
+        {
+          policy: { bucketedVolume: { bucket: new anchor.BN(REVEAL_BUCKET) } },
+          minInterval: new anchor.BN(60),
+          reward: new anchor.BN(REVEAL_REWARD),
+          hideVotes: false,
+        }
       );
       
       // Wait a moment for the event to be processed
@@ -421,6 +625,109 @@ describe("Voting", () => {
       
       logSuccess(`Market ${POLL_ID} created successfully`);
     }
+
+    logStep(`Creating price feed market ${PRICE_FEED_MARKET_ID}`, `Resolves to Yes at or above $100`);
+    globalEventListener.markExpected("initMarketStatsEvent", PRICE_FEED_MARKET_ID);
+    await deposit(program, owner, ata, mint, liquidityParameter * Math.log(options.length) * 1e6);
+    await createMarket(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      PRICE_FEED_MARKET_ID,
+      `$SOL above 100?`,
+      options,
+      liquidityParameter,
+      mint,
+      tradingEndsAt,
+      resolveBy,
+      0,
+      { priceFeed: { feed: PRICE_FEED, threshold: new anchor.BN(100_00000000), expo: -8 } }
+    );
+    logSuccess(`Market ${PRICE_FEED_MARKET_ID} created successfully`);
+
+    logStep(`Creating optimistic market ${OPTIMISTIC_MARKET_ID}`, `Disputes go to the config authority`);
+    globalEventListener.markExpected("initMarketStatsEvent", OPTIMISTIC_MARKET_ID);
+    await deposit(program, owner, ata, mint, liquidityParameter * Math.log(options.length) * 1e6);
+    await createMarket(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      OPTIMISTIC_MARKET_ID,
+      `$SOL ETF?`,
+      options,
+      liquidityParameter,
+      mint,
+      tradingEndsAt,
+      resolveBy,
+      0,
+      { optimistic: { bond: new anchor.BN(OPTIMISTIC_BOND), disputeWindow: new anchor.BN(DISPUTE_WINDOW), threshold: 0 } },
+      [],
+      null,
+      { ...DEFAULT_REVEAL_CONFIG, hideVotes: true }
+    );
+    logSuccess(`Market ${OPTIMISTIC_MARKET_ID} created successfully`);
+
+    logStep(`Creating optimistic market ${DISPUTED_MARKET_ID} to dispute`, `Without resolvers the config authority rules`);
+    globalEventListener.markExpected("initMarketStatsEvent", DISPUTED_MARKET_ID);
+    await deposit(program, owner, ata, mint, liquidityParameter * Math.log(options.length) * 1e6);
+    await createMarket(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      DISPUTED_MARKET_ID,
+      `$SOL ETF staking?`,
+      options,
+      liquidityParameter,
+      mint,
+      tradingEndsAt,
+      resolveBy,
+      0,
+      { optimistic: { bond: new anchor.BN(OPTIMISTIC_BOND), disputeWindow: new anchor.BN(DISPUTE_WINDOW), threshold: 0 } }
+    );
+    logSuccess(`Market ${DISPUTED_MARKET_ID} created successfully`);
+
+    logStep(`Creating scalar market ${SCALAR_MARKET_ID}`, `LONG pays linearly between $0 and $200`);
+    globalEventListener.markExpected("initMarketStatsEvent", SCALAR_MARKET_ID);
+    await deposit(program, owner, ata, mint, liquidityParameter * Math.log(options.length) * 1e6);
+    await createMarket(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      SCALAR_MARKET_ID,
+      `$SOL price?`,
+      ["Long", "Short"],
+      liquidityParameter,
+      mint,
+      tradingEndsAt,
+      resolveBy,
+      0,
+      { authority: {} },
+      [],
+      { lowerBound: new anchor.BN(0), upperBound: new anchor.BN(200) }
+    );
+    logSuccess(`Market ${SCALAR_MARKET_ID} created successfully`);
+
+    logStep(`Creating multisig market ${MULTISIG_MARKET_ID}`, `Two of the owner and the voters settle it`);
+    globalEventListener.markExpected("initMarketStatsEvent", MULTISIG_MARKET_ID);
+    await deposit(program, owner, ata, mint, liquidityParameter * Math.log(options.length) * 1e6);
+    await createMarket(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      MULTISIG_MARKET_ID,
+      `$SOL flips $BTC?`,
+      options,
+      liquidityParameter,
+      mint,
+      tradingEndsAt,
+      resolveBy,
+      0,
+      { multisig: { threshold: 2 } },
+      [owner.publicKey, ...voters.map(voter => voter.publicKey)]
+    );
+    // Resolvers can only vote once trading has closed
+    await expectProgramError(submitResolution(program, owner, MULTISIG_MARKET_ID, 1), "TradingNotClosed");
+    logSuccess(`Market ${MULTISIG_MARKET_ID} created successfully`);
     
     // Clean up market creation listener
     marketCreationListener.cleanup();
@@ -442,20 +749,16 @@ describe("Voting", () => {
     }
 
     logSection("Payment Processing");
-    logStep(`Sending payments to ${POLL_IDS.length} market(s)`);
-    for (let i = 0; i < POLL_IDS.length; i++) {
-      const POLL_ID = POLL_IDS[i];
-      const paymentAmount = 100 * 1e6;
-      logProgress(i + 1, POLL_IDS.length, `Sending payment to market ${POLL_ID}`);
-      
-      await sendPayment(program, owner, ata, mint, POLL_ID, paymentAmount);
-      logSuccess(`Payment sent to market ${POLL_ID}`);
-      logInfo(`   Amount: ${formatUSDC(paymentAmount)}`);
-    }
+    // One deposit funds the trades in every market
+    const paymentAmount = 100 * 1e6 * POLL_IDS.length;
+    logStep(`Depositing for ${POLL_IDS.length} market(s)`);
+    await deposit(program, owner, ata, mint, paymentAmount);
+    logSuccess("Deposit sent");
+    logInfo(`   Amount: ${formatUSDC(paymentAmount)}`);
 
-    logSection("User Position Balance");
-    logStep(`Getting user position balance for market ${POLL_IDS[0]}`);
-    await getUserPosition(program, owner, POLL_IDS[0]);
+    logSection("User Account Balance");
+    logStep("Getting user account balance");
+    await getUserAccount(program, owner, mint);
 
     logSection("Voting Process");
     const voteOutcomes = [0, 1, 0]; // Different outcomes for each poll
@@ -464,6 +767,7 @@ describe("Voting", () => {
     for (let i = 0; i < POLL_IDS.length; i++) {
       const POLL_ID = POLL_IDS[i];
       const sharesToBuy = 100 * 1000000;
+      const maxCost = 100 * 1e6; // slippage limit, the whole deposited balance
       logProgress(i + 1, POLL_IDS.length, `Voting on market ${POLL_ID}`);
       
       // Wait for BuySharesEvent during voting
@@ -480,12 +784,38 @@ describe("Voting", () => {
         POLL_ID,
         0,
         sharesToBuy,
+        maxCost,
         buySharesEventPromise
       );
       logSuccess(`Vote cast for market ${POLL_ID}`);
       logInfo(`   Shares bought: ${sharesToBuy} shares`);
       logInfo(`   Vote choice: Option 0 (${options[0]})`);
     }
+
+    logStep(`Buying No on optimistic market ${OPTIMISTIC_MARKET_ID}`, `Its vote totals stay encrypted`);
+    await deposit(program, owner, ata, mint, 10 * 1e6);
+    await createUserPosition(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      OPTIMISTIC_MARKET_ID,
+      owner
+    );
+    globalEventListener.markExpected("buySharesEvent", OPTIMISTIC_MARKET_ID);
+    await buyShares(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      cipher,
+      publicKey,
+      owner,
+      OPTIMISTIC_MARKET_ID,
+      1,
+      HIDDEN_VOTE_SHARES,
+      10 * 1e6,
+      waitForEvent("buySharesEvent")
+    );
+    logSuccess(`Bought ${HIDDEN_VOTE_SHARES} No shares`);
     
     logSection("Share Trading");
     logStep(`Selling shares for ${POLL_IDS.length} market(s)`);
@@ -508,12 +838,265 @@ describe("Voting", () => {
         POLL_ID,
         0,
         sharesToSell,
+        0, // no min proceeds
         sellSharesEventPromise
       );
       logSuccess(`Shares sold for market ${POLL_ID}`);
       logInfo(`   Shares sold: ${sharesToSell} shares`);
       logInfo(`   Vote choice: Option 0 (${options[0]})`);
     }
+
+    logStep(`Viewing the position in market ${POLL_IDS[0]}`);
+    globalEventListener.markExpected("positionViewEvent", POLL_IDS[0]);
+    const position = await viewPosition(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      cipher,
+      publicKey,
+      owner,
+      POLL_IDS[0],
+      awaitEvent("positionViewEvent")
+    );
+    // 100 shares bought, 1 sold
+    expect(position.shares[0]).to.equal(BigInt(99 * 1000000));
+    expect(position.shares[1]).to.equal(BigInt(0));
+    logSuccess(`Position decrypted: ${position.shares[0]} shares of ${options[0]}`);
+
+    logStep(`Marking the position in market ${POLL_IDS[0]} to market`);
+    globalEventListener.markExpected("positionValueEvent", POLL_IDS[0]);
+    const positionValue = await valuePosition(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      cipher,
+      publicKey,
+      owner,
+      POLL_IDS[0],
+      awaitEvent("positionValueEvent")
+    );
+    // The cost basis is what's left of the buy after the sale's proceeds came off it
+    expect(positionValue.costBasis).to.equal(position.costBasis[0]);
+    expect(positionValue.value > BigInt(0)).to.be.true;
+    logSuccess(`Position value: ${formatUSDC(Number(positionValue.value))}, PnL: ${formatUSDC(Number(positionValue.pnl))}`);
+
+    logStep(`Quoting a trade in market ${POLL_IDS[0]}`);
+    globalEventListener.markExpected("tradeQuoteEvent", POLL_IDS[0]);
+    const quote = await quoteTrade(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      cipher,
+      publicKey,
+      owner,
+      POLL_IDS[0],
+      0,
+      10 * 1e6,
+      awaitEvent("tradeQuoteEvent")
+    );
+    // Selling the same shares back can never return more than buying them costs
+    expect(quote.buyCost > BigInt(0)).to.be.true;
+    expect(quote.sellProceeds > BigInt(0)).to.be.true;
+    expect(quote.sellProceeds < quote.buyCost).to.be.true;
+    logSuccess(`Quote: buy ${formatUSDC(Number(quote.buyCost))}, sell ${formatUSDC(Number(quote.sellProceeds))}`);
+
+    logStep(`Buying ${options[1]} with a fixed budget in market ${POLL_IDS[0]}`, `The circuit works out how many shares it affords`);
+    const budget = 5 * 1e6;
+    await deposit(program, owner, ata, mint, budget);
+    const balanceBeforeBudgetBuy = (await getUserAccount(program, owner, mint)).balance.toNumber();
+    globalEventListener.markExpected("buySharesEvent", POLL_IDS[0]);
+    const budgetBuyEvent = await buyWithAmount(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      cipher,
+      publicKey,
+      owner,
+      POLL_IDS[0],
+      1,
+      budget,
+      waitForEvent("buySharesEvent")
+    );
+    expect(budgetBuyEvent.status).to.equal(1);
+    // The fee is paid on top of the cost, both come out of the budget
+    expect(budgetBuyEvent.amount.toNumber()).to.be.greaterThan(0);
+    expect(budgetBuyEvent.amount.toNumber() + budgetBuyEvent.fee.toNumber()).to.be.at.most(budget);
+    // The unspent part of the escrow is back in the user account
+    const budgetBuyPosition = await getUserPosition(program, owner, POLL_IDS[0]);
+    expect(budgetBuyPosition.pending).to.be.false;
+    expect(budgetBuyPosition.lockedAmount.toNumber()).to.equal(0);
+    const balanceAfterBudgetBuy = (await getUserAccount(program, owner, mint)).balance.toNumber();
+    expect(balanceAfterBudgetBuy).to.equal(
+      balanceBeforeBudgetBuy - budgetBuyEvent.amount.toNumber() - budgetBuyEvent.fee.toNumber()
+    );
+    globalEventListener.markExpected("positionViewEvent", POLL_IDS[0]);
+    const budgetPosition = await viewPosition(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      cipher,
+      publicKey,
+      owner,
+      POLL_IDS[0],
+      awaitEvent("positionViewEvent")
+    );
+    expect(budgetPosition.shares[0]).to.equal(position.shares[0]);
+    expect(budgetPosition.shares[1] > BigInt(0)).to.be.true;
+    logSuccess(`Bought ${budgetPosition.shares[1]} ${options[1]} shares for ${formatUSDC(budgetBuyEvent.amount.toNumber())}`);
+
+    logStep(`Buying and selling a private size in market ${POLL_IDS[0]}`, `Only the payments are revealed`);
+    const privateShares = 2 * 1e6;
+    await deposit(program, owner, ata, mint, budget);
+    const balanceBeforePrivateBuy = (await getUserAccount(program, owner, mint)).balance.toNumber();
+    const privateBuyEvent = await buySharesPrivate(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      cipher,
+      publicKey,
+      owner,
+      POLL_IDS[0],
+      1,
+      privateShares,
+      budget,
+      waitForEvent("buySharesEvent")
+    );
+    expect(privateBuyEvent.status).to.equal(1);
+    expect(privateBuyEvent.amount.toNumber()).to.be.greaterThan(0);
+    expect(privateBuyEvent.amount.toNumber() + privateBuyEvent.fee.toNumber()).to.be.at.most(budget);
+    const balanceAfterPrivateBuy = (await getUserAccount(program, owner, mint)).balance.toNumber();
+    expect(balanceAfterPrivateBuy).to.equal(
+      balanceBeforePrivateBuy - privateBuyEvent.amount.toNumber() - privateBuyEvent.fee.toNumber()
+    );
+    globalEventListener.markExpected("sellSharesEvent", POLL_IDS[0]);
+    const privateSellEvent = await sellSharesPrivate(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      cipher,
+      publicKey,
+      owner,
+      POLL_IDS[0],
+      1,
+      privateShares,
+      0, // no min proceeds
+      waitForEvent("sellSharesEvent")
+    );
+    expect(privateSellEvent.status).to.equal(1);
+    // Selling the same shares straight back returns less than they cost
+    expect(privateSellEvent.amount.toNumber()).to.be.greaterThan(0);
+    expect(privateSellEvent.amount.toNumber()).to.be.lessThan(privateBuyEvent.amount.toNumber());
+    const balanceAfterPrivateSell = (await getUserAccount(program, owner, mint)).balance.toNumber();
+    expect(balanceAfterPrivateSell).to.equal(
+      balanceAfterPrivateBuy + privateSellEvent.amount.toNumber() - privateSellEvent.fee.toNumber()
+    );
+    globalEventListener.markExpected("positionViewEvent", POLL_IDS[0]);
+    const privatePosition = await viewPosition(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      cipher,
+      publicKey,
+      owner,
+      POLL_IDS[0],
+      awaitEvent("positionViewEvent")
+    );
+    expect(privatePosition.shares[1]).to.equal(budgetPosition.shares[1]);
+    logSuccess(`Private buy of ${formatUSDC(privateBuyEvent.amount.toNumber())} sold back for ${formatUSDC(privateSellEvent.amount.toNumber())}`);
+
+    logStep(`Placing and matching a limit order in market ${POLL_IDS[0]}`);
+    const orderId = 0;
+    const orderEscrow = 10 * 1e6;
+    const balanceBeforeOrder = (await getUserAccount(program, owner, mint)).balance.toNumber();
+    await placeLimitOrder(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      cipher,
+      publicKey,
+      owner,
+      POLL_IDS[0],
+      orderId,
+      0,
+      5 * 1e6,
+      1, // any price fills
+      orderEscrow
+    );
+    globalEventListener.markExpected("ordersMatchedEvent", POLL_IDS[0]);
+    const ordersMatchedEvent = await matchOrders(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      owner,
+      POLL_IDS[0],
+      [getLimitOrderAddress(program.programId, POLL_IDS[0], owner.publicKey, orderId)],
+      awaitEvent("ordersMatchedEvent")
+    );
+    expect(ordersMatchedEvent.status).to.equal(1);
+    expect(ordersMatchedEvent.amount.toNumber()).to.be.greaterThan(0);
+
+    globalEventListener.markExpected("limitOrderSettledEvent", POLL_IDS[0]);
+    const limitOrderSettledEvent = await settleLimitOrder(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      owner,
+      POLL_IDS[0],
+      orderId,
+      awaitEvent("limitOrderSettledEvent")
+    );
+    // The order alone filled, so its cost is the whole matched amount and the rest of the escrow comes back
+    expect(limitOrderSettledEvent.cost.toNumber()).to.equal(ordersMatchedEvent.amount.toNumber());
+    const balanceAfterOrder = (await getUserAccount(program, owner, mint)).balance.toNumber();
+    expect(balanceAfterOrder).to.equal(
+      balanceBeforeOrder - limitOrderSettledEvent.cost.toNumber() - limitOrderSettledEvent.fee.toNumber()
+    );
+    logSuccess(`Limit order filled for ${formatUSDC(limitOrderSettledEvent.cost.toNumber())}`);
+
+    logStep(`Minting and redeeming a complete set on market ${POLL_IDS[0]}`, `Neither trades against the market maker`);
+    const completeSetShares = 2 * 1e6;
+    const balanceBeforeSet = (await getUserAccount(program, owner, mint)).balance.toNumber();
+    const completeSetMintedEvent = await mintCompleteSet(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      owner,
+      POLL_IDS[0],
+      completeSetShares,
+      awaitEvent("completeSetMintedEvent")
+    );
+    // One token per share of every outcome, with no fee
+    expect(completeSetMintedEvent.status).to.equal(1);
+    expect(completeSetMintedEvent.amount.toNumber()).to.equal(completeSetShares);
+    expect((await getUserPosition(program, owner, POLL_IDS[0])).mintedSets.toNumber()).to.equal(completeSetShares);
+    // Only minted sets redeem, and only as many as were minted
+    await expectProgramError(
+      redeemCompleteSet(
+        provider as anchor.AnchorProvider,
+        program,
+        arciumEnv.arciumClusterPubkey,
+        owner,
+        POLL_IDS[0],
+        completeSetShares + 1e6,
+        Promise.resolve(null)
+      ),
+      "InsufficientSets"
+    );
+    const completeSetRedeemedEvent = await redeemCompleteSet(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      owner,
+      POLL_IDS[0],
+      completeSetShares,
+      awaitEvent("completeSetRedeemedEvent")
+    );
+    expect(completeSetRedeemedEvent.status).to.equal(1);
+    expect(completeSetRedeemedEvent.amount.toNumber()).to.equal(completeSetShares);
+    expect((await getUserPosition(program, owner, POLL_IDS[0])).mintedSets.toNumber()).to.equal(0);
+    const balanceAfterSet = (await getUserAccount(program, owner, mint)).balance.toNumber();
+    expect(balanceAfterSet).to.equal(balanceBeforeSet);
+    logSuccess(`Complete set of ${completeSetShares} shares minted and redeemed at par`);
     
     logStep(`Revealing final probabilities after trading for ${POLL_IDS.length} market(s)`);
     for (let i = 0; i < POLL_IDS.length; i++) {
@@ -532,12 +1115,214 @@ describe("Voting", () => {
         revealProbsEventPromise
       );
       logSuccess(`Final probabilities revealed for market ${POLL_ID}`);
+      // Only bucketed volume leaves the MPC, the reward is capped by the creator fees accrued so far
+      for (const votes of probs.votes) {
+        expect(votes.toNumber() % REVEAL_BUCKET).to.equal(0);
+      }
+      expect(probs.reward.toNumber()).to.be.at.most(REVEAL_REWARD);
       logInfo(`   Probabilities: ${formatProbability(probs.share0, probs.share1)}`);
     }
 
+    logSection("Batch Trading");
+    logStep(`Creating batch market ${BATCH_MARKET_ID}`, `Commits clear together every ${BATCH_EPOCH} seconds`);
+    globalEventListener.markExpected("initMarketStatsEvent", BATCH_MARKET_ID);
+    await deposit(program, owner, ata, mint, liquidityParameter * Math.log(options.length) * 1e6);
+    await createMarket(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      BATCH_MARKET_ID,
+      `$SOL to 300?`,
+      options,
+      liquidityParameter,
+      mint,
+      tradingEndsAt,
+      resolveBy,
+      BATCH_EPOCH
+    );
+    await createUserPosition(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      BATCH_MARKET_ID,
+      owner
+    );
+    const batchMarket = await getMarketData(program, BATCH_MARKET_ID);
+    const batchEndsAt = (batchId: number) => batchMarket.batchEpochStart.toNumber() + (batchId + 1) * BATCH_EPOCH;
+
+    logStep(`Committing to a batch of market ${BATCH_MARKET_ID}`);
+    // Start on a fresh epoch so the batch is still taking commits once it's open
+    await sleepUntil(batchEndsAt(await getCurrentBatchId(program, BATCH_MARKET_ID)), 1);
+    const batchId = await getCurrentBatchId(program, BATCH_MARKET_ID);
+    // Only the current epoch's batch can be opened
+    await expectProgramError(
+      openBatch(provider as anchor.AnchorProvider, program, arciumEnv.arciumClusterPubkey, owner, BATCH_MARKET_ID, batchId + 1),
+      "BatchNotOpen"
+    );
+    await openBatch(provider as anchor.AnchorProvider, program, arciumEnv.arciumClusterPubkey, owner, BATCH_MARKET_ID, batchId);
+    const batchCommitAmount = 5 * 1e6;
+    await deposit(program, owner, ata, mint, 2 * batchCommitAmount);
+    const balanceBeforeCommit = (await getUserAccount(program, owner, mint)).balance.toNumber();
+    await commitBatchTrade(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      cipher,
+      publicKey,
+      owner,
+      BATCH_MARKET_ID,
+      batchId,
+      0,
+      batchCommitAmount
+    );
+    // The whole commit, fee included, is spent when the batch clears
+    expect((await getUserAccount(program, owner, mint)).balance.toNumber()).to.equal(
+      balanceBeforeCommit - batchCommitAmount
+    );
+    await expectProgramError(
+      clearBatch(provider as anchor.AnchorProvider, program, arciumEnv.arciumClusterPubkey, owner, BATCH_MARKET_ID, batchId),
+      "BatchEpochOpen"
+    );
+    logSuccess(`Committed ${formatUSDC(batchCommitAmount)} to batch ${batchId}`);
+
+    logStep(`Clearing batch ${batchId} and settling its ticket`);
+    await sleepUntil(batchEndsAt(batchId), 1);
+    const batchClearedEventPromise = awaitEvent("batchClearedEvent");
+    await clearBatch(provider as anchor.AnchorProvider, program, arciumEnv.arciumClusterPubkey, owner, BATCH_MARKET_ID, batchId);
+    const batchClearedEvent = await batchClearedEventPromise;
+    expect(batchClearedEvent.status).to.equal(1);
+    expect(batchClearedEvent.numTrades).to.equal(1);
+    expect(batchClearedEvent.amount.toNumber()).to.be.greaterThan(0);
+    const batchTradeSettledEventPromise = awaitEvent("batchTradeSettledEvent");
+    await settleBatchTrade(provider as anchor.AnchorProvider, program, arciumEnv.arciumClusterPubkey, owner, BATCH_MARKET_ID, batchId);
+    const batchTradeSettledEvent = await batchTradeSettledEventPromise;
+    expect(batchTradeSettledEvent.refunded).to.be.false;
+    globalEventListener.markExpected("positionViewEvent", BATCH_MARKET_ID);
+    const batchPosition = await viewPosition(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      cipher,
+      publicKey,
+      owner,
+      BATCH_MARKET_ID,
+      awaitEvent("positionViewEvent")
+    );
+    expect(batchPosition.shares[0] > BigInt(0)).to.be.true;
+    expect(batchPosition.shares[1]).to.equal(BigInt(0));
+    logSuccess(`Batch ${batchId} cleared for ${formatUSDC(batchClearedEvent.amount.toNumber())}, the ticket got ${batchPosition.shares[0]} shares`);
+
+    logStep(`Committing to a batch of market ${BATCH_MARKET_ID} that is left open`, `It is refunded once the market settles`);
+    const openBatchId = await getCurrentBatchId(program, BATCH_MARKET_ID);
+    await openBatch(provider as anchor.AnchorProvider, program, arciumEnv.arciumClusterPubkey, owner, BATCH_MARKET_ID, openBatchId);
+    await commitBatchTrade(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      cipher,
+      publicKey,
+      owner,
+      BATCH_MARKET_ID,
+      openBatchId,
+      1,
+      batchCommitAmount
+    );
+    // An open batch is only refunded once the market can't clear it anymore
+    await expectProgramError(refundBatchTrade(program, owner, BATCH_MARKET_ID, openBatchId), "BatchNotFailed");
+    logSuccess(`Committed ${formatUSDC(batchCommitAmount)} to batch ${openBatchId}`);
+
+    logSection("Market Cancellation & Refunds");
+    logStep(`Creating market ${CANCELLED_MARKET_ID} to cancel`, `Every position gets its cost basis back`);
+    globalEventListener.markExpected("initMarketStatsEvent", CANCELLED_MARKET_ID);
+    await deposit(program, owner, ata, mint, liquidityParameter * Math.log(options.length) * 1e6);
+    await createMarket(
+      provider as anchor.AnchorProvider,
+      program,
+      arciumEnv.arciumClusterPubkey,
+      CANCELLED_MARKET_ID,
+      `$SOL flips $ETH?`,
+      options,
+      liquidityParameter,
+      mint,
+      tradingEndsAt,
+      resolveBy
+    );
+    const fundedTvl = (await getMarketData(program, CANCELLED_MARKET_ID)).tvl.toNumber();
+
+    // The owner buys Yes and the first voter buys No
+    const refundTraders = [owner, voters[0]];
+    const refundTraderAtas = [ata, voterAtas[0]];
+    const refundCosts: number[] = [];
+    globalEventListener.markExpected("buySharesEvent", CANCELLED_MARKET_ID);
+    for (let i = 0; i < refundTraders.length; i++) {
+      await deposit(program, refundTraders[i], refundTraderAtas[i], mint, 10 * 1e6);
+      await createUserPosition(
+        provider as anchor.AnchorProvider,
+        program,
+        arciumEnv.arciumClusterPubkey,
+        CANCELLED_MARKET_ID,
+        refundTraders[i]
+      );
+      const refundBuyEvent = await buyShares(
+        provider as anchor.AnchorProvider,
+        program,
+        arciumEnv.arciumClusterPubkey,
+        cipher,
+        publicKey,
+        refundTraders[i],
+        CANCELLED_MARKET_ID,
+        i,
+        (i + 2) * 1e6,
+        10 * 1e6,
+        waitForEvent("buySharesEvent")
+      );
+      expect(refundBuyEvent.status).to.equal(1);
+      refundCosts.push(refundBuyEvent.amount.toNumber());
+    }
+
+    logStep(`Cancelling market ${CANCELLED_MARKET_ID} and claiming refunds`);
+    await cancelMarket(program, owner, CANCELLED_MARKET_ID);
+    const cancelledMarket = await getMarketData(program, CANCELLED_MARKET_ID);
+    expect(cancelledMarket.status).to.deep.equal({ voided: {} });
+    expect(cancelledMarket.refundPool.toNumber()).to.equal(fundedTvl + refundCosts[0] + refundCosts[1]);
+    for (let i = 0; i < refundTraders.length; i++) {
+      const balanceBeforeRefund = (await getUserAccount(program, refundTraders[i], mint)).balance.toNumber();
+      const claimRefundEvent = await claimRefund(
+        provider as anchor.AnchorProvider,
+        program,
+        arciumEnv.arciumClusterPubkey,
+        refundTraders[i],
+        CANCELLED_MARKET_ID,
+        awaitEvent("claimRefundEvent")
+      );
+      // The pool covers every cost basis, so each trader gets back exactly what their shares cost, fees excluded
+      expect(claimRefundEvent.amount.toNumber()).to.equal(refundCosts[i]);
+      const balanceAfterRefund = (await getUserAccount(program, refundTraders[i], mint)).balance.toNumber();
+      expect(balanceAfterRefund).to.equal(balanceBeforeRefund + refundCosts[i]);
+    }
+    // Only the creator's funding is left in the vault for the market
+    const refundedMarket = await getMarketData(program, CANCELLED_MARKET_ID);
+    expect(refundedMarket.tvl.toNumber()).to.equal(fundedTvl);
+    expect(refundedMarket.unclaimedPositions).to.equal(0);
+    await expectProgramError(
+      claimRefund(
+        provider as anchor.AnchorProvider,
+        program,
+        arciumEnv.arciumClusterPubkey,
+        owner,
+        CANCELLED_MARKET_ID,
+        Promise.resolve(null)
+      ),
+      "AlreadyClaimed"
+    );
+    logSuccess(`Refunded ${formatUSDC(refundCosts[0] + refundCosts[1])}, the funding stayed in the market`);
+
     logSection("Market Settlement & Rewards");
     logStep("Settling markets and claiming rewards");
     
+    logInfo("Waiting for trading to close");
+    await sleepUntil(tradingEndsAt);
+
     logInfo("Settling first market");
     await settleMarket(
       provider as anchor.AnchorProvider,
@@ -548,6 +1333,146 @@ describe("Voting", () => {
       awaitEvent("marketSettledEvent")
     );
     logSuccess("Market settled successfully");
+
+    logInfo("Settling price feed market from the mock price account");
+    // Anyone can settle, so a price published long after trading closed can't be picked
+    await expectProgramError(
+      settleMarket(
+        provider as anchor.AnchorProvider,
+        program,
+        0, // ignored, the price feed picks the winner
+        PRICE_FEED_MARKET_ID,
+        arciumEnv.arciumClusterPubkey,
+        Promise.resolve(null),
+        PRICE_FEED
+      ),
+      "SettlementWindowClosed"
+    );
+    logSuccess("Price feed market refused a price from after the settlement window");
+
+    logInfo("Proposing No for the optimistic market");
+    await deposit(program, owner, ata, mint, OPTIMISTIC_BOND);
+    const balanceBeforeProposal = (await getUserAccount(program, owner, mint)).balance.toNumber();
+    await proposeOutcome(program, owner, OPTIMISTIC_MARKET_ID, 1);
+    await sleepUntil(unixNow() + DISPUTE_WINDOW + 1);
+    const optimisticSettledEvent = await finalizeOutcome(
+      provider as anchor.AnchorProvider,
+      program,
+      OPTIMISTIC_MARKET_ID,
+      arciumEnv.arciumClusterPubkey,
+      awaitEvent("marketSettledEvent")
+    );
+    expect(optimisticSettledEvent.winningOutcome).to.equal(1);
+    // The vote totals stayed encrypted while trading and are revealed with the liability the No shares will claim
+    expect(optimisticSettledEvent.votes[0].toNumber()).to.equal(0);
+    expect(optimisticSettledEvent.votes[1].toNumber()).to.equal(HIDDEN_VOTE_SHARES);
+    expect(optimisticSettledEvent.liability.toNumber()).to.equal(HIDDEN_VOTE_SHARES);
+    await claimBond(program, owner, OPTIMISTIC_MARKET_ID);
+    const balanceAfterBond = (await getUserAccount(program, owner, mint)).balance.toNumber();
+    expect(balanceAfterBond).to.equal(balanceBeforeProposal);
+    logSuccess("Undisputed proposal settled the market and its bond was returned");
+
+    logInfo("Disputing a Yes proposal on the second optimistic market");
+    const disputer = voters[0];
+    await deposit(program, owner, ata, mint, OPTIMISTIC_BOND);
+    await deposit(program, disputer, voterAtas[0], mint, OPTIMISTIC_BOND);
+    const proposerBalanceBefore = (await getUserAccount(program, owner, mint)).balance.toNumber();
+    const disputerBalanceBefore = (await getUserAccount(program, disputer, mint)).balance.toNumber();
+    await proposeOutcome(program, owner, DISPUTED_MARKET_ID, 0);
+    await disputeOutcome(program, disputer, DISPUTED_MARKET_ID);
+    const disputedMarket = await getMarketData(program, DISPUTED_MARKET_ID);
+    expect(disputedMarket.status).to.deep.equal({ disputed: {} });
+    expect(disputedMarket.disputerBond.toNumber()).to.equal(OPTIMISTIC_BOND);
+    expect((await getUserAccount(program, disputer, mint)).balance.toNumber()).to.equal(
+      disputerBalanceBefore - OPTIMISTIC_BOND
+    );
+    // A disputed market waits for the ruling, only the config authority gives it
+    await expectProgramError(
+      finalizeOutcome(
+        provider as anchor.AnchorProvider,
+        program,
+        DISPUTED_MARKET_ID,
+        arciumEnv.arciumClusterPubkey,
+        Promise.resolve(null)
+      ),
+      "ResolutionPending"
+    );
+    await expectProgramError(submitResolution(program, voters[1], DISPUTED_MARKET_ID, 1), "InvalidAuthority");
+    await submitResolution(program, owner, DISPUTED_MARKET_ID, 1);
+    const disputedSettledEvent = await finalizeOutcome(
+      provider as anchor.AnchorProvider,
+      program,
+      DISPUTED_MARKET_ID,
+      arciumEnv.arciumClusterPubkey,
+      awaitEvent("marketSettledEvent")
+    );
+    expect(disputedSettledEvent.winningOutcome).to.equal(1);
+    // The proposal was overturned, so the proposer's bond is slashed and the disputer takes both
+    await expectProgramError(claimBond(program, owner, DISPUTED_MARKET_ID), "NoBondToClaim");
+    expect((await getUserAccount(program, owner, mint)).balance.toNumber()).to.equal(
+      proposerBalanceBefore - OPTIMISTIC_BOND
+    );
+    await claimBond(program, disputer, DISPUTED_MARKET_ID);
+    expect((await getUserAccount(program, disputer, mint)).balance.toNumber()).to.equal(
+      disputerBalanceBefore + OPTIMISTIC_BOND
+    );
+    const ruledMarket = await getMarketData(program, DISPUTED_MARKET_ID);
+    expect(ruledMarket.proposerBond.toNumber()).to.equal(0);
+    expect(ruledMarket.disputerBond.toNumber()).to.equal(0);
+    await expectProgramError(claimBond(program, disputer, DISPUTED_MARKET_ID), "NoBondToClaim");
+    logSuccess("The ruling overturned the proposal and the disputer took both bonds");
+
+    logInfo("Settling scalar market at $150");
+    await settleScalarMarket(
+      provider as anchor.AnchorProvider,
+      program,
+      150,
+      SCALAR_MARKET_ID,
+      arciumEnv.arciumClusterPubkey,
+      awaitEvent("marketSettledEvent")
+    );
+    const scalarMarket = await getMarketData(program, SCALAR_MARKET_ID);
+    expect(scalarMarket.resolvedValue.toNumber()).to.equal(150);
+    // A LONG share pays 0.75 tokens, FIXED_ONE scaled
+    expect(scalarMarket.longPayout.toString()).to.equal("750000000000");
+    logSuccess("Scalar market settled, LONG pays 75%");
+
+    logInfo("Settling multisig market once two resolvers agree");
+    const settleMultisigMarket = (eventPromise: Promise<any>) => settleMarket(
+      provider as anchor.AnchorProvider,
+      program,
+      0, // ignored, the resolvers pick the winner
+      MULTISIG_MARKET_ID,
+      arciumEnv.arciumClusterPubkey,
+      eventPromise
+    );
+    await expectProgramError(settleMultisigMarket(Promise.resolve(null)), "ResolutionPending");
+    await submitResolution(program, owner, MULTISIG_MARKET_ID, 1);
+    await submitResolution(program, voters[0], MULTISIG_MARKET_ID, 0);
+    // One vote each way is short of the threshold
+    await expectProgramError(settleMultisigMarket(Promise.resolve(null)), "ResolutionPending");
+    await submitResolution(program, voters[1], MULTISIG_MARKET_ID, 1);
+    const multisigSettledEvent = await settleMultisigMarket(awaitEvent("marketSettledEvent"));
+    expect(multisigSettledEvent.winningOutcome).to.equal(1);
+    logSuccess("Multisig market settled to No");
+
+    logInfo("Refunding the batch left open on the batch market");
+    await settleMarket(
+      provider as anchor.AnchorProvider,
+      program,
+      0,
+      BATCH_MARKET_ID,
+      arciumEnv.arciumClusterPubkey,
+      awaitEvent("marketSettledEvent")
+    );
+    const balanceBeforeBatchRefund = (await getUserAccount(program, owner, mint)).balance.toNumber();
+    await refundBatchTrade(program, owner, BATCH_MARKET_ID, openBatchId);
+    // The fee taken at commit comes back with the budget
+    expect((await getUserAccount(program, owner, mint)).balance.toNumber()).to.equal(
+      balanceBeforeBatchRefund + batchCommitAmount
+    );
+    await expectProgramError(refundBatchTrade(program, owner, BATCH_MARKET_ID, openBatchId), "TicketSettled");
+    logSuccess(`Batch ${openBatchId} refunded after the market settled`);
     
     logInfo("Claiming rewards for first market");
     
@@ -569,21 +1494,16 @@ describe("Voting", () => {
     logSuccess("Rewards claimed successfully");
     logInfo(`   Reward amount: ${formatUSDC(claimRewardsEvent.amount.toNumber())}`);
 
-    logSection("User Position Balance");
-    logStep(`Getting user position balance for market ${POLL_IDS[0]}`);
-    const userPositionAfterRewards = await getUserPosition(program, owner, POLL_IDS[0]);
+    logSection("User Account Balance");
+    logStep("Getting user account balance");
+    const userAccountAfterRewards = await getUserAccount(program, owner, mint);
 
     logSection("Payment Withdrawal");
-    logStep(`Withdrawing payments from ${POLL_IDS.length} market(s)`);
-    for (let i = 0; i < POLL_IDS.length; i++) {
-      const POLL_ID = POLL_IDS[i];
-      const withdrawalAmount = userPositionAfterRewards.balance.toNumber();
-      logProgress(i + 1, POLL_IDS.length, `Withdrawing from market ${POLL_ID}`);
-      
-      await withdrawPayment(program, owner, ata, mint, POLL_ID, withdrawalAmount);
-      logSuccess(`Payment withdrawn from market ${POLL_ID}`);
-      logInfo(`   Amount: ${formatUSDC(withdrawalAmount)}`);
-    }
+    logStep("Withdrawing the user account balance");
+    const withdrawalAmount = userAccountAfterRewards.balance.toNumber();
+    await withdraw(program, owner, ata, mint, withdrawalAmount);
+    logSuccess("Payment withdrawn");
+    logInfo(`   Amount: ${formatUSDC(withdrawalAmount)}`);
 
     logSection("Claim Market Funds");
     logStep(`Claiming market funds for ${POLL_IDS.length} market(s)`);
@@ -595,6 +1515,44 @@ describe("Voting", () => {
     logInfo(`   Amount: ${formatUSDC(claimMarketFundsEvent.amount.toNumber())}`);
     logSuccess(`Market funds claimed for market ${POLL_IDS[0]}`);
 
+    logStep("Claiming trading fees");
+    const marketAfterTrading = await getMarketData(program, POLL_IDS[0]);
+    logInfo(`   Protocol: ${formatUSDC(marketAfterTrading.protocolFees.toNumber())}, creator: ${formatUSDC(marketAfterTrading.creatorFees.toNumber())}, lp: ${formatUSDC(marketAfterTrading.lpFees.toNumber())}`);
+    // The owner is the config authority, the market creator and its liquidity provider
+    for (const bucket of [{ protocol: {} }, { creator: {} }, { lp: {} }]) {
+      await claimFees(program, owner, ata, mint, POLL_IDS[0], bucket);
+    }
+    logSuccess(`Fees claimed for market ${POLL_IDS[0]}`);
+
+    logSection("Close Accounts");
+    logStep(`Closing the claimed position and market ${POLL_IDS[0]}`);
+    await closeUserPosition(program, owner, POLL_IDS[0]);
+    await closeMarket(program, owner, POLL_IDS[0]);
+    const closedMarket = await program.account.marketAccount.fetchNullable(
+      anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("market"), new anchor.BN(POLL_IDS[0]).toArrayLike(Buffer, "le", 4)],
+        program.programId
+      )[0]
+    );
+    expect(closedMarket).to.be.null;
+    // Positions of the closed market would count in a new market created under its id
+    await expectProgramError(
+      createMarket(
+        provider as anchor.AnchorProvider,
+        program,
+        arciumEnv.arciumClusterPubkey,
+        POLL_IDS[0],
+        question,
+        options,
+        liquidityParameter,
+        mint,
+        unixNow() + 420,
+        unixNow() + 4020
+      ),
+      "MarketIdClosed"
+    );
+    logSuccess(`Position and market ${POLL_IDS[0]} closed, its id can't be reused`);
+
     logSection("Test Completion");
     logSuccess("All voting operations completed successfully! 🎉");
     
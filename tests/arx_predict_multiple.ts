@@ -18,20 +18,22 @@ import * as fs from "fs";
 import * as os from "os";
 import { expect } from "chai";
 
-import { createTokenMint, generateKeypairFromSeed, getRequiredATA, readKpJson, transferSol } from "../client/utils";
+import { createTokenMint, generateKeypairFromSeed, getRequiredATA, readKpJson, sleepUntil, unixNow, transferSol } from "../client/utils";
 import {
   getMXEPublicKeyWithRetry,
+  initializeConfig,
+  DEFAULT_CIRCUIT_BASE_URL,
   getProbs,
   createUserPosition,
   createMarket,
-  sendPayment,
+  deposit,
   // revealResult,
   buyShares,
   sellShares,
-  withdrawPayment,
+  withdraw,
   settleMarket,
   claimRewards,
-  fundMarket,
+  getUserAccount,
   claimMarketFunds,
   getUserPosition,
 } from "../client/arcium_helper";
@@ -330,6 +332,20 @@ describe("Voting", () => {
     );
     logSuccess(`MXE x25519 pubkey: ${mxePublicKey.toString()}`);
 
+    logStep("Initializing protocol config");
+    await initializeConfig(program, owner, {
+      admins: [owner.publicKey],
+      allowedMints: [mint],
+      circuitBaseUrl: DEFAULT_CIRCUIT_BASE_URL,
+      checkAdmins: true,
+      checkMints: true,
+      offchainCircuits: true,
+      protocolFeeBps: 50,
+      creatorFeeBps: 50,
+      lpFeeBps: 100,
+    });
+    logSuccess("Config initialized");
+
     logSection("Computation Definitions Initialization");
     logInfo("Initializing all required computation definitions...");
     
@@ -397,6 +413,11 @@ describe("Voting", () => {
     logInfo(`   Options: ${options.join(', ')}`);
     logInfo(`   Liquidity parameter: ${liquidityParameter}`);
     
+    // Markets must be closed before they can be settled, keep the window just long enough for the trades below
+    const tradingEndsAt = unixNow() + 300;
+    const resolveBy = tradingEndsAt + 3600;
+    logInfo(`   Trading ends at: ${new Date(tradingEndsAt * 1000).toISOString()}`);
+
     // Listen for InitMarketStatsEvent during market creation
     const marketCreationListener = await listenForEvents("Market Creation", ["initMarketStatsEvent"]);
     
@@ -409,8 +430,8 @@ describe("Voting", () => {
 
       const fundingAmount = liquidityParameter * Math.log(options.length) * 1e6;
       logStep(`Funding market ${POLL_ID}, amount: ${fundingAmount} `);
-      await fundMarket(program, owner, ata, mint, POLL_ID, fundingAmount);
-      logSuccess(`Market ${POLL_ID} funded successfully`);
+      await deposit(program, owner, ata, mint, fundingAmount);
+      logSuccess(`Funding for market ${POLL_ID} deposited successfully`);
 
       logStep(`Creating market ${POLL_ID}`);
       await createMarket(
@@ -421,7 +442,9 @@ describe("Voting", () => {
         question,
         options,
         liquidityParameter,
-        mint
+        mint,
+        tradingEndsAt,
+        resolveBy
       );
       
       // Wait a moment for the event to be processed
@@ -459,25 +482,23 @@ describe("Voting", () => {
 
 
     logSection("Payment Processing");
-    logStep(`Sending payments to ${POLL_IDS.length} market(s)`);
+    logStep(`Depositing for ${voters.length} voter(s)`);
     // Send all payments in parallel for efficiency
     const paymentPromises = voters.map(async (voter, index) => {
-      const POLL_ID = POLL_IDS[0];
       const paymentAmount = 100 * 1e6;
-      logProgress(index + 1, voters.length, `Sending payment to market ${POLL_ID}`);
+      logProgress(index + 1, voters.length, `Depositing for voter ${index}`);
       
-      const result = await sendPayment(program, voter, voterAtas[index], mint, POLL_ID, paymentAmount);
-      logSuccess(`Payment sent to market ${POLL_ID}`);
+      const result = await deposit(program, voter, voterAtas[index], mint, paymentAmount);
+      logSuccess(`Deposit sent for voter ${index}`);
       logInfo(`   Amount: ${formatUSDC(paymentAmount)}`);
       return result;
     });
     
     await Promise.all(paymentPromises);
 
-    // logSection("User Position Balance");
-    // logStep(`Getting user position balance for market ${POLL_IDS[0]}`);
-    // const userPositionBalances = await Promise.all(voters.map(voter => getUserPosition(program, voter, POLL_IDS[0])));
-    // logSuccess(`User position balance for market ${POLL_IDS[0]}: ${userPositionBalances.map(balance => balance.balance.toNumber()).join(", ")}`);
+    // logSection("User Account Balance");
+    // const userAccountBalances = await Promise.all(voters.map(voter => getUserAccount(program, voter, mint)));
+    // logSuccess(`User account balances: ${userAccountBalances.map(account => account.balance.toNumber()).join(", ")}`);
 
     logSection("Voting Process");    
     logStep(`Casting votes (buying shares) for ${POLL_IDS.length} market(s)`);
@@ -503,6 +524,7 @@ describe("Voting", () => {
         POLL_ID,
         buyAmounts[i][0],
         buyAmounts[i][1],
+        Number.MAX_SAFE_INTEGER, // no slippage limit
         buySharesEventPromise
       );
       logSuccess(`Vote cast for market ${POLL_ID}`);
@@ -561,6 +583,9 @@ describe("Voting", () => {
     logSection("Market Settlement & Rewards");
     logStep("Settling markets and claiming rewards");
     
+    logInfo("Waiting for trading to close");
+    await sleepUntil(tradingEndsAt);
+
     logInfo("Settling first market");
     await settleMarket(
       provider as anchor.AnchorProvider,
@@ -591,19 +616,17 @@ describe("Voting", () => {
         logInfo(`   Reward amount: ${formatUSDC(claimRewardsEvent.amount.toNumber())}`);
     }
 
-    logSection("User Position Balance");
-    logStep(`Getting user position balance for market ${POLL_IDS[0]}`);
-    const userPositionAfterRewards = await Promise.all(voters.map(voter => getUserPosition(program, voter, POLL_IDS[0])));
+    logSection("User Account Balance");
+    logStep("Getting user account balances");
+    const userAccountAfterRewards = await Promise.all(voters.map(voter => getUserAccount(program, voter, mint)));
 
     logSection("Payment Withdrawal");
-    logStep(`Withdrawing payments from ${POLL_IDS.length} market(s)`);
+    logStep(`Withdrawing payments for ${voters.length} voter(s)`);
     for (let i = 0; i < voters.length; i++) {
-      const POLL_ID = POLL_IDS[0];
-      const withdrawalAmount = userPositionAfterRewards[i].balance.toNumber();
-      //logProgress(i + 1, POLL_IDS.length, `Withdrawing from market ${POLL_ID}`);
+      const withdrawalAmount = userAccountAfterRewards[i].balance.toNumber();
       
-      await withdrawPayment(program, voters[i], voterAtas[i], mint, POLL_ID, withdrawalAmount);
-      logSuccess(`Payment withdrawn from market ${POLL_ID}`);
+      await withdraw(program, voters[i], voterAtas[i], mint, withdrawalAmount);
+      logSuccess(`Payment withdrawn for voter ${i}`);
       logInfo(`   Amount: ${formatUSDC(withdrawalAmount)}`);
     }
 